@@ -38,6 +38,14 @@ fn main() {
     config.field_attribute("spice21.MosPorts.s", "#[serde(default)]");
     config.field_attribute("spice21.MosPorts.b", "#[serde(default)]");
     config.field_attribute("spice21.MosPorts.d", "#[serde(default)]");
+    config.field_attribute("spice21.Capacitor.esr", "#[serde(default)]");
+    config.field_attribute("spice21.Inductor.rser", "#[serde(default)]");
+    config.field_attribute("spice21.Resistor.tol", "#[serde(default)]");
+    config.field_attribute("spice21.Resistor.dist", "#[serde(default)]");
+    config.field_attribute("spice21.DeviceInstance.params", "#[serde(default)]");
+    config.field_attribute("spice21.Corner.shifts", "#[serde(default)]");
+    config.field_attribute("spice21.Vccs.poly", "#[serde(default)]");
+    config.field_attribute("spice21.Vccs.gm", "#[serde(default)]");
 
     // Nicen up our repeated and enum fields
     config.type_attribute("spice21.Instance.comp", "#[serde(tag = \"type\")]");
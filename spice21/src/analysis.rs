@@ -5,12 +5,16 @@ use num::{Complex, Float, Zero};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ops::Index;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::circuit::{Ckt, NodeRef};
+use crate::circuit::{Ckt, Comp, NodeRef};
 use crate::comps::{Component, ComponentSolver};
 use crate::defs;
+use crate::elab::Variation;
 use crate::sparse21::{Eindex, Matrix};
-use crate::{sperror, SpNum, SpResult};
+use crate::{sperror, ConvergenceError, SpError, SpNum, SpResult};
 
 ///
 /// # Matrix Stamps
@@ -24,13 +28,18 @@ pub(crate) struct Stamps<NumT> {
     pub(crate) g: Vec<(Option<Eindex>, NumT)>,
     pub(crate) b: Vec<(Option<VarIndex>, NumT)>,
 }
-impl<NumT: SpNum> Stamps<NumT> {
-    pub fn new() -> Stamps<NumT> {
-        Stamps { g: vec![], b: vec![] }
-    }
+
+/// Noise Source
+/// Uncorrelated noise current between variables `p` and `n`, with power spectral density `psd` in A²/Hz.
+/// Returned by each Component's `load_noise`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NoiseSource {
+    pub(crate) p: Option<VarIndex>,
+    pub(crate) n: Option<VarIndex>,
+    pub(crate) psd: f64,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub(crate) enum VarKind {
     V = 0,
     I,
@@ -40,11 +49,48 @@ pub(crate) enum VarKind {
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]
 pub struct VarIndex(pub usize);
 
-#[derive(Debug, Deserialize, Serialize)]
+///
+/// # Variable Layout
+///
+/// The name and kind of each solver variable, in index order.
+/// Captured from an elaborated circuit via `FlatCkt::layout`, and passed to `FlatCkt::with_layout`
+/// to re-elaborate an edited circuit while keeping the indices of its unchanged variables.
+///
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct VarLayout {
+    names: Vec<String>,
+    kinds: Vec<VarKind>,
+}
+impl VarLayout {
+    /// Index of the variable named `name`, preferring node-voltages over currents.
+    pub fn index(&self, name: &str) -> Option<VarIndex> {
+        let pos = self.names.iter().zip(self.kinds.iter()).position(|(n, k)| n == name && *k == VarKind::V);
+        pos.or_else(|| self.names.iter().position(|n| n == name)).map(VarIndex)
+    }
+    /// Number of variables
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+    /// Boolean indication of whether the layout has no variables
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub(crate) struct Variables<NumT> {
     kinds: Vec<VarKind>,
     values: Vec<NumT>,
     names: Vec<String>,
+    /// Slots reserved from a prior `VarLayout`, not (yet) claimed by `add`
+    #[serde(skip)]
+    pending: Vec<bool>,
+    /// Hierarchical path-names of module ports, e.g. `x1.out`, and the variable each connects to
+    #[serde(skip)]
+    aliases: HashMap<String, VarIndex>,
+    /// Index of the first variable of each (name, kind), including reserved slots
+    #[serde(skip)]
+    index: HashMap<(String, VarKind), VarIndex>,
 }
 impl<NumT: SpNum> Variables<NumT> {
     pub fn new() -> Self {
@@ -52,7 +98,30 @@ impl<NumT: SpNum> Variables<NumT> {
             kinds: vec![],
             values: vec![],
             names: vec![],
+            pending: vec![],
+            aliases: HashMap::new(),
+            index: HashMap::new(),
+        }
+    }
+    /// Create Variables with slots reserved for each entry in `layout`.
+    /// Each is claimed by a later `add` of the same name and kind, at its prior index.
+    pub(crate) fn with_layout(layout: &VarLayout) -> Self {
+        Variables {
+            kinds: layout.kinds.clone(),
+            names: layout.names.clone(),
+            values: vec![NumT::zero(); layout.names.len()],
+            pending: vec![true; layout.names.len()],
+            aliases: HashMap::new(),
+            index: Self::index_of(&layout.names, &layout.kinds),
+        }
+    }
+    /// Index of the first of each (name, kind) pair in `names` and `kinds`
+    fn index_of(names: &[String], kinds: &[VarKind]) -> HashMap<(String, VarKind), VarIndex> {
+        let mut index = HashMap::new();
+        for (i, (name, kind)) in names.iter().zip(kinds.iter()).enumerate() {
+            index.entry((name.clone(), *kind)).or_insert(VarIndex(i));
         }
+        index
     }
     /// Convert Variables<OtherT> to Variables<NumT>
     /// Keeps all `kinds`, while resetting all values to zero.
@@ -61,14 +130,28 @@ impl<NumT: SpNum> Variables<NumT> {
             kinds: other.kinds,
             names: other.names,
             values: vec![NumT::zero(); other.values.len()],
+            pending: other.pending,
+            aliases: other.aliases,
+            index: other.index,
         }
     }
     /// Add a new Variable with attributes `name` and `kind`.
+    /// Claims a matching reserved slot, if one exists, and otherwise appends.
     pub fn add(&mut self, name: String, kind: VarKind) -> VarIndex {
         // FIXME: check if present
+        let key = (name, kind);
+        if let Some(&VarIndex(i)) = self.index.get(&key) {
+            if self.is_pending(i) {
+                self.pending[i] = false;
+                return VarIndex(i);
+            }
+        }
+        let (name, kind) = key.clone();
+        self.index.entry(key).or_insert(VarIndex(self.kinds.len()));
         self.kinds.push(kind);
         self.names.push(name);
         self.values.push(NumT::zero());
+        self.pending.push(false);
         return VarIndex(self.kinds.len() - 1);
     }
     /// Add a new Voltage Variable with name `name`.
@@ -80,35 +163,68 @@ impl<NumT: SpNum> Variables<NumT> {
         self.add(name, VarKind::I)
     }
     /// Find a variable named `name`. Returns `VarIndex` if found, `None` if not present.
+    /// Names of the form `I(x)` refer only to the current-variable named `x`.
+    /// Others prefer a same-named voltage-variable, if present, then any other kind, then any module-port alias.
     pub fn find<S: Into<String>>(&self, name: S) -> Option<VarIndex> {
-        let n = name.into().clone();
-        match self.names.iter().position(|x| *x == n) {
-            Some(i) => Some(VarIndex(i)),
-            None => None,
+        let n = name.into();
+        if let Some(iname) = n.strip_prefix("I(").and_then(|s| s.strip_suffix(')')) {
+            return self.find_kind(iname, VarKind::I);
+        }
+        match self.find_kind(&n, VarKind::V) {
+            Some(i) => Some(i),
+            None => [VarKind::I, VarKind::Q]
+                .iter()
+                .filter_map(|k| self.find_kind(&n, *k))
+                .min_by_key(|i| i.0)
+                .or_else(|| self.aliases.get(&n).cloned()),
+        }
+    }
+    /// Alias hierarchical module-port path `path` to Variable `var`
+    pub(crate) fn alias(&mut self, path: String, var: VarIndex) {
+        self.aliases.insert(path, var);
+    }
+    /// Find a variable named `name` and of kind `kind`
+    fn find_kind(&self, name: &str, kind: VarKind) -> Option<VarIndex> {
+        match self.index.get(&(name.to_string(), kind)) {
+            Some(i) if !self.is_pending(i.0) => Some(*i),
+            _ => None,
+        }
+    }
+    /// Boolean indication of whether slot `i` is reserved but unclaimed
+    fn is_pending(&self, i: usize) -> bool {
+        self.pending.get(i).copied().unwrap_or(false)
+    }
+    /// Claim and return each reserved slot not claimed by any `add`
+    pub(crate) fn take_pending(&mut self) -> Vec<VarIndex> {
+        let rv = (0..self.pending.len()).filter(|i| self.pending[*i]).map(VarIndex).collect();
+        self.pending.iter_mut().for_each(|p| *p = false);
+        rv
+    }
+    /// Snapshot of the names and kinds of each Variable, in index order
+    pub(crate) fn layout(&self) -> VarLayout {
+        VarLayout {
+            names: self.names.clone(),
+            kinds: self.kinds.clone(),
         }
     }
     /// Retrieve the Variable corresponding to Node `node`,
     /// creating it if necessary.
     pub fn find_or_create(&mut self, node: NodeRef) -> Option<VarIndex> {
-        match node {
-            NodeRef::Gnd => None,
-            NodeRef::Name(name) => {
-                // FIXME: shouldn't have to clone all the names here
-                match self.names.iter().cloned().position(|x| x == name) {
-                    Some(i) => Some(VarIndex(i)),
-                    None => Some(self.add(name.clone(), VarKind::V)),
-                }
-            }
-            NodeRef::Num(num) => {
-                let name = num.to_string();
-                // FIXME: shouldn't have to clone all the names here
-                match self.names.iter().cloned().position(|x| x == name) {
-                    Some(i) => Some(VarIndex(i)),
-                    None => Some(self.add(name.clone(), VarKind::V)),
-                }
-            }
+        let name = match node {
+            NodeRef::Gnd => return None,
+            NodeRef::Name(name) => name,
+            NodeRef::Num(num) => num.to_string(),
+        };
+        match self.find_node(&name) {
+            Some(i) => Some(i),
+            None => Some(self.add(name, VarKind::V)),
         }
     }
+    /// Find the Variable of the node named `name`.
+    /// Matches voltage-variables and module-port aliases, never same-named currents.
+    fn find_node(&self, name: &str) -> Option<VarIndex> {
+        self.find_kind(name, VarKind::V).or_else(|| self.aliases.get(name).cloned())
+    }
     /// Retrieve a Variable value.
     /// `None` represents "ground" and always has value zero.
     pub fn get(&self, i: Option<VarIndex>) -> NumT {
@@ -117,6 +233,15 @@ impl<NumT: SpNum> Variables<NumT> {
             Some(ii) => self.values[ii.0],
         }
     }
+    /// Set the value of Variable `i`
+    #[cfg(test)]
+    pub(crate) fn set(&mut self, i: VarIndex, val: NumT) {
+        self.values[i.0] = val;
+    }
+    /// Name of Variable `i`
+    pub(crate) fn name(&self, i: VarIndex) -> &str {
+        &self.names[i.0]
+    }
     pub fn len(&self) -> usize {
         self.kinds.len()
     }
@@ -133,22 +258,118 @@ struct Iteration<NumT: SpNum> {
     itol: Vec<bool>,
 }
 
+/// # Simulation Timing Profile
+///
+/// Time spent in each phase of simulation, plus the number of Newton iterations.
+/// Collected only when `Options.profile` is set.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Profile {
+    /// Hierarchical elaboration
+    pub elaborate: Duration,
+    /// Matrix symbolic setup, i.e. creating its elements
+    pub setup: Duration,
+    /// Component loading, i.e. stamp assembly
+    pub load: Duration,
+    /// Matrix factorization and solution
+    pub solve: Duration,
+    /// Total Newton iterations
+    pub iters: usize,
+}
+
 /// Newton-Style Iterative Solver
 /// Owns each of its circuit's ComponentSolvers,
 /// its SparseMatrix, and Variables.
 pub(crate) struct Solver<'a, NumT: SpNum> {
     pub(crate) comps: Vec<ComponentSolver<'a>>,
+    /// Instance-names of each elaborated entry in `comps`
+    pub(crate) names: Vec<String>,
     pub(crate) vars: Variables<NumT>,
     pub(crate) mat: Matrix<NumT>,
     pub(crate) rhs: Vec<NumT>,
     pub(crate) history: Vec<Vec<NumT>>,
     pub(crate) defs: defs::Defs,
     pub(crate) opts: Options,
+    pub(crate) profile: Option<Profile>,
+    /// Toleranced components, for Monte Carlo analysis
+    pub(crate) variations: Vec<Variation>,
+    /// Per-iteration observer, e.g. for `dcop_iters`
+    pub(crate) on_iter: Option<IterObserver<'a>>,
+    /// Failure during setup, e.g. selecting an undefined corner. Reported by each `solve`.
+    pub(crate) setup_error: Option<SpError>,
+    /// Total Newton iterations, across all solves, e.g. each stage of gmin and source stepping
+    pub(crate) iters: usize,
+    /// Whether the latest transient solve was rejected for a component switching state
+    pub(crate) switched: bool,
+}
+/// Newton Iteration Snapshot, passed to `dcop_iters` callbacks
+#[derive(Debug)]
+pub struct NewtonIter<'a> {
+    /// Iteration number
+    pub k: usize,
+    /// Present guess
+    pub x: &'a [f64],
+    /// Residual `b - G*x`, from which the update `dx` solves `G*dx = res`
+    pub res: &'a [f64],
+    /// Assembled (dense, row-major) matrix `G`, if requested
+    pub mat: Option<&'a [Vec<f64>]>,
+    /// Assembled right-hand-side `b`, if requested
+    pub rhs: Option<&'a [f64]>,
+}
+/// Callback on each Newton iteration, and whether it receives the assembled matrix
+pub(crate) struct IterObserver<'a> {
+    pub(crate) matrix: bool,
+    pub(crate) cb: Box<dyn FnMut(&NewtonIter) + 'a>,
 }
 
 /// Real-valued Solver specifics
 /// FIXME: nearly all of this *should* eventually be share-able with the Complex Solver
 impl Solver<'_, f64> {
+    /// Set initial node-voltage guesses, per our `InitStrategy`
+    fn initialize(&mut self) -> SpResult<()> {
+        match &self.opts.init {
+            InitStrategy::Zero => (),
+            InitStrategy::RailSplit => {
+                // Propagate known node-voltages outward from ground, through each voltage source
+                let mut known: Vec<Option<f64>> = vec![None; self.vars.len()];
+                let val = |known: &Vec<Option<f64>>, x: Option<VarIndex>| match x {
+                    None => Some(0.0),
+                    Some(i) => known[i.0],
+                };
+                loop {
+                    let mut changed = false;
+                    for c in self.comps.iter() {
+                        if let ComponentSolver::Vsrc(v) = c {
+                            match (val(&known, v.p), val(&known, v.n), v.p, v.n) {
+                                (None, Some(vn), Some(p), _) => known[p.0] = Some(vn + v.v),
+                                (Some(vp), None, _, Some(n)) => known[n.0] = Some(vp - v.v),
+                                _ => continue,
+                            }
+                            changed = true;
+                        }
+                    }
+                    if !changed {
+                        break;
+                    }
+                }
+                // Source-driven nodes start at their values, and all others half-way to the highest rail
+                let vmax = known.iter().flatten().cloned().fold(0.0, f64::max);
+                for (k, kind) in self.vars.kinds.iter().enumerate() {
+                    if let VarKind::V = kind {
+                        self.vars.values[k] = known[k].unwrap_or(vmax / 2.0);
+                    }
+                }
+            }
+            InitStrategy::Map(map) => {
+                for (name, val) in map.iter() {
+                    match self.vars.find(name) {
+                        Some(v) => self.vars.values[v.0] = *val,
+                        None => return Err(sperror(format!("Initial-Guess Node Not Found: {}", name))),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
     /// Collect and incorporate updates from all components
     fn update(&mut self, an: &AnalysisInfo) {
         for comp in self.comps.iter_mut() {
@@ -166,36 +387,94 @@ impl Solver<'_, f64> {
             }
         }
     }
-    fn solve(&mut self, an: &AnalysisInfo) -> SpResult<Vec<f64>> {
+    pub(crate) fn solve(&mut self, an: &AnalysisInfo) -> SpResult<Vec<f64>> {
+        if let Some(ref e) = self.setup_error {
+            return Err(e.clone());
+        }
         self.history = vec![]; // Reset our guess-history
+        self.switched = false;
         let mut dx = vec![0.0; self.vars.len()];
+        let mut last_res: Vec<f64> = vec![];
 
-        for _k in 0..100 {
+        for k in 0..100 {
             // FIXME: number of iterations
             // Make a copy of state for tracking
             self.history.push(self.vars.values.clone());
+            self.iters += 1;
             // Reset our matrix and RHS vector
             self.mat.reset();
             self.rhs = vec![0.0; self.vars.len()];
 
             // Load up component updates
+            let t0 = self.tic();
             self.update(an);
+            self.toc(t0, |p, d| {
+                p.load += d;
+                p.iters += 1;
+            });
 
-            // Calculate the residual error
-            let res: Vec<f64> = self.mat.res(&self.vars.values, &self.rhs)?;
+            // Calculate the residual error. Failure here indicates variables absent from the matrix.
+            let (res, scale) = match (self.mat.res(&self.vars.values, &self.rhs), self.mat.res_scale(&self.vars.values, &self.rhs)) {
+                (Ok(res), Ok(scale)) => (res, scale),
+                _ => return Err(self.convergence_error(an, k + 1, &[], &dx, true)),
+            };
+            // Report to any observer, copying out the matrix only if requested
+            if let Some(obs) = self.on_iter.as_mut() {
+                let mat = if obs.matrix { Some(self.mat.to_dense()) } else { None };
+                let iter = NewtonIter {
+                    k,
+                    x: &self.vars.values,
+                    res: &res,
+                    mat: mat.as_deref(),
+                    rhs: if obs.matrix { Some(&self.rhs) } else { None },
+                };
+                (obs.cb)(&iter);
+            }
 
             // Check convergence
-            if self.converged(&dx, &res) {
-                // Converged. Commit component states
-                for c in self.comps.iter_mut() {
-                    c.commit();
+            if self.converged(k, &dx, &res, &scale) {
+                // Reject transient steps in which any component switched state, while they can still be cut
+                if let AnalysisInfo::TRAN(opts, state) = an {
+                    if state.dt / 2.0 >= opts.min_step() && self.comps.iter().any(|c| c.switched()) {
+                        self.switched = true;
+                        return Err(sperror("Component Switched State"));
+                    }
+                }
+                // Converged. Commit component states, except for transient steps, which `Tran` commits upon acceptance.
+                if !matches!(an, AnalysisInfo::TRAN(..)) {
+                    self.commit();
                 }
                 return Ok(self.vars.values.clone()); // FIXME: stop cloning
             }
             // Haven't Converged. Solve for our update.
-            dx = self.mat.solve(res)?;
+            let t0 = self.tic();
+            let soln = self.mat.solve(res.clone());
+            self.toc(t0, |p, d| p.solve += d);
+            dx = match soln {
+                Ok(dx) if dx.iter().all(|v| v.is_finite()) => dx,
+                _ => return Err(self.convergence_error(an, k + 1, &res, &dx, true)),
+            };
+            if self.opts.linear {
+                // Linear circuits are solved exactly, in a single un-limited step
+                for (v, d) in self.vars.values.iter_mut().zip(dx.iter()) {
+                    *v += *d;
+                }
+                // Re-load at the solution, so components commit its operating point
+                self.mat.reset();
+                self.rhs = vec![0.0; self.vars.len()];
+                self.update(an);
+                for c in self.comps.iter_mut() {
+                    c.commit();
+                }
+                return Ok(self.vars.values.clone());
+            }
+            // Limit the largest node-voltage step. Branch currents are not limited.
             let max_step = 1000e-3;
-            let max_abs = dx.iter().fold(0.0, |s, v| if v.abs() > s { v.abs() } else { s });
+            let max_abs = dx
+                .iter()
+                .zip(self.vars.kinds.iter())
+                .filter(|(_, k)| matches!(k, VarKind::V))
+                .fold(0.0, |s, (v, _)| if v.abs() > s { v.abs() } else { s });
             if max_abs > max_step {
                 for r in 0..dx.len() {
                     dx[r] = dx[r] * max_step / max_abs;
@@ -205,8 +484,176 @@ impl Solver<'_, f64> {
             for r in 0..self.vars.len() {
                 self.vars.values[r] += dx[r];
             }
+            last_res = res;
         }
-        return Err(sperror("Convergence Failed"));
+        log::debug!("Newton iterations failed to converge");
+        Err(self.convergence_error(an, 100, &last_res, &dx, false))
+    }
+    /// Diagnose a Newton failure after `iterations`, with last residual `res` and update `dx`.
+    /// Names the variable with the largest residual, or for `singular` matrices,
+    /// the first with an all-zero row or column, as found by re-loading the matrix.
+    fn convergence_error(&mut self, an: &AnalysisInfo, iterations: usize, res: &[f64], dx: &[f64], singular: bool) -> SpError {
+        let maxabs = |v: &[f64]| v.iter().fold(0.0, |m: f64, x| m.max(x.abs()));
+        let mut worst = None;
+        if singular {
+            self.mat.reset();
+            self.rhs = vec![0.0; self.vars.len()];
+            self.update(an);
+            let (rows, cols) = self.mat.norms();
+            let zero = |norms: &[f64], k: usize| norms.get(k).map_or(true, |n| *n == 0.0);
+            worst = (0..self.vars.len())
+                .find(|k| zero(&rows, *k))
+                .or_else(|| (0..self.vars.len()).find(|k| zero(&cols, *k)));
+        }
+        if worst.is_none() && !res.is_empty() {
+            worst = (0..res.len()).max_by(|a, b| res[*a].abs().partial_cmp(&res[*b].abs()).unwrap_or(std::cmp::Ordering::Equal));
+        }
+        let worst_var = match worst {
+            Some(k) => self.vars.name(VarIndex(k)).to_string(),
+            None => String::new(),
+        };
+        SpError::convergence(ConvergenceError {
+            iterations,
+            max_residual: maxabs(res),
+            worst_var,
+            last_dx: maxabs(dx),
+            singular,
+        })
+    }
+    /// Set variable-values to `values`, and commit each component's operating point there,
+    /// as though converged to them
+    pub(crate) fn commit_at(&mut self, values: &[f64]) {
+        self.vars.values = values.to_vec();
+        self.mat.reset();
+        self.rhs = vec![0.0; self.vars.len()];
+        self.update(&AnalysisInfo::OP);
+        for c in self.comps.iter_mut() {
+            c.commit();
+        }
+    }
+    /// Solve for the DC operating point.
+    /// Should Newton fail to converge, falls back to gmin stepping, and then to source stepping.
+    pub(crate) fn solve_op(&mut self) -> SpResult<Vec<f64>> {
+        let mut err = match self.solve(&AnalysisInfo::OP) {
+            Ok(x) => return Ok(x),
+            Err(e) => e,
+        };
+        if self.setup_error.is_some() {
+            return Err(err);
+        }
+        if self.opts.gmin_steps > 0 && self.opts.gmin < GMIN_STEP_START {
+            match self.gmin_stepping() {
+                Ok(x) => return Ok(x),
+                Err(e) => err = e,
+            }
+        }
+        if self.opts.source_steps > 0 {
+            match self.source_stepping() {
+                Ok(x) => return Ok(x),
+                Err(e) => err = e,
+            }
+        }
+        Err(err)
+    }
+    /// Gmin Stepping.
+    /// Restarts from our initial guess at `gmin` of `GMIN_STEP_START`, then reduces it geometrically to `opts.gmin`
+    /// over `opts.gmin_steps` solves, each starting from the last.
+    fn gmin_stepping(&mut self) -> SpResult<Vec<f64>> {
+        let (target, nsteps) = (self.opts.gmin, self.opts.gmin_steps);
+        log::debug!("DCOP failed to converge, gmin stepping from {:e}", GMIN_STEP_START);
+        self.initialize()?;
+        let ratio = (target / GMIN_STEP_START).powf(1.0 / nsteps as f64);
+        let mut rv = Err(sperror("Gmin Stepping Failed"));
+        for k in 0..=nsteps {
+            let gmin = if k == nsteps { target } else { GMIN_STEP_START * ratio.powi(k as i32) };
+            self.opts.gmin = gmin;
+            rv = self.solve(&AnalysisInfo::OP);
+            if let Err(mut e) = rv {
+                e.desc = format!("DCOP Gmin Stepping Failed at gmin={:e}: {}", gmin, e.desc);
+                rv = Err(e);
+                break;
+            }
+        }
+        self.opts.gmin = target;
+        rv
+    }
+    /// Source Stepping.
+    /// Restarts from our initial guess with all independent sources zeroed, then ramps them linearly to their
+    /// values at the prior `opts.src_factor` over `opts.source_steps` solves, each starting from the last.
+    fn source_stepping(&mut self) -> SpResult<Vec<f64>> {
+        let (target, nsteps) = (self.opts.src_factor, self.opts.source_steps);
+        log::debug!("DCOP failed to converge, source stepping");
+        self.initialize()?;
+        let mut rv = Err(sperror("Source Stepping Failed"));
+        for k in 0..=nsteps {
+            let factor = if k == nsteps { target } else { target * k as f64 / nsteps as f64 };
+            self.opts.src_factor = factor;
+            rv = self.solve(&AnalysisInfo::OP);
+            if let Err(mut e) = rv {
+                e.desc = format!("DCOP Source Stepping Failed at factor {}: {}", factor, e.desc);
+                rv = Err(e);
+                break;
+            }
+        }
+        self.opts.src_factor = target;
+        rv
+    }
+    /// Commit each component's state at our latest solution
+    pub(crate) fn commit(&mut self) {
+        for c in self.comps.iter_mut() {
+            c.commit();
+        }
+    }
+    /// Largest current through the `gmin` conductances at our present solution.
+    /// Evaluated as the KCL residual of each node with `gmin` removed, i.e. of only the physical devices.
+    fn gmin_current(&mut self) -> SpResult<f64> {
+        let gmin = self.opts.gmin;
+        self.opts.gmin = 0.0;
+        self.mat.reset();
+        self.rhs = vec![0.0; self.vars.len()];
+        self.update(&AnalysisInfo::OP);
+        let res = self.mat.res(&self.vars.values, &self.rhs);
+        // Restore `gmin`, and re-load our components' operating points with it
+        self.opts.gmin = gmin;
+        self.mat.reset();
+        self.rhs = vec![0.0; self.vars.len()];
+        self.update(&AnalysisInfo::OP);
+
+        let mut imax: f64 = 0.0;
+        for (kind, r) in self.vars.kinds.iter().zip(res?.iter()) {
+            if let VarKind::V = kind {
+                imax = imax.max(r.abs());
+            }
+        }
+        Ok(imax)
+    }
+    /// Matrix-free residual `f(x)` at variable-values `x`, summed from each component's `residual`.
+    /// Independent of the linearized stamps, and hence useful for cross-checking them at convergence.
+    pub(crate) fn residual(&self, x: &[f64]) -> SpResult<Vec<f64>> {
+        if x.len() != self.vars.len() {
+            return Err(sperror("Invalid residual variable-values"));
+        }
+        let vars = Variables {
+            kinds: self.vars.kinds.clone(),
+            names: self.vars.names.clone(),
+            values: x.to_vec(),
+            pending: vec![],
+            aliases: HashMap::new(),
+            index: self.vars.index.clone(),
+        };
+        let mut f = vec![0.0; x.len()];
+        for comp in self.comps.iter() {
+            let contribs = match comp.residual(&vars, &self.opts) {
+                Some(c) => c,
+                None => return Err(sperror("Matrix-free residual not supported for all components")),
+            };
+            for (var, val) in contribs.into_iter() {
+                if let Some(v) = var {
+                    f[v.0] += val;
+                }
+            }
+        }
+        Ok(f)
     }
 }
 
@@ -218,12 +665,19 @@ impl<'a> Solver<'a, Complex<f64>> {
     fn from(re: Solver<'a, f64>) -> Self {
         let mut op = Solver::<'a, Complex<f64>> {
             comps: re.comps,
+            names: re.names,
             vars: Variables::<Complex<f64>>::from(re.vars),
             mat: Matrix::new(),
             rhs: vec![],
             history: vec![],
             defs: re.defs,
             opts: re.opts,
+            profile: re.profile,
+            variations: re.variations,
+            on_iter: None,
+            setup_error: re.setup_error,
+            switched: false,
+            iters: re.iters,
         };
 
         // Create matrix elements, over-writing each Component's pointers
@@ -259,12 +713,18 @@ impl<'a> Solver<'a, Complex<f64>> {
             // FIXME: number of iterations
             // Make a copy of state for tracking
             self.history.push(self.vars.values.clone());
+            self.iters += 1;
             // Reset our matrix and RHS vector
             self.mat.reset();
             self.rhs = vec![Complex::zero(); self.vars.len()];
 
             // Load up component updates
+            let t0 = self.tic();
             self.update(an);
+            self.toc(t0, |p, d| {
+                p.load += d;
+                p.iters += 1;
+            });
 
             // Calculate the residual error
             let res: Vec<Complex<f64>> = self.mat.res(&self.vars.values, &self.rhs)?;
@@ -279,15 +739,25 @@ impl<'a> Solver<'a, Complex<f64>> {
                 return Ok(self.vars.values.clone());
             }
             // Solve for our update
+            let t0 = self.tic();
             dx = self.mat.solve(res)?;
-            let max_step = 1.0;
-            let max_abs = dx.iter().fold(0.0, |s, v| if v.norm() > s { v.norm() } else { s });
-            if max_abs > max_step {
-                for r in 0..dx.len() {
-                    dx[r] = dx[r] * max_step / max_abs;
+            self.toc(t0, |p, d| p.solve += d);
+            if self.opts.linear {
+                // Linear circuits are solved exactly, in a single un-limited step
+                for (v, d) in self.vars.values.iter_mut().zip(dx.iter()) {
+                    *v += *d;
+                }
+                // Re-load at the solution, so components commit its operating point
+                self.mat.reset();
+                self.rhs = vec![Complex::zero(); self.vars.len()];
+                self.update(an);
+                for c in self.comps.iter_mut() {
+                    c.commit();
                 }
+                return Ok(self.vars.values.clone());
             }
-            // And update our guess
+            // And update our guess.
+            // Small-signal systems are linear in our variables, so updates are not step-limited.
             for r in 0..self.vars.len() {
                 self.vars.values[r] += dx[r];
             }
@@ -305,43 +775,176 @@ impl<'a> Solver<'a, Complex<f64>> {
 
 impl<'a, NumT: SpNum> Solver<'a, NumT> {
     /// Create a new Solver, translate `Ckt` Components into its `ComponentSolvers`.
-    pub(crate) fn new(ckt: Ckt, opts: Options) -> Solver<'a, NumT> {
+    pub(crate) fn new(mut ckt: Ckt, opts: Options) -> Solver<'a, NumT> {
+        let mut profile = if opts.profile { Some(Profile::default()) } else { None };
+        // Apply any selected corner to our models, prior to deriving their parameters
+        let setup_error = match opts.corner {
+            Some(ref c) => ckt.defs.apply_corner(c, &opts).err(),
+            None => None,
+        };
         // Elaborate the circuit
-        use crate::elab::{elaborate, Elaborator};
-        let e = elaborate(ckt, opts);
+        use crate::elab::Elaborator;
+        let t0 = Instant::now();
+        let Ckt { comps, defs, signals, .. } = ckt;
+        let mut e = Elaborator::new(defs, opts, None);
+        let setup_error = setup_error.or_else(|| e.elaborate_top(comps, signals).err());
         let Elaborator {
-            defs, mut comps, vars, opts, ..
+            defs,
+            mut comps,
+            names,
+            vars,
+            opts,
+            variations,
+            ..
         } = e;
+        if let Some(p) = profile.as_mut() {
+            p.elaborate = t0.elapsed();
+        }
         // Create our matrix and its elements
+        let t0 = Instant::now();
         let mut mat = Matrix::new();
         for comp in comps.iter_mut() {
             comp.create_matrix_elems(&mut mat);
         }
+        if let Some(p) = profile.as_mut() {
+            p.setup = t0.elapsed();
+        }
         // And return a Solver with the combination
-        Solver {
+        let mut solver = Solver {
             comps,
+            names,
             vars,
             mat,
             rhs: Vec::new(),
             history: Vec::new(),
             defs,
             opts,
+            profile,
+            variations,
+            on_iter: None,
+            setup_error,
+            switched: false,
+            iters: 0,
+        };
+        if solver.setup_error.is_none() {
+            solver.setup_error = solver.check_linear().err();
         }
+        solver
     }
-    fn converged(&self, dx: &Vec<NumT>, res: &Vec<NumT>) -> bool {
-        // Inter-step Newton convergence
-        for e in dx.iter() {
-            if e.absv() > self.opts.reltol {
-                return false;
+    /// In linear-only mode, check for any nonlinear components
+    fn check_linear(&self) -> SpResult<()> {
+        if !self.opts.linear {
+            return Ok(());
+        }
+        for (k, comp) in self.comps.iter().enumerate() {
+            if !comp.linear() {
+                let name = self.names.get(k).map(|s| s.as_str()).unwrap_or("");
+                return Err(sperror(format!("Nonlinear Instance {} Not Allowed in Linear-Only Mode", name)));
             }
         }
-        // KCL convergence
-        for e in res.iter() {
-            if e.absv() > self.opts.iabstol {
-                return false;
+        Ok(())
+    }
+    /// Start a profiling timer, if profiling is enabled
+    fn tic(&self) -> Option<Instant> {
+        if self.profile.is_some() {
+            Some(Instant::now())
+        } else {
+            None
+        }
+    }
+    /// Stop profiling timer `t0`, and add its elapsed time to our profile via `f`
+    fn toc<F: FnOnce(&mut Profile, Duration)>(&mut self, t0: Option<Instant>, f: F) {
+        if let (Some(p), Some(t0)) = (self.profile.as_mut(), t0) {
+            f(p, t0.elapsed());
+        }
+    }
+    /// Check convergence of iteration `k`, per our `ConvergenceCriterion` option
+    fn converged(&self, k: usize, dx: &Vec<NumT>, res: &Vec<NumT>, scale: &[f64]) -> bool {
+        match self.opts.convergence {
+            // Our initial `dx` is all zeros, so update-only checks never accept the first iteration
+            ConvergenceCriterion::Update => k > 0 && self.update_converged(dx),
+            ConvergenceCriterion::Residual => self.residual_converged(res, scale),
+            ConvergenceCriterion::Both => self.update_converged(dx) && self.residual_converged(res, scale),
+        }
+    }
+    /// Inter-step Newton convergence
+    fn update_converged(&self, dx: &Vec<NumT>) -> bool {
+        dx.iter().all(|e| e.absv() <= self.opts.reltol)
+    }
+    /// KCL convergence.
+    /// Tolerances are relaxed by each row's term-magnitude `scale`, by a (small) multiple of the floating-point precision,
+    /// so that large conductances (e.g. small-timestep capacitors) do not produce residuals which can never converge.
+    fn residual_converged(&self, res: &Vec<NumT>, scale: &[f64]) -> bool {
+        res.iter().zip(scale.iter()).all(|(e, s)| e.absv() <= self.opts.iabstol + RES_NOISE_TOL * s)
+    }
+}
+
+/// Relative residual tolerance, at roughly the noise-floor of f64 cancellation
+const RES_NOISE_TOL: f64 = 1e-12;
+
+/// Solver Snapshot, for debug reports.
+/// Captured at the end of each analysis.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DebugInfo {
+    /// Simulation options, debug-formatted
+    pub options: String,
+    /// Matrix dimension, i.e. number of variables
+    pub size: usize,
+    /// Structurally non-zero matrix elements, including fill-ins
+    pub nnz: usize,
+    /// Variable names, kinds ("V" or "I"), and final values
+    pub vars: Vec<(String, String, f64)>,
+    /// Instance names and their named operating-point quantities
+    pub devices: Vec<(String, Vec<(String, f64)>)>,
+}
+impl DebugInfo {
+    /// Text report, headed by analysis-name `title`
+    fn report(&self, title: &str) -> String {
+        let mut s = format!("# Spice21 Debug Dump: {}\n", title);
+        s.push_str(&format!("\n## Matrix\nsize: {} x {}\nnnz: {}\n", self.size, self.size, self.nnz));
+        s.push_str("\n## Variables\n");
+        for (name, kind, val) in self.vars.iter() {
+            s.push_str(&format!("{} ({}) = {:e}\n", name, kind, val));
+        }
+        s.push_str("\n## Devices\n");
+        for (name, op) in self.devices.iter() {
+            s.push_str(&format!("{}:\n", name));
+            for (key, val) in op.iter() {
+                s.push_str(&format!("  {} = {:e}\n", key, val));
             }
         }
-        return true;
+        s.push_str(&format!("\n## Options\n{}\n", self.options));
+        s
+    }
+}
+impl Solver<'_, f64> {
+    /// Snapshot our present state, for debug reports
+    fn debug_info(&self) -> DebugInfo {
+        let vars = (0..self.vars.len())
+            .map(|i| {
+                let kind = match self.vars.kinds[i] {
+                    VarKind::V => "V",
+                    VarKind::I => "I",
+                    VarKind::Q => "Q",
+                };
+                (self.vars.names[i].clone(), kind.to_string(), self.vars.values[i])
+            })
+            .collect();
+        let devices = self
+            .comps
+            .iter()
+            .zip(self.names.iter())
+            .map(|(comp, name)| (name.clone(), comp.op_point()))
+            .filter(|(_, op)| !op.is_empty())
+            .map(|(name, op)| (name, op.into_iter().map(|(k, v)| (k.to_string(), v)).collect()))
+            .collect();
+        DebugInfo {
+            options: format!("{:#?}", self.opts),
+            size: self.vars.len(),
+            nnz: self.mat.nnz(),
+            vars,
+            devices,
+        }
     }
 }
 
@@ -351,16 +954,48 @@ pub struct OpResult {
     pub names: Vec<String>,
     pub values: Vec<f64>,
     pub map: HashMap<String, f64>,
+    pub profile: Option<Profile>,
+    /// Largest current through any `gmin` conductance, i.e. the KCL error of the solution with `gmin` removed
+    pub gmin_current: f64,
+    /// Names of each current (rather than node-voltage) unknown, e.g. through voltage sources.
+    /// Values follow the branch convention, positive flowing *into* the `p` terminal.
+    /// See `source_current` for the current delivered by a source.
+    pub currents: Vec<String>,
+    /// Solver snapshot, for `debug_dump`
+    pub debug: DebugInfo,
 }
 impl OpResult {
     /// Create an OpResult from a (typically final) set of `Variables`.
+    /// Current-variables are mapped as `I(name)`, and as `name` unless a same-named node-voltage exists.
     fn from(vars: Variables<f64>) -> Self {
         let mut map: HashMap<String, f64> = HashMap::new();
+        let mut currents = vec![];
+        for i in 0..vars.names.len() {
+            if let VarKind::V = vars.kinds[i] {
+                map.insert(vars.names[i].clone(), vars.values[i]);
+            }
+        }
         for i in 0..vars.names.len() {
-            map.insert(vars.names[i].clone(), vars.values[i]);
+            if let VarKind::I = vars.kinds[i] {
+                map.insert(format!("I({})", vars.names[i]), vars.values[i]);
+                map.entry(vars.names[i].clone()).or_insert(vars.values[i]);
+                currents.push(vars.names[i].clone());
+            }
         }
         let Variables { names, values, .. } = vars;
-        OpResult { names, values, map }
+        OpResult {
+            names,
+            values,
+            map,
+            profile: None,
+            gmin_current: 0.0,
+            currents,
+            debug: DebugInfo::default(),
+        }
+    }
+    /// Text report of the final solver state, for bug reports. See `DebugInfo`.
+    pub fn debug_dump(&self) -> String {
+        self.debug.report("Operating Point")
     }
     /// Get the value of signal `signame`, or an `SpError` if not present
     pub(crate) fn get<S: Into<String>>(&self, signame: S) -> SpResult<f64> {
@@ -369,6 +1004,84 @@ impl OpResult {
             None => Err(sperror("Signal Not Found")),
         }
     }
+    /// Get the current delivered by source `name`, i.e. flowing out of its `p` terminal into the circuit.
+    /// Positive whenever the source supplies power to a resistive load, whether either terminal is grounded.
+    /// The negation of its branch-current `I(name)`.
+    pub fn source_current(&self, name: &str) -> SpResult<f64> {
+        match self.map.get(&format!("I({})", name)) {
+            Some(i) => Ok(-i),
+            None => Err(sperror(format!("Source Current Not Found: {}", name))),
+        }
+    }
+    /// Get operating-point quantity `param` (e.g. "vgs", "gm") of device-instance `inst`
+    pub fn device_op(&self, inst: &str, param: &str) -> SpResult<f64> {
+        let dev = match self.debug.devices.iter().find(|(name, _)| name == inst) {
+            Some((_, d)) => d,
+            None => return Err(sperror(format!("Device Not Found: {}", inst))),
+        };
+        match dev.iter().find(|(name, _)| name == param) {
+            Some((_, v)) => Ok(*v),
+            None => Err(sperror(format!("Operating Point Parameter Not Found: {}.{}", inst, param))),
+        }
+    }
+    /// Operating-point summaries of each device with internal operating-point state, keyed by instance name
+    pub fn device_ops(&self) -> HashMap<String, DeviceOp> {
+        self.debug
+            .devices
+            .iter()
+            .map(|(name, op)| (name.clone(), DeviceOp::from(op)))
+            .collect()
+    }
+    /// Human-readable operating-point report, as SPICE's `.op` printout.
+    /// Lists each device, sorted by instance name, with its region (if any) and operating-point quantities.
+    pub fn op_report(&self) -> String {
+        let mut ops: Vec<(String, DeviceOp)> = self.device_ops().into_iter().collect();
+        ops.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut s = String::new();
+        for (name, op) in ops.iter() {
+            match op.region {
+                Some(r) => s.push_str(&format!("{}: {:?}\n", name, r)),
+                None => s.push_str(&format!("{}:\n", name)),
+            }
+            let mut params: Vec<(&String, &f64)> = op.params.iter().collect();
+            params.sort_by(|a, b| a.0.cmp(b.0));
+            for (key, val) in params {
+                s.push_str(&format!("  {} = {:e}\n", key, val));
+            }
+        }
+        s
+    }
+}
+/// Mos Operating Region
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MosRegion {
+    Cutoff,
+    Triode,
+    Saturation,
+}
+/// Device Operating-Point Summary. See `OpResult::device_ops`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceOp {
+    /// Mos operating region, for devices which report overdrive `vov` and `vds`
+    pub region: Option<MosRegion>,
+    /// Named operating-point quantities, e.g. `ids`, `vgs`, `gm`
+    pub params: HashMap<String, f64>,
+}
+impl DeviceOp {
+    fn from(op: &[(String, f64)]) -> Self {
+        let params: HashMap<String, f64> = op.iter().cloned().collect();
+        let region = match (params.get("vov"), params.get("vds")) {
+            (Some(&vov), Some(&vds)) => Some(if vov <= 0.0 {
+                MosRegion::Cutoff
+            } else if vds < vov {
+                MosRegion::Triode
+            } else {
+                MosRegion::Saturation
+            }),
+            _ => None,
+        };
+        Self { region, params }
+    }
 }
 /// Maintain much (most?) of our original vector-result-format
 /// via enabling integer indexing
@@ -380,11 +1093,333 @@ impl Index<usize> for OpResult {
 }
 
 /// Dc Operating Point Analysis
+///
+/// The optional second argument sets simulation `Options`, defaulting to `Options::default()`.
+/// The Newton starting point is set by `Options.init`. An explicit initial guess is provided via
+/// `InitStrategy::Map`, from node-names to voltages, e.g. to aid convergence of difficult circuits,
+/// or to select among multiple DC solutions. Unlike a `.nodeset`, no node is held at its guess;
+/// the guess only sets where iteration begins.
 pub fn dcop(ckt: Ckt, opts: Option<Options>) -> SpResult<OpResult> {
     let o = if let Some(o) = opts { o } else { Options::default() };
+    dcop_solve(Solver::<f64>::new(ckt, o))
+}
+/// Dc Operating Point Analysis, calling `cb` on each Newton iteration.
+/// Each call includes the assembled matrix and right-hand-side if `matrix` is set,
+/// at the cost of copying them. Primarily for teaching and debug of convergence.
+pub fn dcop_iters<'a>(ckt: Ckt, opts: Option<Options>, matrix: bool, cb: impl FnMut(&NewtonIter) + 'a) -> SpResult<OpResult> {
+    let o = opts.unwrap_or_default();
+    let mut s = Solver::<f64>::new(ckt, o);
+    s.on_iter = Some(IterObserver { matrix, cb: Box::new(cb) });
+    dcop_solve(s)
+}
+/// Solve, and collect an `OpResult` from, Dc Solver `s`
+fn dcop_solve(s: Solver<f64>) -> SpResult<OpResult> {
+    Ok(dcop_solve_detailed(s)?.op)
+}
+/// Detailed Dc Operating Point Result, including measures of its quality
+#[derive(Debug)]
+pub struct OpDetail {
+    pub op: OpResult,
+    /// KCL error current at each node, at the solution
+    pub residual: HashMap<String, f64>,
+    /// Whether `residual` is summed from each component's matrix-free residual.
+    /// If any component lacks one (e.g. Mos1), it instead comes from the linearized stamps of the final Newton iteration.
+    pub matrix_free: bool,
+    /// Newton iterations to converge, summed across any failed attempt and each stage of gmin and source stepping
+    pub iters: usize,
+}
+impl OpDetail {
+    /// Largest absolute KCL error current, across all nodes
+    pub fn max_residual(&self) -> f64 {
+        self.residual.values().fold(0.0, |m, r| m.max(r.abs()))
+    }
+}
+/// Dc Operating Point Analysis, also reporting the final KCL residual and iteration count.
+/// For programmatically checking solution quality, rather than relying on convergence alone.
+pub fn dcop_detailed(ckt: Ckt, opts: Option<Options>) -> SpResult<OpDetail> {
+    dcop_solve_detailed(Solver::<f64>::new(ckt, opts.unwrap_or_default()))
+}
+/// Solve, and collect an `OpDetail` from, Dc Solver `s`
+fn dcop_solve_detailed(mut s: Solver<f64>) -> SpResult<OpDetail> {
+    s.initialize()?;
+    let iters0 = s.iters;
+    let _r = s.solve_op()?;
+    let iters = s.iters - iters0;
+    let (res, matrix_free) = match s.residual(&s.vars.values) {
+        Ok(r) => (r, true),
+        Err(_) => (s.mat.res(&s.vars.values, &s.rhs)?, false),
+    };
+    let mut residual = HashMap::new();
+    for ((name, kind), r) in s.vars.names.iter().zip(s.vars.kinds.iter()).zip(res.into_iter()) {
+        if let VarKind::V = kind {
+            residual.insert(name.clone(), r);
+        }
+    }
+    let gmin_current = s.gmin_current()?;
+    let debug = s.debug_info();
+    let mut op = OpResult::from(s.vars);
+    op.profile = s.profile;
+    op.gmin_current = gmin_current;
+    op.debug = debug;
+    Ok(OpDetail {
+        op,
+        residual,
+        matrix_free,
+        iters,
+    })
+}
+
+/// Swept quantity of a DC Sweep
+#[derive(Debug, Clone)]
+pub enum SweepParam {
+    /// Value of the instance named `name`: DC value of an independent source, or conductance of a resistor
+    Instance(String),
+    /// Parameter `param` of the Mos1 model named `model`
+    Mos1Model { model: String, param: String },
+    /// Circuit temperature, in Kelvin. See `temp_sweep`.
+    Temp,
+}
+/// DC Sweep Options
+#[derive(Debug, Clone)]
+pub struct DcSweepOptions {
+    pub param: SweepParam,
+    pub values: Vec<f64>,
+}
+impl DcSweepOptions {
+    /// Sweep of the value of instance `name`, from `start` through `stop` in increments of `step`.
+    /// Includes `stop` if it falls on a step.
+    pub fn range(name: &str, start: f64, stop: f64, step: f64) -> SpResult<Self> {
+        Ok(Self {
+            param: SweepParam::Instance(name.to_string()),
+            values: sweep_range(start, stop, step)?,
+        })
+    }
+}
+/// Values from `start` through `stop` in increments of `step`, including `stop` if it falls on a step
+fn sweep_range(start: f64, stop: f64, step: f64) -> SpResult<Vec<f64>> {
+    let nsteps = (stop - start) / step;
+    if !nsteps.is_finite() || nsteps < 0.0 {
+        return Err(sperror(format!("Invalid DC Sweep Range: {} to {} by {}", start, stop, step)));
+    }
+    // Allow for rounding in the step-count, so that e.g. 0 to 1 by 0.1 includes 1
+    let nsteps = (nsteps + 1e-9).floor() as usize;
+    Ok((0..=nsteps).map(|k| start + k as f64 * step).collect())
+}
+/// DC Sweep Result
+/// Includes the swept `values`, and the operating point at each
+#[derive(Debug, Default)]
+pub struct DcSweepResult {
+    pub values: Vec<f64>,
+    pub names: Vec<String>,
+    pub data: Vec<Vec<f64>>,
+    pub map: HashMap<String, Vec<f64>>,
+}
+impl Solver<'_, f64> {
+    /// Set swept quantity `param` to `val`, re-deriving any internal parameters which depend on it
+    fn alter(&mut self, param: &SweepParam, val: f64) -> SpResult<()> {
+        match param {
+            SweepParam::Instance(name) => {
+                let idx = match self.names.iter().position(|n| n == name) {
+                    Some(idx) => idx,
+                    None => return Err(sperror(format!("Sweep Instance Not Found: {}", name))),
+                };
+                match self.comps[idx] {
+                    ComponentSolver::Vsrc(_) | ComponentSolver::Isrc(_) | ComponentSolver::Resistor(_) => self.comps[idx].update(val),
+                    _ => return Err(sperror(format!("Instance {} has no Sweepable Value", name))),
+                }
+            }
+            SweepParam::Mos1Model { model, param } => self.defs.mos1.alter_model(model, param, val, &self.opts)?,
+            SweepParam::Temp => self.recompute_temp(val)?,
+        }
+        Ok(())
+    }
+    /// Set the circuit temperature to `temp` (Kelvin), re-deriving the temperature-dependent internal parameters
+    /// of each Mos1 and Diode instance. Instances with their own `temp` keep it.
+    /// Bsim4 instances are (for now) always derived at 300.15K. Other temperatures are an error in their presence.
+    pub fn recompute_temp(&mut self, temp: f64) -> SpResult<()> {
+        if temp != 300.15 && self.comps.iter().any(|c| matches!(c, ComponentSolver::Bsim4(_))) {
+            return Err(sperror(format!("Bsim4 Instances Only Support 300.15K, Not {}K", temp)));
+        }
+        self.opts.temp = temp;
+        self.defs.mos1.rederive(&self.opts);
+        self.defs.diodes.rederive(&self.opts);
+        Ok(())
+    }
+}
+/// DC Sweep Analysis
+/// Solves an operating point at each of `args.values`, each starting from the prior solution.
+pub fn dcsweep(ckt: Ckt, opts: Option<Options>, args: DcSweepOptions) -> SpResult<DcSweepResult> {
+    let o = opts.unwrap_or_default();
+    let mut s = Solver::<f64>::new(ckt, o);
+    s.initialize()?;
+    let mut res = DcSweepResult {
+        names: s.vars.names.clone(),
+        ..Default::default()
+    };
+    for val in args.values.iter() {
+        s.alter(&args.param, *val)?;
+        let soln = s.solve(&AnalysisInfo::OP)?;
+        res.values.push(*val);
+        res.data.push(soln);
+    }
+    res.map_signals();
+    Ok(res)
+}
+impl DcSweepResult {
+    /// Collect each signal's values across `data` into `map`
+    fn map_signals(&mut self) {
+        for (i, name) in self.names.iter().enumerate() {
+            self.map.insert(name.clone(), self.data.iter().map(|pt| pt[i]).collect());
+        }
+    }
+}
+/// Temperature Sweep
+/// Solves an operating point at each circuit temperature in `temps` (Kelvin), each starting from the prior solution.
+/// Results are keyed by temperature, in `DcSweepResult.values`.
+/// Circuits with Bsim4 instances fail for any temperature other than 300.15K.
+pub fn temp_sweep(ckt: Ckt, temps: &[f64], opts: Option<Options>) -> SpResult<DcSweepResult> {
+    let args = DcSweepOptions {
+        param: SweepParam::Temp,
+        values: temps.to_vec(),
+    };
+    dcsweep(ckt, opts, args)
+}
+/// Source Sweep Specification
+/// Independent source `src`, from `start` through `stop` in increments of `step`. Includes `stop` if it falls on a step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepSpec {
+    pub src: String,
+    pub start: f64,
+    pub stop: f64,
+    pub step: f64,
+}
+impl SweepSpec {
+    /// Swept values, after checking `src` is an independent source in `ckt`
+    fn values(&self, ckt: &Ckt) -> SpResult<Vec<f64>> {
+        let SweepSpec { src, start, stop, step } = self;
+        match ckt.comps.iter().find(|c| c.name() == src) {
+            Some(Comp::V(_)) | Some(Comp::I(_)) => (),
+            Some(_) => return Err(sperror(format!("Sweep Instance {} is not an Independent Source", src))),
+            None => return Err(sperror(format!("Sweep Instance Not Found: {}", src))),
+        }
+        sweep_range(*start, *stop, *step)
+    }
+}
+/// Nested DC Sweep, e.g. for families of MOS characteristic curves.
+/// Sweeps source `inner` across its range for each value of source `outer`,
+/// returning one `DcSweepResult` per `outer` value, in order.
+/// Each curve starts from the solution at the first point of its predecessor.
+pub fn dc_sweep2(ckt: Ckt, inner: SweepSpec, outer: SweepSpec, opts: Option<Options>) -> SpResult<Vec<DcSweepResult>> {
+    let inner_vals = inner.values(&ckt)?;
+    let outer_vals = outer.values(&ckt)?;
+    if inner.src == outer.src {
+        return Err(sperror(format!("Nested DC Sweep of the same source: {}", inner.src)));
+    }
+    let (inner_param, outer_param) = (SweepParam::Instance(inner.src), SweepParam::Instance(outer.src));
+    let mut s = Solver::<f64>::new(ckt, opts.unwrap_or_default());
+    s.initialize()?;
+    let mut curves: Vec<DcSweepResult> = vec![];
+    for oval in outer_vals.iter() {
+        if let Some(prev) = curves.last() {
+            s.vars.values = prev.data[0].clone();
+        }
+        s.alter(&outer_param, *oval)?;
+        let mut res = DcSweepResult {
+            names: s.vars.names.clone(),
+            ..Default::default()
+        };
+        for ival in inner_vals.iter() {
+            s.alter(&inner_param, *ival)?;
+            let soln = s.solve(&AnalysisInfo::OP)?;
+            res.values.push(*ival);
+            res.data.push(soln);
+        }
+        res.map_signals();
+        curves.push(res);
+    }
+    Ok(curves)
+}
+/// Monte Carlo Options
+#[derive(Debug, Clone)]
+pub struct MonteCarloOptions {
+    /// Number of runs
+    pub runs: usize,
+    /// Random seed. Runs with equal seeds produce equal results.
+    pub seed: u64,
+}
+impl Default for MonteCarloOptions {
+    fn default() -> Self {
+        Self { runs: 100, seed: 21 }
+    }
+}
+/// Monte Carlo Result
+/// Includes the operating point of each run
+#[derive(Debug, Default)]
+pub struct MonteCarloResult {
+    pub names: Vec<String>,
+    pub data: Vec<Vec<f64>>,
+    pub map: HashMap<String, Vec<f64>>,
+}
+/// Monte Carlo Dc Operating Point Analysis
+/// Draws each toleranced component's value per its own `tol` and `dist` annotations, and solves an operating point per run.
+/// Tolerances are fractions of resistance: one standard deviation for normal distributions,
+/// and the half-width for uniform ones. Draws of non-positive resistance are discarded and redrawn.
+pub fn monte_carlo(ckt: Ckt, opts: Option<Options>, args: MonteCarloOptions) -> SpResult<MonteCarloResult> {
+    use crate::circuit::Dist;
+    let o = opts.unwrap_or_default();
     let mut s = Solver::<f64>::new(ckt, o);
-    let _r = s.solve(&AnalysisInfo::OP)?;
-    return Ok(OpResult::from(s.vars));
+    s.initialize()?;
+    let mut rng = Rng::new(args.seed);
+    let mut res = MonteCarloResult {
+        names: s.vars.names.clone(),
+        ..Default::default()
+    };
+    for _ in 0..args.runs {
+        for k in 0..s.variations.len() {
+            let Variation { comp, nom, tol, dist } = s.variations[k];
+            let r = loop {
+                let x = match dist {
+                    Dist::Normal => rng.normal(),
+                    Dist::Uniform => 2.0 * rng.uniform() - 1.0,
+                };
+                if 1.0 + tol * x > 0.0 {
+                    break 1.0 + tol * x;
+                }
+            };
+            // Variations apply to resistance, i.e. to the inverse of our conductances
+            s.comps[comp].update(nom / r);
+        }
+        res.data.push(s.solve(&AnalysisInfo::OP)?);
+    }
+    for (i, name) in res.names.iter().enumerate() {
+        res.map.insert(name.clone(), res.data.iter().map(|pt| pt[i]).collect());
+    }
+    Ok(res)
+}
+/// Pseudo-Random Number Generator, via the SplitMix64 algorithm.
+/// Small and fast, and more than random enough for Monte Carlo.
+struct Rng(u64);
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    /// Uniform on [0, 1)
+    fn uniform(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+    /// Standard normal, via the Box-Muller transform
+    fn normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.uniform(); // On (0, 1], avoiding ln(0)
+        let u2 = self.uniform();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
 }
 pub(crate) enum AnalysisInfo<'a> {
     OP,
@@ -392,14 +1427,32 @@ pub(crate) enum AnalysisInfo<'a> {
     AC(&'a AcOptions, &'a AcState),
 }
 
-pub(crate) enum NumericalIntegration {
-    BE,
-    TRAP,
+/// Transient Numerical Integration Method
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegMethod {
+    /// Trapezoidal
+    Trap,
+    /// First-order Gear, i.e. Backward Euler
+    #[default]
+    Gear1,
+    /// Second-order Gear, i.e. BDF2. Damps the numerical ringing of `Trap`, at second-order accuracy.
+    Gear2,
 }
-
-impl Default for NumericalIntegration {
-    fn default() -> NumericalIntegration {
-        NumericalIntegration::BE
+impl IntegMethod {
+    /// Order of accuracy. Local truncation error per step scales with `dt` to one more than this.
+    pub fn order(&self) -> usize {
+        match self {
+            IntegMethod::Gear1 => 1,
+            IntegMethod::Trap | IntegMethod::Gear2 => 2,
+        }
+    }
+    /// Local truncation error coefficient `C`, of per-step error `C * dt^(p+1) * x^(p+1)` for order `p`
+    fn lte_coeff(&self) -> f64 {
+        match self {
+            IntegMethod::Gear1 => 1.0 / 2.0,
+            IntegMethod::Trap => 1.0 / 12.0,
+            IntegMethod::Gear2 => 2.0 / 9.0,
+        }
     }
 }
 
@@ -411,50 +1464,177 @@ impl Default for NumericalIntegration {
 pub(crate) struct TranState {
     pub(crate) t: f64,
     pub(crate) dt: f64,
+    /// Last accepted time-step, or zero before the first. Multi-point methods fall back to `Gear1` without one.
+    pub(crate) dt_prev: f64,
     pub(crate) vic: Vec<usize>,
     pub(crate) ric: Vec<usize>,
-    pub(crate) ni: NumericalIntegration,
+    pub(crate) ni: IntegMethod,
 }
 impl TranState {
-    /// Numerical Integration
-    pub fn integrate(&self, dq: f64, dq_dv: f64, vguess: f64, _ip: f64) -> (f64, f64, f64) {
-        let dt = self.dt;
-        match self.ni {
-            NumericalIntegration::BE => {
-                let g = dq_dv / dt;
-                let i = dq / dt;
-                let rhs = i - g * vguess;
-                (g, i, rhs)
-            }
-            NumericalIntegration::TRAP => {
-                panic!("Trapezoidal Integration is Disabled!");
-                // let g = 2.0 * dq_dv / dt;
-                // let i = 2.0 * dq / dt - ip;
-                // let rhs = i - g * vguess;
-                // (g, i, rhs)
-            }
-        }
+    /// Numerical Integration, for components without charge history.
+    /// `Gear2` falls back to `Gear1`.
+    pub fn integrate(&self, dq: f64, dq_dv: f64, vguess: f64, ip: f64) -> (f64, f64, f64) {
+        let prev = ChargeInteg {
+            i: ip,
+            ..Default::default()
+        };
+        let ChargeInteg { g, i, rhs, .. } = self.integq_with(dq, dq_dv, vguess, &prev, false);
+        (g, i, rhs)
     }
-    pub fn integq(&self, dq: f64, dq_dv: f64, vguess: f64, _ip: f64) -> ChargeInteg {
-        let (g, i, rhs) = self.integrate(dq, dq_dv, vguess, _ip);
-        ChargeInteg { g, i, rhs }
+    /// Numerical Integration of charge-change `dq`, given the companion model `prev` of the last accepted step
+    pub fn integq(&self, dq: f64, dq_dv: f64, vguess: f64, prev: &ChargeInteg) -> ChargeInteg {
+        self.integq_with(dq, dq_dv, vguess, prev, true)
+    }
+    fn integq_with(&self, dq: f64, dq_dv: f64, vguess: f64, prev: &ChargeInteg, history: bool) -> ChargeInteg {
+        let (h, h1) = (self.dt, self.dt_prev);
+        // Both multi-point methods start with a `Gear1` step
+        let method = if h1 <= 0.0 { IntegMethod::Gear1 } else { self.ni };
+        let (g, i) = match method {
+            IntegMethod::Gear1 => (dq_dv / h, dq / h),
+            IntegMethod::Trap => (2.0 * dq_dv / h, 2.0 * dq / h - prev.i),
+            IntegMethod::Gear2 if !history => (dq_dv / h, dq / h),
+            IntegMethod::Gear2 => {
+                // Variable-step coefficients, with step-ratio `r`
+                let r = h / h1;
+                let a0 = (1.0 + 2.0 * r) / ((1.0 + r) * h);
+                let a1 = r * r / ((1.0 + r) * h);
+                (a0 * dq_dv, a0 * dq - a1 * prev.dq)
+            }
+        };
+        let rhs = i - g * vguess;
+        ChargeInteg { g, i, rhs, dq }
     }
 }
-/// Result of numerical integration for a charge-element
-#[derive(Default, Clone)]
-pub(crate) struct ChargeInteg {
-    pub(crate) g: f64,
-    pub(crate) i: f64,
-    pub(crate) rhs: f64,
+/// Result of numerical integration for a charge-element,
+/// i.e. its transient companion model
+#[derive(Debug, Default, Clone)]
+pub struct ChargeInteg {
+    /// Equivalent conductance
+    pub g: f64,
+    /// Current
+    pub i: f64,
+    /// Equivalent (RHS) current source
+    pub rhs: f64,
+    /// Charge change over the step, as history for multi-point methods
+    pub dq: f64,
 }
+/// Initial `gmin` of DCOP gmin stepping
+const GMIN_STEP_START: f64 = 1e-3;
+/// Initial-condition forcing conductance, applied during the initial operating point.
+/// Forced nodes settle within (current / IC_GFORCE) of their initial-condition values.
+const IC_GFORCE: f64 = 1e6;
+/// Initial-condition conductance after release, for all transient time-points
+const IC_GRELEASE: f64 = 1e-9;
 /// Transient Analysis Options
 #[derive(Debug)]
 pub struct TranOptions {
     pub tstep: f64,
     pub tstop: f64,
+    /// Initial conditions, by node, e.g. `("out".into(), 0.0)`.
+    /// Named nodes resolve against the elaborated circuit, and must exist in it.
+    /// Hierarchical paths such as `x1.out` address nodes and ports inside module instances.
     pub ic: Vec<(NodeRef, f64)>,
+    /// Use initial conditions: skip the initial operating point, and start from the values of `ic`,
+    /// with all other variables zero.
+    pub uic: bool,
+    /// Cooperative cancellation flag, checked at each time-step.
+    /// Setting it ends the simulation, returning results through the current time.
+    pub cancel: Option<Arc<AtomicBool>>,
+    /// Additional quantities to record, e.g. `"P(r1)"` for the power dissipated in instance `r1`.
+    /// Each is added to `TranResult.map` under the same key.
+    pub save: Vec<String>,
+    /// Output time-points
+    pub grid: TranGrid,
+    /// Smallest time-step, to which steps are cut upon failure to converge,
+    /// or for `adaptive` steps, to keep truncation error within `lte_tol`.
+    /// Defaults to one-millionth of `tstep`.
+    pub tstep_min: Option<f64>,
+    /// Largest `adaptive` time-step. Defaults to a fiftieth of `tstop`.
+    pub tstep_max: Option<f64>,
+    /// Local truncation error tolerance for `adaptive` time-steps, in volts per step. Defaults to 1mV.
+    pub lte_tol: Option<f64>,
+    /// Adaptive time-stepping. Starting from `tstep`, each step shrinks or grows within
+    /// [`tstep_min`, `tstep_max`], keeping its estimated local truncation error within `lte_tol`.
+    /// Otherwise steps are `tstep`, cut only upon failure to converge.
+    pub adaptive: bool,
+    /// Numerical integration method. Defaults to `Gear1`.
+    pub method: IntegMethod,
+    /// Largest number of rejected steps while advancing by a single `tstep`, beyond which the simulation has stalled.
+    /// Defaults to 100.
+    pub stall_rejects: Option<usize>,
+    /// Recording window, as (start, end) times. Time-points before the start are solved but not stored,
+    /// and the simulation ends at the earlier of the window's end and `tstop`.
+    pub window: Option<(f64, f64)>,
+    /// Initial operating point, e.g. from a prior `dcop` of the same circuit, skipping the initial solve.
+    /// Its variables must match those of the transient circuit, and it excludes initial conditions `ic`.
+    pub op: Option<OpResult>,
+    /// Return the partial results of runs which end early, with `TranResult.failure` set.
+    /// Otherwise such runs, whether on `tstep_min`, a stall, or an error-severity monitor, return an error.
+    pub partial: bool,
+    /// Threshold-crossing events to record.
+    /// Crossing times are collected in the `events` field of the result, keyed by event name.
+    pub events: Vec<Event>,
+    /// Monitors, checked at each accepted time-point.
+    /// First violations are collected in the `violations` field of the result, keyed by monitor name,
+    /// or fail the simulation for `Severity::Error` monitors.
+    pub monitors: Vec<Monitor>,
+    /// Streaming callback. If set, each accepted time-point is passed to it rather than stored.
+    /// See `TranCallback`.
+    pub stream: Option<TranCallback>,
+}
+/// Transient Streaming Callback
+/// Called with the time and variable-values (ordered as `TranResult.signals`) of each accepted time-point,
+/// e.g. to compute running measurements or write to disk without holding the entire waveform in memory.
+/// Results of streamed runs have no waveforms, but include events, saved powers, and any failure.
+/// Output `grid`s do not apply.
+pub struct TranCallback(pub Box<dyn FnMut(f64, &[f64])>);
+impl TranCallback {
+    pub fn new(f: impl FnMut(f64, &[f64]) + 'static) -> Self {
+        Self(Box::new(f))
+    }
+}
+impl std::fmt::Debug for TranCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TranCallback")
+    }
+}
+/// Transient Output Time-Grid
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum TranGrid {
+    /// Each internal, adaptively-chosen time-point
+    #[default]
+    Native,
+    /// Linear steps of `tstep` through `tlin`, followed by `npts` logarithmically-spaced points through `tstop`.
+    /// Results are interpolated from the internal time-points.
+    Log { tlin: f64, npts: usize },
+}
+impl TranGrid {
+    /// Output time-points for a simulation of `tstep` through `tstop`, or `None` for native time-points
+    fn times(&self, tstep: f64, tstop: f64) -> SpResult<Option<Vec<f64>>> {
+        match *self {
+            TranGrid::Native => Ok(None),
+            TranGrid::Log { tlin, npts } => {
+                if tlin <= 0.0 || tlin >= tstop || npts == 0 || tstep <= 0.0 {
+                    return Err(sperror("Invalid Logarithmic Transient Grid"));
+                }
+                let nlin = (tlin / tstep).round() as usize;
+                let mut times: Vec<f64> = (0..nlin).map(|k| tlin * k as f64 / nlin as f64).collect();
+                let ratio = tstop / tlin;
+                times.extend((0..=npts).map(|k| tlin * ratio.powf(k as f64 / npts as f64)));
+                Ok(Some(times))
+            }
+        }
+    }
 }
 impl TranOptions {
+    /// Smallest time-step, per `tstep_min`
+    pub(crate) fn min_step(&self) -> f64 {
+        self.tstep_min.unwrap_or(self.tstep * 1e-6)
+    }
+    /// Largest adaptive time-step, per `tstep_max`
+    fn max_step(&self) -> f64 {
+        self.tstep_max.unwrap_or(self.tstop / 50.0)
+    }
     pub fn decode(bytes_: &[u8]) -> SpResult<Self> {
         // FIXME: remove
         use prost::Message;
@@ -477,6 +1657,22 @@ impl From<proto::TranOptions> for TranOptions {
             tstep: i.tstep,
             tstop: i.tstop,
             ic,
+            uic: false,
+            cancel: None,
+            save: vec![],
+            grid: TranGrid::Native,
+            tstep_min: None,
+            tstep_max: None,
+            lte_tol: None,
+            adaptive: false,
+            method: IntegMethod::default(),
+            stall_rejects: None,
+            window: None,
+            op: None,
+            partial: false,
+            events: vec![],
+            monitors: vec![],
+            stream: None,
         }
     }
 }
@@ -486,10 +1682,143 @@ impl Default for TranOptions {
     }
 }
 
-pub(crate) struct Tran<'a> {
-    solver: Solver<'a, f64>,
+/// Suggest transient `tstep` and `tstop`, from the RC time-constants of `ckt`.
+/// Each capacitor terminal's time-constant is its capacitance over the total resistor conductance at the node.
+/// Suggests a `tstep` of a hundredth of the shortest such time-constant, and a `tstop` of five times the longest.
+/// Heuristic: considers only resistors and capacitors, including those inside module instances and arrays.
+/// Module multipliers `m` are not applied.
+pub fn suggest_tran_options(ckt: &Ckt) -> SpResult<TranOptions> {
+    use crate::circuit::{Ci, Comp, Ri, Visit};
+    // Collect (value, p, n) of each resistor and capacitor, with hierarchical node-names
+    let (mut rs, mut cs) = (vec![], vec![]);
+    ckt.visit_flat(".", &mut |v| {
+        if let Visit::Comp { comp, net, .. } = v {
+            match comp {
+                Comp::R(Ri { g, p, n, .. }) => rs.push((g.abs(), net(p), net(n))),
+                Comp::C(Ci { c, p, n, .. }) => cs.push((*c, net(p), net(n))),
+                _ => (),
+            }
+        }
+        true
+    })?;
+    // Total conductance at each (non-ground) node
+    let mut gnode: HashMap<String, f64> = HashMap::new();
+    for (g, p, n) in rs.into_iter() {
+        for node in [p, n].iter().filter(|s| !s.is_empty()) {
+            *gnode.entry(node.clone()).or_insert(0.0) += g;
+        }
+    }
+    // Time-constants at each capacitor terminal
+    let mut taus: Vec<f64> = vec![];
+    for (c, p, n) in cs.into_iter() {
+        for node in [p, n].iter() {
+            if let Some(g) = gnode.get(node) {
+                if *g > 0.0 && c > 0.0 {
+                    taus.push(c / g);
+                }
+            }
+        }
+    }
+    if taus.is_empty() {
+        return Err(sperror("Cannot suggest TranOptions: no RC time-constants found"));
+    }
+    let tau_min = taus.iter().cloned().fold(f64::INFINITY, f64::min);
+    let tau_max = taus.iter().cloned().fold(0.0, f64::max);
+    Ok(TranOptions {
+        tstep: tau_min / 100.0,
+        tstop: 5.0 * tau_max,
+        ..Default::default()
+    })
+}
+
+/// Threshold-Crossing Direction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Edge {
+    Rising,
+    Falling,
+    Either,
+}
+impl Edge {
+    /// Interpolated time at which the segment from `(t0, v0)` to `(t1, v1)`
+    /// crosses `threshold` in our direction, or `None` if it does not.
+    pub fn crossing(&self, threshold: f64, t0: f64, v0: f64, t1: f64, v1: f64) -> Option<f64> {
+        let rising = v0 < threshold && v1 >= threshold;
+        let falling = v0 > threshold && v1 <= threshold;
+        let crossed = match self {
+            Edge::Rising => rising,
+            Edge::Falling => falling,
+            Edge::Either => rising || falling,
+        };
+        if !crossed {
+            return None;
+        }
+        let frac = (threshold - v0) / (v1 - v0);
+        Some(t0 + frac * (t1 - t0))
+    }
+}
+
+/// Transient Threshold-Crossing Event
+/// Records the (interpolated) times at which `signal` crosses `threshold`, in direction `edge`.
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub name: String,
+    pub signal: String,
+    pub threshold: f64,
+    pub edge: Edge,
+}
+
+/// Severity of a `Monitor` violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Severity {
+    /// Record the first violation in the result's `violations`, and continue
+    #[default]
+    Warning,
+    /// Fail the simulation upon the first violation
+    Error,
+}
+
+/// Transient Monitor
+/// Checks that `signal` stays within [`min`, `max`] at each accepted time-point. Either bound may be omitted.
+#[derive(Debug, Clone)]
+pub struct Monitor {
+    pub name: String,
+    pub signal: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub severity: Severity,
+}
+impl Monitor {
+    /// Boolean indication of whether `val` is out of bounds
+    fn violated(&self, val: f64) -> bool {
+        self.min.is_some_and(|m| val < m) || self.max.is_some_and(|m| val > m)
+    }
+}
+
+/// First violation of a `Monitor`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    pub time: f64,
+    pub value: f64,
+}
+
+/// Transient stopping condition, after a number of periods of a signal
+pub(crate) struct PeriodStop {
+    pub(crate) var: VarIndex,
+    pub(crate) threshold: f64,
+    pub(crate) periods: usize,
+}
+
+pub(crate) struct Tran<'a> {
+    pub(crate) solver: Solver<'a, f64>,
     state: TranState,
     pub(crate) opts: TranOptions,
+    pub(crate) stop: Option<PeriodStop>,
+    pub(crate) events: Vec<(VarIndex, Event)>,
+    pub(crate) monitors: Vec<(VarIndex, Monitor)>,
+    /// Initial-condition forcing nodes, excluded from truncation-error estimates
+    icvars: Vec<usize>,
+    /// Initial-condition values, by variable index, of both forced and forcing nodes. Starting points for `uic`.
+    icvals: Vec<(usize, f64)>,
 }
 
 impl<'a> Tran<'a> {
@@ -500,21 +1829,66 @@ impl<'a> Tran<'a> {
             solver,
             opts: args,
             state: TranState::default(),
+            stop: None,
+            events: vec![],
+            monitors: vec![],
+            icvars: vec![],
+            icvals: vec![],
         };
         for (node, val) in &ics {
             t.ic(node.clone(), *val);
         }
+        for ev in std::mem::take(&mut t.opts.events).into_iter() {
+            match t.solver.vars.find(&ev.signal) {
+                Some(var) => t.events.push((var, ev)),
+                None => t.signal_not_found(&ev.signal),
+            }
+        }
+        for mon in std::mem::take(&mut t.opts.monitors).into_iter() {
+            match t.solver.vars.find(&mon.signal) {
+                Some(var) => t.monitors.push((var, mon)),
+                None => t.signal_not_found(&mon.signal),
+            }
+        }
         t
     }
-    /// Create and set an initial condition on Node `n`, value `val`.
-    pub fn ic(&mut self, n: NodeRef, val: f64) {
+    /// Record a setup error for event or monitor signal `name`, which is not in our circuit
+    fn signal_not_found(&mut self, name: &str) {
+        let e = sperror(format!("Signal Not Found: {}", name));
+        self.solver.setup_error.get_or_insert(e);
+    }
+    /// Create and set an initial condition on Node `n`, value `val`, e.g. `t.ic("out", 0.0)`.
+    /// Nodes must resolve after elaboration. Hierarchical paths, e.g. `x1.out`,
+    /// address nodes and ports inside module instances.
+    pub fn ic<N: Into<NodeRef>>(&mut self, n: N, val: f64) {
         use crate::comps::{Resistor, Vsrc};
 
+        let n = n.into();
+        let var = match n {
+            NodeRef::Gnd => None,
+            _ => {
+                let name = n.to_string();
+                match self.solver.vars.find_node(&name) {
+                    Some(v) => Some(v),
+                    None => {
+                        let e = sperror(format!("Initial-Condition Node Not Found: {}", name));
+                        self.solver.setup_error.get_or_insert(e);
+                        return;
+                    }
+                }
+            }
+        };
+
         // Create two new variables: the forcing voltage, and current in its source
         let fnode = self.solver.vars.add(format!(".{}.vic", n.to_string()), VarKind::V);
+        self.icvars.push(fnode.0);
         let ivar = self.solver.vars.add(format!(".{}.iic", n.to_string()), VarKind::I);
 
-        let mut r = Resistor::new(1.0, Some(fnode), self.solver.vars.find_or_create(n)); // FIXME: rforce value
+        self.icvals.push((fnode.0, val));
+        if let Some(v) = var {
+            self.icvals.push((v.0, val));
+        }
+        let mut r = Resistor::new(IC_GFORCE, Some(fnode), var);
         r.create_matrix_elems(&mut self.solver.mat);
         self.solver.comps.push(r.into());
         self.state.ric.push(self.solver.comps.len() - 1);
@@ -523,21 +1897,194 @@ impl<'a> Tran<'a> {
         self.solver.comps.push(v.into());
         self.state.vic.push(self.solver.comps.len() - 1);
     }
+    /// Check that initial operating point `op` matches our variables
+    fn check_op(&self, op: &OpResult) -> SpResult<()> {
+        if !self.opts.ic.is_empty() {
+            return Err(sperror("Transient initial operating point cannot be combined with initial conditions"));
+        }
+        let names = &self.solver.vars.names;
+        if op.names.len() != names.len() || op.values.len() != names.len() {
+            return Err(sperror(format!(
+                "Initial operating point has {} variables, circuit has {}",
+                op.names.len(),
+                names.len()
+            )));
+        }
+        if let Some((a, b)) = op.names.iter().zip(names.iter()).find(|(a, b)| a != b) {
+            return Err(sperror(format!(
+                "Initial operating point variable {} does not match circuit variable {}",
+                a, b
+            )));
+        }
+        Ok(())
+    }
+    /// Resolve the `save` requests of our options into (key, component-index) pairs
+    fn saved_powers(&self) -> SpResult<Vec<(String, usize)>> {
+        let mut rv = vec![];
+        for key in self.opts.save.iter() {
+            let name = match key.strip_prefix("P(").and_then(|k| k.strip_suffix(")")) {
+                Some(n) => n,
+                None => return Err(sperror(format!("Invalid Save Request: {}", key))),
+            };
+            match self.solver.names.iter().position(|x| x == name) {
+                Some(idx) if self.solver.comps[idx].power(&self.solver.vars).is_some() => rv.push((key.clone(), idx)),
+                Some(_) => return Err(sperror(format!("Power Not Available For: {}", name))),
+                None => return Err(sperror(format!("Instance Not Found: {}", name))),
+            }
+        }
+        Ok(rv)
+    }
+    /// Estimated local truncation error of the step to solution `x` at time `t`,
+    /// following prior accepted solutions `prior`, oldest first.
+    /// Error of an order-`p` method is `C * dt^(p+1)` times each node-voltage's `(p+1)`th derivative,
+    /// for coefficient `C` per `IntegMethod::lte_coeff`, here estimating the derivative by divided differences.
+    /// Second-order methods lacking the history for their own estimate, i.e. on early steps, use that of `Gear1`.
+    /// Returns the largest error across all nodes, and the order of its estimate, or `None` without enough history.
+    fn lte(&self, prior: &[&(f64, Vec<f64>)], t: f64, x: &[f64]) -> Option<(f64, usize)> {
+        let method = match self.opts.method {
+            m if prior.len() > m.order() => m,
+            _ if prior.len() > 1 => IntegMethod::Gear1,
+            _ => return None,
+        };
+        let order = method.order();
+        let prior = &prior[prior.len() - order - 1..];
+        let mut ts: Vec<f64> = prior.iter().map(|p| p.0).collect();
+        ts.push(t);
+        let h = t - prior[order].0;
+        // Scale of the `(order+1)`th divided difference to the error: `C * h^(p+1) * (p+1)!`
+        let factorial = (1..=order + 1).product::<usize>() as f64;
+        let scale = method.lte_coeff() * h.powi(order as i32 + 1) * factorial;
+        let mut lte: f64 = 0.0;
+        for (k, kind) in self.solver.vars.kinds.iter().enumerate() {
+            if *kind != VarKind::V || self.icvars.contains(&k) {
+                continue;
+            }
+            // Divided-difference table, reduced in place
+            let mut dd: Vec<f64> = prior.iter().map(|p| p.1[k]).collect();
+            dd.push(x[k]);
+            for level in 1..=order + 1 {
+                for i in 0..dd.len() - level {
+                    dd[i] = (dd[i + 1] - dd[i]) / (ts[i + level] - ts[i]);
+                }
+            }
+            lte = lte.max((scale * dd[0]).abs());
+        }
+        Some((lte, order))
+    }
+    /// Check each monitor at time `t` and solution `x`, recording any first violations in `results`.
+    /// Returns the failure of any violated `Severity::Error` monitor.
+    fn check_monitors(&self, t: f64, x: &[f64], results: &mut TranResult) -> Option<TranFailure> {
+        for (var, mon) in self.monitors.iter() {
+            let value = x[var.0];
+            if !mon.violated(value) || results.violations.contains_key(&mon.name) {
+                continue;
+            }
+            if let Severity::Error = mon.severity {
+                return Some(TranFailure::Monitor {
+                    time: t,
+                    name: mon.name.clone(),
+                    signal: mon.signal.clone(),
+                    value,
+                });
+            }
+            log::warn!("Monitor {} violated at t={:e}: {}={:e}", mon.name, t, mon.signal, value);
+            results.violations.insert(mon.name.clone(), Violation { time: t, value });
+        }
+        None
+    }
+    /// Append the power of each of `saved` to `powers`, at our current solution
+    fn record_powers(&self, saved: &[(String, usize)], powers: &mut [Vec<f64>]) {
+        for ((_, idx), vals) in saved.iter().zip(powers.iter_mut()) {
+            vals.push(self.solver.comps[*idx].power(&self.solver.vars).unwrap_or(0.0));
+        }
+    }
+    /// Solve, storing each time-point in the returned `TranResult`, and interpolating onto any output `grid`
     pub fn solve(&mut self) -> SpResult<TranResult> {
+        if let Some(mut stream) = self.opts.stream.take() {
+            return self.solve_with(|t, x| (stream.0)(t, x));
+        }
+        let mut results = self.solve_into(&mut |results, t, x| results.push(t, x))?;
+        results.end();
+        if let Some(times) = self.opts.grid.times(self.opts.tstep, self.opts.tstop)? {
+            // Interpolate onto our output grid, within our window, and stopping at any cancellation or failure
+            let (tlo, thi) = self.opts.window.unwrap_or((0.0, self.opts.tstop));
+            let tend = results.cancelled.or_else(|| results.failure.as_ref().map(|f| f.time())).unwrap_or(thi);
+            let times: Vec<f64> = times.into_iter().filter(|t| *t >= tlo && *t <= tend).collect();
+            let mut gridded = results.resample(&times)?;
+            gridded.events = results.events;
+            gridded.cancelled = results.cancelled;
+            gridded.failure = results.failure;
+            gridded.debug = results.debug;
+            gridded.profile = results.profile;
+            results = gridded;
+        }
+        Ok(results)
+    }
+    /// Solve, calling `f` with the time and variable-values (ordered as `TranResult.signals`)
+    /// of each accepted time-point within our recording window, without storing them.
+    /// The returned `TranResult` includes everything but these waveforms: events, saved powers,
+    /// and any cancellation or failure. Output `grid`s apply only to `solve`.
+    /// Runs which end early fail, unless `partial` results are requested.
+    pub fn solve_with(&mut self, mut f: impl FnMut(f64, &[f64])) -> SpResult<TranResult> {
+        self.solve_into(&mut |_, t, x| f(t, x))
+    }
+    /// Shared implementation of `solve` and `solve_with`.
+    /// Calls `f` with the in-progress `TranResult`, time, and variable-values of each accepted time-point within our recording window.
+    fn solve_into(&mut self, f: &mut dyn FnMut(&mut TranResult, f64, &[f64])) -> SpResult<TranResult> {
         // Initialize results
         let mut results = TranResult::new();
         results.signals(&self.solver.vars);
+        for (_, ev) in self.events.iter() {
+            results.events.insert(ev.name.clone(), vec![]);
+        }
+        let saved = self.saved_powers()?;
+        let mut powers: Vec<Vec<f64>> = vec![vec![]; saved.len()];
+        // Recording window, and end time
+        let (tlo, thi) = match self.opts.window {
+            Some((tlo, thi)) if tlo >= thi => {
+                return Err(sperror(format!("Invalid Transient Window ({:e}, {:e})", tlo, thi)));
+            }
+            Some(w) => w,
+            None => (0.0, self.opts.tstop),
+        };
+        let tstop = self.opts.tstop.min(thi);
+        let in_window = |t: f64| t >= tlo && t <= thi;
 
-        // Solve for our initial condition
-        let tsoln = self.solver.solve(&AnalysisInfo::OP);
-        let tdata = match tsoln {
-            Ok(x) => x,
-            Err(e) => {
-                println!("Failed to find initial solution");
-                return Err(e);
+        // Solve for our initial condition, or use the one provided
+        let tdata = match self.opts.op.take() {
+            Some(op) => {
+                self.check_op(&op)?;
+                self.solver.commit_at(&op.values);
+                op.values
+            }
+            None if self.opts.uic => {
+                if let Some(ref e) = self.solver.setup_error {
+                    return Err(e.clone());
+                }
+                let mut x = vec![0.0; self.solver.vars.len()];
+                for (k, val) in self.icvals.iter() {
+                    x[*k] = *val;
+                }
+                self.solver.commit_at(&x);
+                x
+            }
+            None => {
+                self.solver.initialize()?;
+                let tsoln = self.solver.solve(&AnalysisInfo::OP);
+                match tsoln {
+                    Ok(x) => x,
+                    Err(e) => {
+                        log::warn!("Failed to find initial solution");
+                        return Err(e);
+                    }
+                }
             }
         };
-        results.push(self.state.t, &tdata);
+        if in_window(self.state.t) {
+            f(&mut results, self.state.t, &tdata);
+            self.record_powers(&saved, &mut powers);
+        }
+        results.failure = self.check_monitors(self.state.t, &tdata, &mut results);
 
         // Update initial-condition sources and resistances
         // FIXME: whether to change the voltages
@@ -545,33 +2092,216 @@ impl<'a> Tran<'a> {
             self.solver.comps[*c].update(0.0);
         }
         for c in self.state.ric.iter() {
-            self.solver.comps[*c].update(1e-9);
+            self.solver.comps[*c].update(IC_GRELEASE);
         }
 
         let mut tpoint: usize = 0;
         let max_tpoints: usize = 1e9 as usize;
+        let mut crossings: usize = 0;
+        let mut prev = (self.state.t, tdata);
+        // Accepted points preceding `prev`, oldest first, for truncation-error estimates
+        let mut hist: Vec<(f64, Vec<f64>)> = vec![];
+        let tstep_min = self.opts.min_step();
+        let (tstep_max, lte_tol) = (self.opts.max_step(), self.opts.lte_tol.unwrap_or(1e-3));
+        // Stall detection: rejections, by cause, since last advancing a full `tstep`
+        let stall_rejects = self.opts.stall_rejects.unwrap_or(100);
+        let mut stall_start = prev.0;
+        let mut rejects: HashMap<TranLimit, usize> = HashMap::new();
         self.state.t = self.opts.tstep;
         self.state.dt = self.opts.tstep;
-        while self.state.t < self.opts.tstop && tpoint < max_tpoints {
+        self.state.dt_prev = 0.0;
+        self.state.ni = self.opts.method;
+        while self.state.t < tstop && tpoint < max_tpoints && results.failure.is_none() {
+            // Check for cancellation
+            if let Some(cancel) = &self.opts.cancel {
+                if cancel.load(Ordering::Relaxed) {
+                    results.cancelled = Some(prev.0);
+                    break;
+                }
+            }
             let aninfo = AnalysisInfo::TRAN(&self.opts, &self.state);
             let tsoln = self.solver.solve(&aninfo);
             let tdata = match tsoln {
                 Ok(x) => x,
-                Err(e) => {
-                    println!("Failed at t={}", self.state.t);
-                    return Err(e);
+                Err(_) => {
+                    // Cut the step and retry from the last accepted point, down to `tstep_min`
+                    let cause = if self.solver.switched { TranLimit::Switch } else { TranLimit::Convergence };
+                    log::debug!("Rejected step at t={:e}, dt={:e}: {:?}", prev.0, self.state.dt, cause);
+                    self.state.dt /= 2.0;
+                    if self.state.dt < tstep_min {
+                        results.failure = Some(TranFailure::StepMin {
+                            time: prev.0,
+                            dt: self.state.dt,
+                            cause,
+                        });
+                        break;
+                    }
+                    // Or until steps alternate between acceptance and rejection, without making progress
+                    *rejects.entry(cause).or_insert(0) += 1;
+                    if rejects.values().sum::<usize>() > stall_rejects {
+                        let mut rejects: Vec<(TranLimit, usize)> = rejects.into_iter().collect();
+                        rejects.sort_by_key(|(c, _)| format!("{:?}", c));
+                        results.failure = Some(TranFailure::Stall {
+                            time: prev.0,
+                            since: stall_start,
+                            rejects,
+                        });
+                        break;
+                    }
+                    self.solver.vars.values = prev.1.clone();
+                    self.state.t = prev.0 + self.state.dt;
+                    continue;
                 }
             };
-            results.push(self.state.t, &tdata);
+            // Adaptive steps: estimate truncation error, and retry any steps with too much, while they can still be cut.
+            // Step-sizes scale with the error to the power of one over the method's order plus one, with some margin.
+            let mut grow = 2.0;
+            if self.opts.adaptive {
+                let prior: Vec<&(f64, Vec<f64>)> = hist.iter().chain(std::iter::once(&prev)).collect();
+                if let Some((lte, order)) = self.lte(&prior, self.state.t, &tdata) {
+                    let scale = if lte > 0.0 { 0.9 * (lte_tol / lte).powf(1.0 / (order + 1) as f64) } else { 2.0 };
+                    if lte > lte_tol && self.state.dt / 2.0 >= tstep_min {
+                        log::debug!("Rejected step at t={:e}, dt={:e}: truncation error {:e}", prev.0, self.state.dt, lte);
+                        self.state.dt = (self.state.dt * scale.max(0.25)).max(tstep_min);
+                        self.solver.vars.values = prev.1.clone();
+                        self.state.t = prev.0 + self.state.dt;
+                        continue;
+                    }
+                    grow = scale.min(2.0);
+                }
+            }
+            self.solver.commit();
+            if in_window(self.state.t) {
+                f(&mut results, self.state.t, &tdata);
+                self.record_powers(&saved, &mut powers);
+            }
+
+            results.failure = self.check_monitors(self.state.t, &tdata, &mut results);
+            // Record any threshold-crossing events
+            for (var, ev) in self.events.iter() {
+                let (v0, v1) = (prev.1[var.0], tdata[var.0]);
+                if let Some(t) = ev.edge.crossing(ev.threshold, prev.0, v0, self.state.t, v1) {
+                    results.events.entry(ev.name.clone()).or_default().push(t);
+                }
+            }
+            // Check for any periodic stopping condition
+            if let Some(stop) = &self.stop {
+                let (v0, v1) = (prev.1[stop.var.0], tdata[stop.var.0]);
+                if Edge::Rising.crossing(stop.threshold, prev.0, v0, self.state.t, v1).is_some() {
+                    crossings += 1;
+                    // Periods are measured between crossings, so stop on the one following the last
+                    if crossings > stop.periods {
+                        break;
+                    }
+                }
+            }
+            hist.push(std::mem::replace(&mut prev, (self.state.t, tdata)));
+            if hist.len() > 2 {
+                hist.remove(0);
+            }
+            if prev.0 >= stall_start + self.opts.tstep {
+                stall_start = prev.0;
+                rejects.clear();
+            }
 
-            // self.state.ni = NumericalIntegration::TRAP; // FIXME!
             tpoint += 1;
-            self.state.t += self.opts.tstep;
+            self.state.dt_prev = self.state.dt;
+            self.state.dt = if self.opts.adaptive {
+                (grow * self.state.dt).max(tstep_min).min(tstep_max)
+            } else {
+                // Grow back towards `tstep` after any cuts
+                (2.0 * self.state.dt).min(self.opts.tstep)
+            };
+            self.state.t += self.state.dt;
+        }
+        for ((key, _), vals) in saved.into_iter().zip(powers) {
+            results.map.insert(key, vals);
+        }
+        results.debug = self.solver.debug_info();
+        results.profile = self.solver.profile.clone();
+        match results.failure {
+            Some(ref f) if !self.opts.partial => Err(sperror(f.to_string())),
+            _ => Ok(results),
+        }
+    }
+    /// Re-run from initial conditions `ic`, through time `tstop`.
+    /// Values in `ic` are ordered as our initial-condition nodes.
+    fn rerun(&mut self, ic: &[f64], tstop: f64) -> SpResult<TranResult> {
+        // Restore our initial-condition sources and resistances
+        for (c, val) in self.state.vic.iter().zip(ic.iter()) {
+            self.solver.comps[*c].update(*val);
+        }
+        for c in self.state.ric.iter() {
+            self.solver.comps[*c].update(IC_GFORCE);
+        }
+        self.state.t = 0.0;
+        self.opts.tstop = tstop;
+        let res = self.solve()?;
+        match res.failure {
+            Some(f) => Err(sperror(f.to_string())),
+            None => Ok(res),
         }
-        results.end();
-        Ok(results)
     }
 }
+/// Leading bytes of the binary `TranResult` format
+const TRAN_BINARY_MAGIC: &[u8; 8] = b"SP21TRAN";
+/// Version of the binary `TranResult` format. Version 1 lacks the list of current-signals.
+const TRAN_BINARY_VERSION: u32 = 2;
+/// Write the header of a Berkeley SPICE ASCII rawfile, through its `Values:` line.
+/// Each of `vars` is a (name, type) pair, e.g. `("out", "voltage")`.
+fn rawfile_header<W: std::io::Write>(
+    w: &mut W,
+    date: &str,
+    plotname: &str,
+    flags: &str,
+    vars: &[(String, &str)],
+    npoints: usize,
+) -> std::io::Result<()> {
+    writeln!(w, "Title: spice21")?;
+    writeln!(w, "Date: {}", date)?;
+    writeln!(w, "Plotname: {}", plotname)?;
+    writeln!(w, "Flags: {}", flags)?;
+    writeln!(w, "No. Variables: {}", vars.len())?;
+    writeln!(w, "No. Points: {}", npoints)?;
+    writeln!(w, "Variables:")?;
+    for (k, (name, kind)) in vars.iter().enumerate() {
+        writeln!(w, "\t{}\t{}\t{}", k, name, kind)?;
+    }
+    writeln!(w, "Values:")
+}
+/// Present UTC date and time, in the rawfile `Date:` format, e.g. `Sat Oct 17 09:30:00 2026`
+fn rawfile_date() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    rawfile_date_at(secs)
+}
+/// UTC date and time `secs` after the Unix epoch, in the rawfile `Date:` format
+pub(crate) fn rawfile_date_at(secs: u64) -> String {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let (days, rem) = ((secs / 86400) as i64, secs % 86400);
+    // Civil date from days since the epoch, counting in 400-year eras starting each March 1
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{} {} {:2} {:02}:{:02}:{:02} {}",
+        DAYS[(days % 7) as usize],
+        MONTHS[(month - 1) as usize],
+        day,
+        rem / 3600,
+        rem / 60 % 60,
+        rem % 60,
+        year
+    )
+}
 /// # TranResult
 /// In-Memory Store for transient data
 #[derive(Default, Serialize, Deserialize)]
@@ -580,6 +2310,73 @@ pub struct TranResult {
     pub time: Vec<f64>,
     pub data: Vec<Vec<f64>>,
     pub map: HashMap<String, Vec<f64>>,
+    pub profile: Option<Profile>,
+    #[serde(default)]
+    pub events: HashMap<String, Vec<f64>>,
+    /// First violation of each `Monitor`, keyed by monitor name
+    #[serde(default)]
+    pub violations: HashMap<String, Violation>,
+    /// Time of the last result, if cancelled before completion
+    #[serde(default)]
+    pub cancelled: Option<f64>,
+    /// Solver snapshot at the final time-point, for `debug_dump`
+    #[serde(default)]
+    pub debug: DebugInfo,
+    /// Reason the simulation ended early, if it did. Results run through `failure.time()`.
+    /// Only set for runs requesting `partial` results; others instead return an error.
+    #[serde(default)]
+    pub failure: Option<TranFailure>,
+    /// Names of each current (rather than node-voltage) signal, e.g. through voltage sources
+    #[serde(default)]
+    pub currents: Vec<String>,
+}
+/// Cause of a transient time-step being rejected, and hence cut
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TranLimit {
+    /// Newton iterations failed to converge
+    Convergence,
+    /// A component switched state, e.g. a `Vswitch` toggling
+    Switch,
+}
+/// Early end of a transient simulation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TranFailure {
+    /// Time-step fell below `tstep_min`, after the last accepted point at `time`
+    StepMin { time: f64, dt: f64, cause: TranLimit },
+    /// Rejected steps exceeded `stall_rejects` at `time`, having last advanced a full `tstep` at `since`
+    Stall {
+        time: f64,
+        since: f64,
+        rejects: Vec<(TranLimit, usize)>,
+    },
+    /// Error-severity monitor `name` was violated at `time`
+    Monitor { time: f64, name: String, signal: String, value: f64 },
+}
+impl TranFailure {
+    /// Time of the last accepted point, through which partial results run
+    pub fn time(&self) -> f64 {
+        match self {
+            TranFailure::StepMin { time, .. } | TranFailure::Stall { time, .. } | TranFailure::Monitor { time, .. } => *time,
+        }
+    }
+}
+impl std::fmt::Display for TranFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TranFailure::StepMin { time, dt, cause } => write!(f, "Time-step too small ({:e}) at t={:e}: {:?} failure", dt, time, cause),
+            TranFailure::Stall { time, since, rejects } => {
+                let rejects: Vec<String> = rejects.iter().map(|(c, n)| format!("{:?}: {}", c, n)).collect();
+                write!(
+                    f,
+                    "Transient Stall at t={:e}, last advanced at t={:e}. Rejected steps: {}",
+                    time,
+                    since,
+                    rejects.join(", ")
+                )
+            }
+            TranFailure::Monitor { time, name, signal, value } => write!(f, "Monitor {} Violated at t={:e}: {}={:e}", name, time, signal, value),
+        }
+    }
 }
 impl TranResult {
     pub fn new() -> Self {
@@ -588,16 +2385,26 @@ impl TranResult {
             time: vec![],
             data: vec![],
             map: HashMap::new(),
+            profile: None,
+            events: HashMap::new(),
+            violations: HashMap::new(),
+            cancelled: None,
+            debug: DebugInfo::default(),
+            failure: None,
+            currents: vec![],
         }
     }
     fn signals(&mut self, vars: &Variables<f64>) {
-        for name in vars.names.iter() {
+        for (name, kind) in vars.names.iter().zip(vars.kinds.iter()) {
             self.signals.push(name.to_string());
+            if let VarKind::I = kind {
+                self.currents.push(name.to_string());
+            }
         }
     }
-    fn push(&mut self, t: f64, vals: &Vec<f64>) {
+    fn push(&mut self, t: f64, vals: &[f64]) {
         self.time.push(t);
-        self.data.push(vals.clone());
+        self.data.push(vals.to_vec());
         // FIXME: filter out un-saved and internal variables
     }
     /// Simulation complete, re-org data into hash-map of signals
@@ -614,6 +2421,10 @@ impl TranResult {
     pub fn len(&self) -> usize {
         self.time.len()
     }
+    /// Text report of the solver state at the final time-point, for bug reports. See `DebugInfo`.
+    pub fn debug_dump(&self) -> String {
+        self.debug.report("Transient")
+    }
     /// Retrieve values of signal `name`
     pub fn get(&self, name: &str) -> SpResult<&Vec<f64>> {
         match self.map.get(name) {
@@ -621,6 +2432,308 @@ impl TranResult {
             None => Err(sperror(format!("Signal Not Found: {}", name))),
         }
     }
+    /// Write in our compact binary format. All values are little-endian.
+    ///
+    /// * Header: magic bytes `SP21TRAN`, a `u32` format version, then the names of each signal,
+    ///   followed by those of any other per-time-point quantities (e.g. saved powers),
+    ///   and those of the signals which are currents.
+    ///   Each list is a `u64` count, then a (`u64` byte-length, UTF-8 bytes) pair per name.
+    /// * Data: the time vector, then each signal and other quantity in header order.
+    ///   Each is a `u64` length followed by its `f64` values.
+    ///
+    /// Only waveforms are written; events, profiles, and debug info are not.
+    pub fn write_binary<W: std::io::Write>(&self, w: &mut W) -> SpResult<()> {
+        let extras = self.extra_keys();
+        w.write_all(TRAN_BINARY_MAGIC)?;
+        w.write_all(&TRAN_BINARY_VERSION.to_le_bytes())?;
+        for names in [&self.signals, &extras, &self.currents].iter() {
+            w.write_all(&(names.len() as u64).to_le_bytes())?;
+            for name in names.iter() {
+                w.write_all(&(name.len() as u64).to_le_bytes())?;
+                w.write_all(name.as_bytes())?;
+            }
+        }
+        let write_vals = |w: &mut W, vals: &[f64]| -> SpResult<()> {
+            w.write_all(&(vals.len() as u64).to_le_bytes())?;
+            for v in vals.iter() {
+                w.write_all(&v.to_le_bytes())?;
+            }
+            Ok(())
+        };
+        write_vals(w, &self.time)?;
+        for (i, _) in self.signals.iter().enumerate() {
+            let vals: Vec<f64> = self.data.iter().map(|row| row[i]).collect();
+            write_vals(w, &vals)?;
+        }
+        for key in extras.iter() {
+            write_vals(w, &self.map[key])?;
+        }
+        Ok(())
+    }
+    /// Write in the ASCII Berkeley SPICE rawfile format, as read by waveform viewers such as `gaw` and ngspice.
+    /// Variables are time, then each signal, then any other saved quantities (e.g. powers).
+    /// The header is dated with the present UTC time; see `to_rawfile_with_date` for reproducible output.
+    pub fn to_rawfile<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        self.to_rawfile_with_date(w, &rawfile_date())
+    }
+    /// Write in the ASCII rawfile format, as for `to_rawfile`.
+    /// `date` is written verbatim to the header's `Date:` line, e.g. `Sat Oct 17 09:30:00 2026`.
+    pub fn to_rawfile_with_date<W: std::io::Write>(&self, w: &mut W, date: &str) -> std::io::Result<()> {
+        let extras = self.extra_keys();
+        let kind = |name: &String| if self.currents.contains(name) { "current" } else { "voltage" };
+        let mut vars = vec![("time".to_string(), "time")];
+        vars.extend(self.signals.iter().map(|s| (s.clone(), kind(s))));
+        vars.extend(extras.iter().map(|s| (s.clone(), "power")));
+        rawfile_header(w, date, "Transient Analysis", "real", &vars, self.time.len())?;
+        for (k, t) in self.time.iter().enumerate() {
+            writeln!(w, " {}\t{:e}", k, t)?;
+            for v in self.data[k].iter().take(self.signals.len()) {
+                writeln!(w, "\t{:e}", v)?;
+            }
+            for key in extras.iter() {
+                writeln!(w, "\t{:e}", self.map[key][k])?;
+            }
+        }
+        Ok(())
+    }
+    /// Read a `TranResult` written by `write_binary`
+    pub fn read_binary<R: std::io::Read>(r: &mut R) -> SpResult<TranResult> {
+        use std::convert::TryFrom;
+        use std::io::Read;
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != TRAN_BINARY_MAGIC {
+            return Err(sperror("Invalid Binary TranResult"));
+        }
+        let mut buf4 = [0u8; 4];
+        r.read_exact(&mut buf4)?;
+        let version = u32::from_le_bytes(buf4);
+        if version != 1 && version != TRAN_BINARY_VERSION {
+            return Err(sperror(format!("Unsupported Binary TranResult Version: {}", version)));
+        }
+        let read_u64 = |r: &mut R| -> SpResult<usize> {
+            let mut buf = [0u8; 8];
+            r.read_exact(&mut buf)?;
+            usize::try_from(u64::from_le_bytes(buf)).map_err(|_| sperror("Invalid Binary TranResult Length"))
+        };
+        // Read `len` bytes. Lengths come from the input, so are read through `take`,
+        // allocating only as data arrives, and failing on any shortfall.
+        let read_bytes = |r: &mut R, len: usize| -> SpResult<Vec<u8>> {
+            let mut bytes = vec![];
+            r.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+            if bytes.len() != len {
+                return Err(sperror("Invalid Binary TranResult: unexpected end of data"));
+            }
+            Ok(bytes)
+        };
+        let read_names = |r: &mut R| -> SpResult<Vec<String>> {
+            let mut names = vec![];
+            for _ in 0..read_u64(r)? {
+                let len = read_u64(r)?;
+                let bytes = read_bytes(r, len)?;
+                names.push(String::from_utf8(bytes).map_err(|_| sperror("Invalid Binary TranResult Signal Name"))?);
+            }
+            Ok(names)
+        };
+        let read_vals = |r: &mut R| -> SpResult<Vec<f64>> {
+            let len = read_u64(r)?;
+            let nbytes = len.checked_mul(8).ok_or_else(|| sperror("Invalid Binary TranResult Length"))?;
+            let bytes = read_bytes(r, nbytes)?;
+            Ok(bytes.chunks_exact(8).map(|b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])).collect())
+        };
+        let signals = read_names(r)?;
+        let extras = read_names(r)?;
+        let currents = if version == 1 { vec![] } else { read_names(r)? };
+        let time = read_vals(r)?;
+        let mut cols = vec![];
+        for _ in signals.iter() {
+            let vals = read_vals(r)?;
+            if vals.len() != time.len() {
+                return Err(sperror("Invalid Binary TranResult: signal and time lengths differ"));
+            }
+            cols.push(vals);
+        }
+        let mut res = TranResult::new();
+        res.signals = signals;
+        res.currents = currents;
+        for (t, &tval) in time.iter().enumerate() {
+            let row: Vec<f64> = cols.iter().map(|c| c[t]).collect();
+            res.push(tval, &row);
+        }
+        res.end();
+        for key in extras.into_iter() {
+            let vals = read_vals(r)?;
+            res.map.insert(key, vals);
+        }
+        Ok(res)
+    }
+    /// Keys of `map` other than time and our signals, in sorted order
+    fn extra_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .map
+            .keys()
+            .filter(|k| k.as_str() != "time" && !self.signals.contains(k))
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+    /// Resample onto time-points `times`, via linear interpolation.
+    /// Points outside our time-range hold our first or last values.
+    pub fn resample(&self, times: &[f64]) -> SpResult<TranResult> {
+        if self.time.is_empty() {
+            return Err(sperror("Cannot resample empty TranResult"));
+        }
+        let last = self.time.len() - 1;
+        // Interpolation index and fraction for each of `times`
+        let points: Vec<(usize, f64)> = times
+            .iter()
+            .map(|&t| {
+                // Index of the first time-point at or beyond `t`
+                let i = self.time.partition_point(|&x| x < t);
+                if i == 0 {
+                    (0, 0.0)
+                } else if i > last {
+                    (last, 0.0)
+                } else {
+                    let (t0, t1) = (self.time[i - 1], self.time[i]);
+                    (i - 1, (t - t0) / (t1 - t0))
+                }
+            })
+            .collect();
+        let interp = |vals: &dyn Fn(usize) -> f64, (i, frac): (usize, f64)| {
+            if frac == 0.0 {
+                vals(i)
+            } else {
+                vals(i) + frac * (vals(i + 1) - vals(i))
+            }
+        };
+        let mut res = TranResult::new();
+        res.signals = self.signals.clone();
+        res.currents = self.currents.clone();
+        for (&t, &pt) in times.iter().zip(points.iter()) {
+            let vals: Vec<f64> = (0..self.signals.len()).map(|s| interp(&|i| self.data[i][s], pt)).collect();
+            res.push(t, &vals);
+        }
+        res.end();
+        // Also carry over any other per-time-point quantities, e.g. saved powers
+        for (key, vals) in self.map.iter() {
+            if !res.map.contains_key(key) && vals.len() == self.time.len() {
+                let rvals = points.iter().map(|&pt| interp(&|i| vals[i], pt)).collect();
+                res.map.insert(key.clone(), rvals);
+            }
+        }
+        Ok(res)
+    }
+    /// Fourier analysis of signal `name`, ala SPICE's `.four`.
+    /// Extracts the DC value and first `n_harm` harmonics of fundamental frequency `f0`,
+    /// over the last full period of the simulation.
+    pub fn fourier(&self, name: &str, f0: f64, n_harm: usize) -> SpResult<FourierResult> {
+        if f0 <= 0.0 || n_harm == 0 {
+            return Err(sperror("Invalid Fourier Analysis Options"));
+        }
+        if self.time.is_empty() {
+            return Err(sperror("Cannot Fourier-analyze empty TranResult"));
+        }
+        let period = 1.0 / f0;
+        let tend = self.time[self.time.len() - 1];
+        let tstart = tend - period;
+        if tstart < self.time[0] {
+            return Err(sperror("Fourier analysis requires a full period of data"));
+        }
+        // Interpolate a single period onto evenly-spaced points
+        let npts = 200.max(20 * n_harm);
+        let times: Vec<f64> = (0..npts).map(|k| tstart + period * k as f64 / npts as f64).collect();
+        let res = self.resample(&times)?;
+        let vals = res.get(name)?;
+
+        // And evaluate the discrete Fourier series at each harmonic
+        let dc = vals.iter().sum::<f64>() / npts as f64;
+        let mut mag = vec![];
+        let mut phase = vec![];
+        for h in 1..=n_harm {
+            let mut c = Complex::new(0.0, 0.0);
+            for (k, v) in vals.iter().enumerate() {
+                let theta = 2.0 * std::f64::consts::PI * (h * k) as f64 / npts as f64;
+                c += Complex::new(theta.cos(), -theta.sin()) * v;
+            }
+            c *= 2.0 / npts as f64;
+            mag.push(c.norm());
+            phase.push(c.arg().to_degrees());
+        }
+        // Total harmonic distortion, relative to the fundamental
+        let thd = if mag[0] > 0.0 {
+            mag[1..].iter().map(|m| m * m).sum::<f64>().sqrt() / mag[0]
+        } else {
+            0.0
+        };
+        Ok(FourierResult { f0, dc, mag, phase, thd })
+    }
+    /// Reductions of signal `name` over the time-window from `t0` to `t1`.
+    /// Values are linearly interpolated between time-points, including at the window edges,
+    /// so that the integral-based quantities are exact for piecewise-linear waveforms.
+    /// Applies to any saved per-time-point quantity, e.g. saved powers, as well as signals.
+    pub fn reduce(&self, name: &str, t0: f64, t1: f64) -> SpResult<Reductions> {
+        if self.time.is_empty() {
+            return Err(sperror("Cannot reduce empty TranResult"));
+        }
+        let (first, last) = (self.time[0], self.time[self.time.len() - 1]);
+        if t1 <= t0 || t0 < first || t1 > last {
+            return Err(sperror(format!(
+                "Invalid reduction window ({:e}, {:e}) for simulation from {:e} to {:e}",
+                t0, t1, first, last
+            )));
+        }
+        // Window edges, plus each time-point in between
+        let mut times = vec![t0];
+        times.extend(self.time.iter().filter(|&&t| t > t0 && t < t1));
+        times.push(t1);
+        let res = self.resample(&times)?;
+        let vals = match res.map.get(name) {
+            Some(v) => v,
+            None => return Err(sperror(format!("Signal Not Found: {}", name))),
+        };
+        // Trapezoidal integrals of the value and its square
+        let (mut integral, mut sq) = (0.0, 0.0);
+        for k in 1..times.len() {
+            let dt = times[k] - times[k - 1];
+            let (a, b) = (vals[k - 1], vals[k]);
+            integral += dt * (a + b) / 2.0;
+            // Exact for the linear segment from `a` to `b`
+            sq += dt * (a * a + a * b + b * b) / 3.0;
+        }
+        let span = t1 - t0;
+        Ok(Reductions {
+            mean: integral / span,
+            rms: (sq / span).sqrt(),
+            min: vals.iter().cloned().fold(f64::INFINITY, f64::min),
+            max: vals.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            integral,
+        })
+    }
+}
+/// Reductions of a transient quantity over a time-window. See `TranResult::reduce`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Reductions {
+    pub rms: f64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Time-integral, e.g. charge for a current, or energy for a power
+    pub integral: f64,
+}
+/// Fourier Analysis Result
+/// Harmonics are indexed from the fundamental, i.e. `mag[0]` is that of frequency `f0`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FourierResult {
+    pub f0: f64,
+    pub dc: f64,
+    /// Harmonic magnitudes
+    pub mag: Vec<f64>,
+    /// Harmonic phases, in degrees
+    pub phase: Vec<f64>,
+    /// Total Harmonic Distortion, as a fraction of the fundamental
+    pub thd: f64,
 }
 /// Maintain much (most?) of our original vector-result-format
 /// via enabling integer indexing
@@ -632,13 +2745,254 @@ impl Index<usize> for TranResult {
 }
 
 /// Transient Analysis
+/// Runs which end early, e.g. on failing to converge at `tstep_min`, return an error,
+/// unless `TranOptions.partial` requests their partial results, with `failure` set.
 pub fn tran(ckt: Ckt, opts: Option<Options>, args: Option<TranOptions>) -> SpResult<TranResult> {
     let o = if let Some(val) = opts { val } else { Options::default() };
     let a = if let Some(val) = args { val } else { TranOptions::default() };
     return Tran::new(ckt, o, a).solve();
 }
 
+/// Newton Initial-Guess Strategy
+/// Sets node-voltage values prior to the first iteration of DC and transient operating points.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum InitStrategy {
+    /// All node-voltages start at zero
+    #[default]
+    Zero,
+    /// Nodes driven by voltage sources start at their DC values,
+    /// and all others half-way between ground and the highest such rail
+    RailSplit,
+    /// Named node-voltages start at their values in the map; all others at zero.
+    /// Names may be hierarchical paths, such as `x1.out`.
+    Map(HashMap<String, f64>),
+}
+/// Newton Convergence Criteria
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConvergenceCriterion {
+    /// Converge on inter-iteration update magnitude (`dx`) only
+    Update,
+    /// Converge on KCL current residual only
+    Residual,
+    /// Require both update and residual convergence
+    Both,
+}
+impl Default for ConvergenceCriterion {
+    fn default() -> Self {
+        ConvergenceCriterion::Both
+    }
+}
+
+/// Transient Analysis, run for `periods` periods of signal `signame`.
+/// Periods are delimited by rising crossings of `threshold`.
+/// Simulation stops on the crossing ending the last period, or at `args.tstop`, whichever comes first.
+pub fn tran_periods(
+    ckt: Ckt,
+    opts: Option<Options>,
+    args: Option<TranOptions>,
+    signame: &str,
+    threshold: f64,
+    periods: usize,
+) -> SpResult<TranResult> {
+    let o = if let Some(val) = opts { val } else { Options::default() };
+    let a = if let Some(val) = args { val } else { TranOptions::default() };
+    let mut tr = Tran::new(ckt, o, a);
+    let var = match tr.solver.vars.find(signame) {
+        Some(v) => v,
+        None => return Err(sperror(format!("Signal Not Found: {}", signame))),
+    };
+    tr.stop = Some(PeriodStop { var, threshold, periods });
+    tr.solve()
+}
+
+/// Periodic Steady-State Analysis Options
+pub struct PssOptions {
+    /// Period, or initial guess thereof for autonomous circuits
+    pub period: f64,
+    /// Whether the circuit is autonomous (e.g. an oscillator), and its period is to be solved for
+    pub autonomous: bool,
+    /// Number of (guessed) periods of transient simulation before shooting
+    pub settle: usize,
+    /// Maximum end-state mismatch, in volts
+    pub tol: f64,
+    /// Maximum number of Newton iterations
+    pub max_iter: usize,
+}
+impl Default for PssOptions {
+    fn default() -> Self {
+        Self {
+            period: 0.0,
+            autonomous: true,
+            settle: 0,
+            tol: 1e-3,
+            max_iter: 20,
+        }
+    }
+}
+/// Periodic Steady-State Result
+pub struct PssResult {
+    /// Converged period
+    pub period: f64,
+    /// One-period waveforms
+    pub tran: TranResult,
+}
+
+/// Periodic Steady-State Analysis
+///
+/// Shooting-Newton solution for the periodic orbit of `ckt`.
+/// The state-variables are the nodes with initial conditions in `args.ic`,
+/// and their values serve as the initial guess.
+/// Each Newton iteration integrates one period, forms the sensitivity of the end-state
+/// to the initial-state by finite differences, and updates the initial-state.
+/// For autonomous circuits the period is also solved for, with the first state-node's
+/// initial value held fixed as a phase condition.
+pub fn pss(ckt: Ckt, opts: Option<Options>, args: Option<TranOptions>, pargs: PssOptions) -> SpResult<PssResult> {
+    let o = if let Some(val) = opts { val } else { Options::default() };
+    let a = if let Some(val) = args { val } else { TranOptions::default() };
+    let PssOptions {
+        mut period,
+        autonomous,
+        settle,
+        tol,
+        max_iter,
+    } = pargs;
+    if a.ic.is_empty() {
+        return Err(sperror("PSS requires initial conditions on its state nodes"));
+    }
+    if period <= 0.0 || a.tstep <= 0.0 {
+        return Err(sperror("PSS requires positive period and time-step"));
+    }
+    let names: Vec<String> = a.ic.iter().map(|(node, _)| node.to_string()).collect();
+    let mut x0: Vec<f64> = a.ic.iter().map(|(_, val)| *val).collect();
+    let tstep = a.tstep;
+    let mut tr = Tran::new(ckt, o, a);
+
+    // Values of each state-node at time `t`
+    let states = |res: &TranResult, t: f64| -> SpResult<Vec<f64>> {
+        let r = res.resample(&[t])?;
+        names.iter().map(|name| Ok(r.get(name)?[0])).collect()
+    };
+    // Simulate from `x0`, returning the initial state, and the state at each time in `times`
+    let shoot = |tr: &mut Tran, x0: &[f64], times: &[f64]| -> SpResult<(Vec<f64>, Vec<Vec<f64>>, TranResult)> {
+        let tstop = times.iter().cloned().fold(0.0, f64::max) + tstep;
+        let res = tr.rerun(x0, tstop)?;
+        let start = states(&res, 0.0)?;
+        let ends: SpResult<Vec<Vec<f64>>> = times.iter().map(|t| states(&res, *t)).collect();
+        Ok((start, ends?, res))
+    };
+
+    // Settle for a few periods, starting shooting from the resultant state
+    if settle > 0 {
+        let tsettle = settle as f64 * period;
+        let (_, ends, res) = shoot(&mut tr, &x0, &[tsettle])?;
+        x0 = ends[0].clone();
+        if autonomous {
+            // Refine our guesses from the settling waveform's last two rising mid-swing crossings,
+            // starting from the latter. This also keeps the first-state's phase condition near its steepest point.
+            let sig = res.get(&names[0])?;
+            let (lo, hi) = sig.iter().fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+            let mid = (lo + hi) / 2.0;
+            let mut crossings: Vec<f64> = vec![];
+            for k in 1..sig.len() {
+                if res.time[k] > tsettle {
+                    break;
+                }
+                if let Some(t) = Edge::Rising.crossing(mid, res.time[k - 1], sig[k - 1], res.time[k], sig[k]) {
+                    crossings.push(t);
+                }
+            }
+            if crossings.len() >= 2 {
+                let last = crossings[crossings.len() - 1];
+                period = last - crossings[crossings.len() - 2];
+                x0 = states(&res, last)?;
+            }
+        }
+    }
+    let n = x0.len();
+    let delta = 1e-3; // Initial-state perturbation, in volts
+    for _k in 0..max_iter {
+        // Baseline run, evaluated at and around the period
+        let (start, ends, res) = shoot(&mut tr, &x0, &[period, period - tstep, period + tstep])?;
+        let resid: Vec<f64> = (0..n).map(|i| ends[0][i] - start[i]).collect();
+        if resid.iter().all(|r| r.abs() < tol) {
+            // Converged. Return one period of waveforms.
+            let mut times: Vec<f64> = res.time.iter().cloned().filter(|t| *t < period).collect();
+            times.push(period);
+            return Ok(PssResult {
+                period,
+                tran: res.resample(&times)?,
+            });
+        }
+        // Jacobian columns, one per unknown.
+        // Autonomous circuits hold the first state fixed, and solve for the period instead.
+        let mut cols: Vec<Vec<f64>> = vec![];
+        let first = if autonomous { 1 } else { 0 };
+        for j in first..n {
+            let mut xp = x0.clone();
+            xp[j] += delta;
+            let (pstart, pends, _) = shoot(&mut tr, &xp, &[period])?;
+            cols.push((0..n).map(|i| (pends[0][i] - pstart[i] - resid[i]) / delta).collect());
+        }
+        if autonomous {
+            cols.push((0..n).map(|i| (ends[2][i] - ends[1][i]) / (2.0 * tstep)).collect());
+        }
+        // Solve for the Newton update
+        let mut mat = Matrix::<f64>::new();
+        for (j, col) in cols.iter().enumerate() {
+            for (i, val) in col.iter().enumerate() {
+                mat.add_element(i, j, *val);
+            }
+        }
+        let rhs: Vec<f64> = resid.iter().map(|r| -r).collect();
+        let mut du = mat.solve(rhs)?;
+        // Limit the state-update to a half-volt, and period-update to a fifth of a period
+        let max_dx = du[..n - first].iter().fold(0.0, |s: f64, v| s.max(v.abs()));
+        let mut scale = if max_dx > 0.5 { 0.5 / max_dx } else { 1.0 };
+        if autonomous && du[n - 1].abs() * scale > 0.2 * period {
+            scale = 0.2 * period / du[n - 1].abs();
+        }
+        for d in du.iter_mut() {
+            *d *= scale;
+        }
+        // And apply it
+        for j in first..n {
+            x0[j] += du[j - first];
+        }
+        if autonomous {
+            period += du[n - 1];
+        }
+    }
+    Err(sperror("PSS Convergence Failed"))
+}
+
+/// Transient Sensitivity Analysis
+///
+/// Finite-difference sensitivity of each signal in `base` to a parameter-change of `dparam`,
+/// where `perturbed` is the baseline circuit with its parameter incremented by `dparam`.
+/// The perturbed simulation is resampled onto the time-points of `base`.
+/// Returns a `TranResult` of sensitivity waveforms, `dV(t)/dparam`, for each signal.
+pub fn tran_sens(base: &TranResult, perturbed: Ckt, dparam: f64, opts: Option<Options>, args: Option<TranOptions>) -> SpResult<TranResult> {
+    if dparam == 0.0 {
+        return Err(sperror("Sensitivity requires a non-zero perturbation"));
+    }
+    let pert = tran(perturbed, opts, args)?.resample(&base.time)?;
+    let mut res = TranResult::new();
+    res.signals = base.signals.clone();
+    for (k, &t) in base.time.iter().enumerate() {
+        let mut vals: Vec<f64> = vec![];
+        for name in base.signals.iter() {
+            let p = pert.get(name)?[k];
+            let b = base.get(name)?[k];
+            vals.push((p - b) / dparam);
+        }
+        res.push(t, &vals);
+    }
+    res.end();
+    Ok(res)
+}
+
 /// Simulation Options
+#[derive(Debug)]
 pub struct Options {
     pub temp: f64,
     pub tnom: f64,
@@ -656,8 +3010,25 @@ pub struct Options {
     pub max_order: usize,
     pub pivot_abs_tol: f64,
     pub pivot_rel_tol: f64,
+    /// Scale factor of independent-source values in DC operating points, as ramped by source stepping
     pub src_factor: f64,
     pub diag_gmin: f64,
+    /// Number of gmin-stepping solves, should the DC operating point fail to converge. Zero disables stepping.
+    pub gmin_steps: usize,
+    /// Number of source-stepping solves, should the DC operating point fail to converge, including after any gmin stepping.
+    /// Zero disables stepping.
+    pub source_steps: usize,
+    pub convergence: ConvergenceCriterion,
+    pub profile: bool,
+    pub hier_separator: String,
+    /// Newton initial-guess strategy
+    pub init: InitStrategy,
+    /// Linear-only mode: reject nonlinear devices, and solve each point in a single step
+    pub linear: bool,
+    /// Process corner, selecting a set of model-parameter shifts defined via `Defs::add_corner`
+    pub corner: Option<String>,
+    /// Newton voltage limiting of nonlinear devices, per `comps::limiting`. Disabling is primarily for comparison and debug.
+    pub limiting: bool,
 }
 
 use crate::proto;
@@ -683,6 +3054,15 @@ impl From<proto::SimOptions> for Options {
             pivot_rel_tol: 1e-3,
             src_factor: 1.0,
             diag_gmin: 0.0,
+            gmin_steps: 10,
+            source_steps: 10,
+            convergence: ConvergenceCriterion::default(),
+            profile: false,
+            hier_separator: ".".into(),
+            init: InitStrategy::default(),
+            linear: false,
+            corner: None,
+            limiting: true,
         }
     }
 }
@@ -726,14 +3106,29 @@ pub struct AcResult {
     pub freq: Vec<f64>,
     pub data: Vec<Vec<Complex<f64>>>,
     pub map: HashMap<String, Vec<Complex<f64>>>,
+    pub profile: Option<Profile>,
+    /// Names of each current (rather than node-voltage) signal, e.g. through voltage sources
+    #[serde(default)]
+    pub currents: Vec<String>,
+    /// DC operating point linearized about
+    #[serde(skip)]
+    op: Option<OpResult>,
 }
 impl AcResult {
     fn new() -> Self {
         Self::default()
     }
+    /// DC operating point linearized about, including its device operating-points.
+    /// Present for results of `ac`, and absent for those deserialized.
+    pub fn operating_point(&self) -> Option<&OpResult> {
+        self.op.as_ref()
+    }
     fn signals<T>(&mut self, vars: &Variables<T>) {
-        for name in vars.names.iter() {
+        for (name, kind) in vars.names.iter().zip(vars.kinds.iter()) {
             self.signals.push(name.to_string());
+            if let VarKind::I = kind {
+                self.currents.push(name.to_string());
+            }
         }
     }
     fn push(&mut self, f: f64, vals: &Vec<Complex<f64>>) {
@@ -755,6 +3150,150 @@ impl AcResult {
     pub fn len(&self) -> usize {
         self.freq.len()
     }
+    /// Write in the ASCII Berkeley SPICE rawfile format, as for `TranResult::to_rawfile`.
+    /// Variables are frequency, then each signal, all written as complex (real, imaginary) pairs.
+    pub fn to_rawfile<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        self.to_rawfile_with_date(w, &rawfile_date())
+    }
+    /// Write in the ASCII rawfile format, as for `to_rawfile`, dated with `date`
+    pub fn to_rawfile_with_date<W: std::io::Write>(&self, w: &mut W, date: &str) -> std::io::Result<()> {
+        let mut vars = vec![("frequency".to_string(), "frequency")];
+        for s in self.signals.iter() {
+            vars.push((s.clone(), if self.currents.contains(s) { "current" } else { "voltage" }));
+        }
+        rawfile_header(w, date, "AC Analysis", "complex", &vars, self.freq.len())?;
+        for (k, f) in self.freq.iter().enumerate() {
+            writeln!(w, " {}\t{:e},{:e}", k, f, 0.0)?;
+            for v in self.data[k].iter() {
+                writeln!(w, "\t{:e},{:e}", v.re, v.im)?;
+            }
+        }
+        Ok(())
+    }
+    /// Group delay of signal `name`, i.e. `-dφ/dω`, in seconds, at each frequency.
+    /// Phase is unwrapped across the frequency grid, then differentiated by finite differences:
+    /// central at interior points, and one-sided at either end.
+    pub fn group_delay(&self, name: &str) -> SpResult<Vec<f64>> {
+        use std::f64::consts::PI;
+        let vals = match self.map.get(name) {
+            Some(v) => v,
+            None => return Err(sperror(format!("Signal Not Found: {}", name))),
+        };
+        if vals.len() < 2 {
+            return Err(sperror("Group delay requires at least two frequency points"));
+        }
+        // Unwrap phase, removing jumps of more than pi between adjacent points
+        let mut phase: Vec<f64> = Vec::with_capacity(vals.len());
+        for v in vals.iter() {
+            let mut p = v.arg();
+            if let Some(prev) = phase.last() {
+                p -= 2.0 * PI * ((p - prev) / (2.0 * PI)).round();
+            }
+            phase.push(p);
+        }
+        let omega: Vec<f64> = self.freq.iter().map(|f| 2.0 * PI * f).collect();
+        let last = vals.len() - 1;
+        let delay = (0..vals.len())
+            .map(|i| {
+                let (lo, hi) = (i.saturating_sub(1), (i + 1).min(last));
+                -(phase[hi] - phase[lo]) / (omega[hi] - omega[lo])
+            })
+            .collect();
+        Ok(delay)
+    }
+}
+
+/// Small-Signal Impedance, across frequency. See `ac_impedance`.
+#[derive(Debug, Default, Clone)]
+pub struct AcImpedance {
+    pub freq: Vec<f64>,
+    pub z: Vec<Complex<f64>>,
+}
+/// Driving-point impedance looking into node-pair `port`, across frequency.
+/// Injects a unit AC current into `port.0`, returning through `port.1`, and reads the resulting voltage across them.
+/// All other (top-level) AC sources are zeroed: voltage sources become AC shorts, current sources opens.
+pub fn ac_impedance(mut ckt: Ckt, port: (NodeRef, NodeRef), opts: Option<Options>, args: Option<AcOptions>) -> SpResult<AcImpedance> {
+    use crate::circuit::{Comp, Ii};
+    let name = "ac_impedance_probe";
+    if ckt.comps.iter().any(|c| c.name() == name) {
+        return Err(sperror(format!("Instance Name Reserved for Impedance Probe: {}", name)));
+    }
+    for comp in ckt.comps.iter_mut() {
+        match comp {
+            Comp::V(x) => x.acm = 0.0,
+            Comp::I(x) => x.acm = 0.0,
+            _ => (),
+        }
+    }
+    let (p, n) = port;
+    let (pname, nname) = (p.to_string(), n.to_string());
+    ckt.comps.push(Comp::I(Ii {
+        name: name.into(),
+        dc: 0.0,
+        acm: 1.0,
+        p,
+        n,
+    }));
+    let soln = ac(ckt, opts, args)?;
+    // Port voltage, with ground as zero
+    let v = |name: &str| -> SpResult<Vec<Complex<f64>>> {
+        if name.is_empty() {
+            return Ok(vec![Complex::zero(); soln.freq.len()]);
+        }
+        match soln.map.get(name) {
+            Some(v) => Ok(v.clone()),
+            None => Err(sperror(format!("Signal Not Found: {}", name))),
+        }
+    };
+    let (vp, vn) = (v(&pname)?, v(&nname)?);
+    let z = vp.iter().zip(vn.iter()).map(|(p, n)| p - n).collect();
+    Ok(AcImpedance { freq: soln.freq.clone(), z })
+}
+
+/// Linearized System `(G + sC) x = b`, around a DC operating point. See `linearize`.
+#[derive(Debug, Default, Clone)]
+pub struct LinearSystem {
+    /// Variable names, indexed by the rows and columns of `g` and `c`
+    pub names: Vec<String>,
+    /// Conductance matrix `G`, as (row, col, value) triplets
+    pub g: Vec<(usize, usize, f64)>,
+    /// Capacitance matrix `C`, as (row, col, value) triplets.
+    /// Inductances appear (negated) on the rows of their branch equations.
+    pub c: Vec<(usize, usize, f64)>,
+}
+impl LinearSystem {
+    /// Index of the variable named `name`
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+}
+/// Linearize `ckt` around its DC operating point, returning its conductance and capacitance matrices separately.
+/// For model-order reduction, eigen-analysis, and the like.
+/// Both are split from the AC system at unit radian frequency, i.e. `G + jC`, retaining only non-zero entries.
+pub fn linearize(ckt: Ckt, opts: Option<Options>) -> SpResult<LinearSystem> {
+    let mut solver = Solver::<f64>::new(ckt, opts.unwrap_or_default());
+    if let Err(e) = solver.solve(&AnalysisInfo::OP) {
+        return Err(sperror(format!("Linearization Failed: DCOP did not converge ({})", e)));
+    }
+    let mut solver = Solver::<Complex<f64>>::from(solver);
+    let (args, state) = (AcOptions::default(), AcState { omega: 1.0 });
+    solver.mat.reset();
+    solver.rhs = vec![Complex::zero(); solver.vars.len()];
+    solver.update(&AnalysisInfo::AC(&args, &state));
+
+    let mut sys = LinearSystem {
+        names: solver.vars.names.clone(),
+        ..Default::default()
+    };
+    for (row, col, val) in solver.mat.entries() {
+        if val.re != 0.0 {
+            sys.g.push((row, col, val.re));
+        }
+        if val.im != 0.0 {
+            sys.c.push((row, col, val.im));
+        }
+    }
+    Ok(sys)
 }
 
 /// AC Analysis
@@ -770,9 +3309,15 @@ pub fn ac(ckt: Ckt, opts: Option<Options>, args: Option<AcOptions>) -> SpResult<
     let opts = if let Some(val) = opts { val } else { Options::default() };
     let args = if let Some(val) = args { val } else { AcOptions::default() };
 
-    // Initial DCOP solver and solution
+    // Initial DCOP solver and solution.
+    // Nonlinear components linearize around it, so failure here is fatal to AC analysis.
     let mut solver = Solver::<f64>::new(ckt, opts);
-    let _dc_soln = solver.solve(&AnalysisInfo::OP)?;
+    if let Err(e) = solver.solve(&AnalysisInfo::OP) {
+        return Err(sperror(format!("AC Analysis Failed: DCOP did not converge ({})", e)));
+    }
+    // Retain it for reporting
+    let mut op = OpResult::from(solver.vars.clone());
+    op.debug = solver.debug_info();
 
     // Convert to an AC solver
     let mut solver = Solver::<Complex<f64>>::from(solver);
@@ -788,13 +3333,8 @@ pub fn ac(ckt: Ckt, opts: Option<Options>, args: Option<AcOptions>) -> SpResult<
     let mut results = AcResult::new();
     results.signals(&solver.vars);
 
-    // Set up frequency sweep
-    let mut f = args.fstart as f64;
-    let fstop = args.fstop as f64;
-    let fstep = (10.0).powf(f64::log10(fstop / f) / args.npts as f64);
-
     // Main Frequency Loop
-    while f <= fstop {
+    for f in ac_freqs(&args) {
         use std::f64::consts::PI;
         state.omega = 2.0 * PI * f;
         let an = AnalysisInfo::AC(&args, &state);
@@ -811,11 +3351,6 @@ pub fn ac(ckt: Ckt, opts: Option<Options>, args: Option<AcOptions>) -> SpResult<
         seq.serialize_element(&flat).unwrap();
         // AND push to our simple vector-data
         soln.push(fsoln);
-        // Last-iteration handling
-        if f == fstop {
-            break;
-        }
-        f = f64::min(f * fstep, fstop);
     }
     // Close up streaming results
     SerializeSeq::end(seq).unwrap();
@@ -828,5 +3363,200 @@ pub fn ac(ckt: Ckt, opts: Option<Options>, args: Option<AcOptions>) -> SpResult<
 
     // And return our results
     results.end();
+    results.profile = solver.profile;
+    results.op = Some(op);
     return Ok(results);
 }
+
+/// Logarithmically-spaced AC sweep frequencies, from `fstart` through `fstop`
+fn ac_freqs(args: &AcOptions) -> Vec<f64> {
+    let mut f = args.fstart as f64;
+    let fstop = args.fstop as f64;
+    let fstep = (10.0).powf(f64::log10(fstop / f) / args.npts as f64);
+    let mut freqs = vec![];
+    while f <= fstop {
+        freqs.push(f);
+        // Last-iteration handling
+        if f == fstop {
+            break;
+        }
+        f = f64::min(f * fstep, fstop);
+    }
+    freqs
+}
+
+/// Noise Analysis Results. See `noise`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct NoiseResult {
+    pub freq: Vec<f64>,
+    /// Output noise density, in V/sqrt(Hz)
+    pub onoise: Vec<f64>,
+    /// Input-referred noise density, i.e. `onoise` divided by the magnitude of `gain`
+    pub inoise: Vec<f64>,
+    /// AC transfer from the input source to the output
+    pub gain: Vec<Complex<f64>>,
+    /// Output noise density contributed by each instance, in V/sqrt(Hz)
+    pub contributions: HashMap<String, Vec<f64>>,
+}
+/// Small-Signal Noise Analysis
+/// Linearizes `ckt` around its DC operating point, and at each AC-sweep frequency
+/// sums the (uncorrelated) contributions of each Component's `load_noise` sources
+/// to the noise density across node-pair `output`.
+/// Independent source `input_src` provides a unit AC excitation, with all other (top-level) AC sources zeroed,
+/// from which the transfer `gain` and input-referred noise are derived.
+pub fn noise(mut ckt: Ckt, input_src: &str, output: (NodeRef, NodeRef), opts: Option<Options>, args: Option<AcOptions>) -> SpResult<NoiseResult> {
+    use std::f64::consts::PI;
+    let mut found = false;
+    for comp in ckt.comps.iter_mut() {
+        let acm = if comp.name() == input_src { 1.0 } else { 0.0 };
+        match comp {
+            Comp::V(x) => x.acm = acm,
+            Comp::I(x) => x.acm = acm,
+            _ => continue,
+        }
+        found |= acm != 0.0;
+    }
+    if !found {
+        return Err(sperror(format!("Noise Input Source Not Found: {}", input_src)));
+    }
+    let opts = opts.unwrap_or_default();
+    let args = args.unwrap_or_default();
+
+    let mut solver = Solver::<f64>::new(ckt, opts);
+    if let Err(e) = solver.solve(&AnalysisInfo::OP) {
+        return Err(sperror(format!("Noise Analysis Failed: DCOP did not converge ({})", e)));
+    }
+    // Output variables, with ground as `None`
+    let var = |node: &NodeRef| -> SpResult<Option<VarIndex>> {
+        match node {
+            NodeRef::Gnd => Ok(None),
+            _ => match solver.vars.find(node.to_string()) {
+                Some(v) => Ok(Some(v)),
+                None => Err(sperror(format!("Noise Output Node Not Found: {}", node.to_string()))),
+            },
+        }
+    };
+    let (outp, outn) = (var(&output.0)?, var(&output.1)?);
+    let vout = |x: &[Complex<f64>]| -> Complex<f64> {
+        let v = |i: Option<VarIndex>| i.map_or(Complex::zero(), |i| x[i.0]);
+        v(outp) - v(outn)
+    };
+
+    let mut solver = Solver::<Complex<f64>>::from(solver);
+    let mut state = AcState::default();
+    let mut result = NoiseResult::default();
+    let mut contributions: Vec<Vec<f64>> = vec![vec![]; solver.comps.len()];
+    for f in ac_freqs(&args) {
+        state.omega = 2.0 * PI * f;
+        solver.mat.reset();
+        solver.rhs = vec![Complex::zero(); solver.vars.len()];
+        solver.update(&AnalysisInfo::AC(&args, &state));
+        let gain = vout(&solver.mat.solve(solver.rhs.clone())?);
+
+        // Each noise source requires a solve, all re-using our factored matrix
+        let mut total = 0.0;
+        for (k, comp) in solver.comps.iter().enumerate() {
+            let mut psd = 0.0;
+            for src in comp.load_noise(f, &solver.opts).iter() {
+                let mut e = vec![Complex::zero(); solver.vars.len()];
+                if let Some(p) = src.p {
+                    e[p.0] += Complex::new(1.0, 0.0);
+                }
+                if let Some(n) = src.n {
+                    e[n.0] -= Complex::new(1.0, 0.0);
+                }
+                psd += vout(&solver.mat.solve(e)?).norm_sqr() * src.psd;
+            }
+            contributions[k].push(psd.sqrt());
+            total += psd;
+        }
+        result.freq.push(f);
+        result.onoise.push(total.sqrt());
+        result.gain.push(gain);
+    }
+    result.inoise = crate::noise::input_referred(&result.onoise, &result.gain)?;
+    for (k, c) in contributions.into_iter().enumerate() {
+        if c.iter().any(|v| *v != 0.0) {
+            result.contributions.insert(solver.names[k].clone(), c);
+        }
+    }
+    Ok(result)
+}
+
+/// Analysis Specification
+/// Serializable description of a single analysis, e.g. from a batch job-file, tagged by its `type`:
+///
+/// ```yaml
+/// {type: Tran, tstep: 1e-8, tstop: 1e-5, ic: {out: 0.0} }
+/// ```
+///
+/// Run via `run`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum AnalysisSpec {
+    /// Operating Point. See `dcop`.
+    Dcop,
+    /// DC Sweep of independent source `src`. See `dc_sweep`.
+    Dc { src: String, start: f64, stop: f64, step: f64 },
+    /// AC Analysis. See `AcOptions`.
+    Ac { fstart: usize, fstop: usize, npts: usize },
+    /// Transient Analysis, with initial conditions `ic` in the form {node: value}. See `TranOptions`.
+    Tran {
+        tstep: f64,
+        tstop: f64,
+        #[serde(default)]
+        ic: HashMap<String, f64>,
+    },
+    /// Noise Analysis, excited by independent source `src`, measured from node `out` to `out_ref` (default ground). See `noise`.
+    Noise {
+        src: String,
+        out: String,
+        #[serde(default)]
+        out_ref: Option<String>,
+        fstart: usize,
+        fstop: usize,
+        npts: usize,
+    },
+}
+/// Analysis Output, tagged by the `AnalysisSpec` which produced it
+pub enum AnalysisOutput {
+    Dcop(OpResult),
+    Dc(DcSweepResult),
+    Ac(AcResult),
+    Tran(TranResult),
+    Noise(NoiseResult),
+}
+/// Run the analysis described by `spec`, with default `Options`
+pub fn run(ckt: Ckt, spec: AnalysisSpec) -> SpResult<AnalysisOutput> {
+    match spec {
+        AnalysisSpec::Dcop => Ok(AnalysisOutput::Dcop(dcop(ckt, None)?)),
+        AnalysisSpec::Dc { src, start, stop, step } => {
+            let args = DcSweepOptions::range(&src, start, stop, step)?;
+            Ok(AnalysisOutput::Dc(dcsweep(ckt, None, args)?))
+        }
+        AnalysisSpec::Ac { fstart, fstop, npts } => Ok(AnalysisOutput::Ac(ac(ckt, None, Some(AcOptions { fstart, fstop, npts }))?)),
+        AnalysisSpec::Tran { tstep, tstop, ic } => {
+            use super::circuit::n;
+            let args = TranOptions {
+                tstep,
+                tstop,
+                ic: ic.into_iter().map(|(name, val)| (n(name), val)).collect(),
+                ..Default::default()
+            };
+            Ok(AnalysisOutput::Tran(tran(ckt, None, Some(args))?))
+        }
+        AnalysisSpec::Noise {
+            src,
+            out,
+            out_ref,
+            fstart,
+            fstop,
+            npts,
+        } => {
+            use super::circuit::n;
+            let output = (n(out), out_ref.map_or(NodeRef::Gnd, n));
+            let args = AcOptions { fstart, fstop, npts };
+            Ok(AnalysisOutput::Noise(noise(ckt, &src, output, None, Some(args))?))
+        }
+    }
+}
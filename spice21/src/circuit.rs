@@ -9,9 +9,11 @@
 //!
 
 use enum_dispatch::enum_dispatch;
+use std::collections::{HashMap, HashSet};
 
 use super::comps::mos::MosPorts;
 use super::defs::Defs;
+use crate::analysis::Options;
 use crate::{SpError, SpResult};
 
 use super::proto;
@@ -19,9 +21,11 @@ use super::proto::instance::Comp as CompProto;
 use super::proto::Circuit as CircuitProto;
 
 // Re-exports
+pub use super::proto::DeviceInstance as DeviceI;
 pub use super::proto::Diode as DiodeI;
 pub use super::proto::Module as ModuleDef;
 pub use super::proto::ModuleInstance as ModuleI;
+pub use super::proto::ModuleInstanceArray as ModuleArrayI;
 
 /// Node Reference
 #[derive(Debug, Clone)]
@@ -62,6 +66,7 @@ pub(crate) fn s<S: Into<String>>(from: S) -> String {
     from.into()
 }
 /// Voltage Source Instance
+#[derive(Debug)]
 pub struct Vi {
     pub name: String,
     pub vdc: f64,
@@ -70,6 +75,7 @@ pub struct Vi {
     pub n: NodeRef,
 }
 /// Current Source Instance
+#[derive(Debug)]
 pub struct Ii {
     pub name: String,
     pub dc: f64,
@@ -78,27 +84,159 @@ pub struct Ii {
     pub n: NodeRef,
 }
 /// Resistance (really conductance) Instance
+#[derive(Debug)]
 pub struct Ri {
     pub name: String,
     pub g: f64,
     pub p: NodeRef,
     pub n: NodeRef,
+    /// Monte Carlo tolerance, as a fraction of resistance. Zero for none.
+    pub tol: f64,
+    /// Monte Carlo distribution
+    pub dist: Dist,
+}
+/// Monte Carlo Distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dist {
+    /// Normal, with `tol` as its standard deviation
+    Normal,
+    /// Uniform, with `tol` as its half-width
+    Uniform,
 }
 /// Capacitor Instance
+#[derive(Debug)]
 pub struct Ci {
     pub name: String,
     pub c: f64,
+    /// Equivalent series resistance. Zero for none.
+    pub esr: f64,
+    pub p: NodeRef,
+    pub n: NodeRef,
+}
+/// Inductor Instance
+#[derive(Debug)]
+pub struct Li {
+    pub name: String,
+    pub l: f64,
+    /// Series resistance. Zero for none.
+    pub rser: f64,
     pub p: NodeRef,
     pub n: NodeRef,
 }
+/// Mutual Inductance Instance
+/// Couples inductors named `l1` and `l2`, in the same scope, by mutual inductance `k * sqrt(L1 * L2)`.
+#[derive(Debug)]
+pub struct Ki {
+    pub name: String,
+    pub l1: String,
+    pub l2: String,
+    pub k: f64,
+}
+/// Voltage-Controlled Current Source Instance
+/// Current from `p` to `n` is polynomial `poly` of the voltages across each `ctrl` pair.
+/// See `comps::Vccs` for coefficient ordering.
+#[derive(Debug)]
+pub struct Gi {
+    pub name: String,
+    pub poly: Vec<f64>,
+    pub p: NodeRef,
+    pub n: NodeRef,
+    pub ctrl: Vec<(NodeRef, NodeRef)>,
+}
+
+/// Voltage-Controlled Voltage Source Instance
+/// Sets `V(p) - V(n)` to `gain` times the voltage from `cp` to `cn`.
+#[derive(Debug)]
+pub struct Ei {
+    pub name: String,
+    pub gain: f64,
+    pub p: NodeRef,
+    pub n: NodeRef,
+    pub cp: NodeRef,
+    pub cn: NodeRef,
+}
+
+/// Behavioral Current Source Instance
+/// Current, flowing from `p` to `n` through the source, is expression `expr` of node-voltages.
+/// See `comps::behavioral` for its syntax.
+#[derive(Debug)]
+pub struct Bi {
+    pub name: String,
+    pub p: NodeRef,
+    pub n: NodeRef,
+    pub expr: String,
+}
+
+/// Voltage-Controlled Switch Instance
+/// Resistance from `p` to `n` is `ron` when on and `roff` when off.
+/// Turns on as the voltage from `cp` to `cn` rises above `von`, and off as it falls below `voff`.
+#[derive(Debug)]
+pub struct Si {
+    pub name: String,
+    pub p: NodeRef,
+    pub n: NodeRef,
+    pub cp: NodeRef,
+    pub cn: NodeRef,
+    pub ron: f64,
+    pub roff: f64,
+    pub von: f64,
+    pub voff: f64,
+}
 
 /// Mos Instance
+#[derive(Debug)]
 pub struct Mosi {
     pub(crate) name: String,             // Instance Name
     pub(crate) model: String,            // Model Name
     pub(crate) params: String,           // Instance Param-Set Name
     pub(crate) ports: MosPorts<NodeRef>, // Port Connections
 }
+/// `{:?}`-style text of map `m`, with entries sorted by key.
+/// `HashMap` iteration order differs between processes, and between otherwise-equal maps.
+fn sorted_debug<K: Ord + std::fmt::Debug, V: std::fmt::Debug>(m: &HashMap<K, V>) -> String {
+    let sorted: std::collections::BTreeMap<&K, &V> = m.iter().collect();
+    format!("{:?}", sorted)
+}
+/// Content-hashing text of a module instance
+fn module_inst_key(x: &ModuleI) -> String {
+    let (ports, params) = (sorted_debug(&x.ports), sorted_debug(&x.params));
+    format!("ModuleI {{ name: {:?}, module: {:?}, ports: {}, params: {} }}", x.name, x.module, ports, params)
+}
+/// Content-hashing text of a module instance array
+fn module_array_key(x: &ModuleArrayI) -> String {
+    let (ports, params) = (sorted_debug(&x.ports), sorted_debug(&x.params));
+    format!(
+        "ModuleArrayI {{ name: {:?}, module: {:?}, width: {}, ports: {}, params: {} }}",
+        x.name, x.module, x.width, ports, params
+    )
+}
+/// Content-hashing text of a plugin device instance
+fn device_inst_key(x: &DeviceI) -> String {
+    let (ports, params) = (sorted_debug(&x.ports), sorted_debug(&x.params));
+    format!("DeviceI {{ name: {:?}, kind: {:?}, ports: {}, params: {} }}", x.name, x.kind, ports, params)
+}
+/// Content-hashing text of a module definition, including each of its instances
+pub(crate) fn module_def_key(m: &ModuleDef) -> String {
+    let comps: Vec<String> = m
+        .comps
+        .iter()
+        .map(|inst| match &inst.comp {
+            Some(CompProto::X(x)) => module_inst_key(x),
+            Some(CompProto::Xa(x)) => module_array_key(x),
+            Some(CompProto::U(x)) => device_inst_key(x),
+            other => format!("{:?}", other),
+        })
+        .collect();
+    format!(
+        "ModuleDef {{ name: {:?}, ports: {:?}, signals: {:?}, comps: {:?}, params: {} }}",
+        m.name,
+        m.ports,
+        m.signals,
+        comps,
+        sorted_debug(&m.params)
+    )
+}
+
 ///
 /// # Component Enum
 ///
@@ -106,14 +244,23 @@ pub struct Mosi {
 /// From and Into methods for each variant are generated by `enum_dispatch` macros.
 ///
 #[enum_dispatch]
+#[derive(Debug)]
 pub enum Comp {
     V(Vi),
     I(Ii),
     R(Ri),
     C(Ci),
+    L(Li),
+    K(Ki),
+    G(Gi),
+    E(Ei),
+    S(Si),
+    B(Bi),
     D(DiodeI),
+    U(DeviceI),
     Mos(Mosi),
     Module(ModuleI),
+    ModuleArray(ModuleArrayI),
 }
 // The empty `CompTrait` allows the `enum_dispatch` macros to generate `From` and `Into`
 // between the Comp enum and each of its variants.
@@ -121,34 +268,165 @@ pub enum Comp {
 trait CompTrait {}
 
 impl Comp {
-    /// Replacement for deprecated original `V` enum variant
-    pub fn vdc<S: Into<String>>(name: S, vdc: f64, p: NodeRef, n: NodeRef) -> Comp {
+    /// Replacement for deprecated original `V` enum variant.
+    /// Node arguments, here and in the other constructors, accept `NodeRef`s or `&str` names, with `""` for ground.
+    pub fn vdc<S: Into<String>, P: Into<NodeRef>, N: Into<NodeRef>>(name: S, vdc: f64, p: P, n: N) -> Comp {
         Comp::V(Vi {
             name: name.into(),
             vdc,
             acm: 0.0,
-            p,
-            n,
+            p: p.into(),
+            n: n.into(),
         })
     }
-    pub fn idc<S: Into<String>>(name: S, dc: f64, p: NodeRef, n: NodeRef) -> Comp {
+    pub fn idc<S: Into<String>, P: Into<NodeRef>, N: Into<NodeRef>>(name: S, dc: f64, p: P, n: N) -> Comp {
         Comp::I(Ii {
             name: name.into(),
             dc,
             acm: 0.0,
-            p,
-            n,
+            p: p.into(),
+            n: n.into(),
+        })
+    }
+    pub fn r<S: Into<String>, P: Into<NodeRef>, N: Into<NodeRef>>(name: S, g: f64, p: P, n: N) -> Comp {
+        Comp::R(Ri {
+            name: name.into(),
+            g,
+            p: p.into(),
+            n: n.into(),
+            tol: 0.0,
+            dist: Dist::Normal,
         })
     }
-    pub fn r<S: Into<String>>(name: S, g: f64, p: NodeRef, n: NodeRef) -> Comp {
-        Comp::R(Ri { name: name.into(), g, p, n })
+    pub fn c<S: Into<String>, P: Into<NodeRef>, N: Into<NodeRef>>(name: S, c: f64, p: P, n: N) -> Comp {
+        Comp::C(Ci {
+            name: name.into(),
+            c,
+            esr: 0.0,
+            p: p.into(),
+            n: n.into(),
+        })
     }
-    pub fn c<S: Into<String>>(name: S, c: f64, p: NodeRef, n: NodeRef) -> Comp {
-        Comp::C(Ci { name: name.into(), c, p, n })
+    /// Instance Name
+    pub fn name(&self) -> &str {
+        match self {
+            Comp::V(x) => &x.name,
+            Comp::I(x) => &x.name,
+            Comp::R(x) => &x.name,
+            Comp::C(x) => &x.name,
+            Comp::L(x) => &x.name,
+            Comp::K(x) => &x.name,
+            Comp::G(x) => &x.name,
+            Comp::E(x) => &x.name,
+            Comp::S(x) => &x.name,
+            Comp::B(x) => &x.name,
+            Comp::D(x) => &x.name,
+            Comp::U(x) => &x.name,
+            Comp::Mos(x) => &x.name,
+            Comp::Module(x) => &x.name,
+            Comp::ModuleArray(x) => &x.name,
+        }
     }
-    /// Convert from protobuf-generated classes
+    /// Rename this instance
+    pub(crate) fn rename(&mut self, name: String) {
+        match self {
+            Comp::V(x) => x.name = name,
+            Comp::I(x) => x.name = name,
+            Comp::R(x) => x.name = name,
+            Comp::C(x) => x.name = name,
+            Comp::L(x) => x.name = name,
+            Comp::K(x) => x.name = name,
+            Comp::G(x) => x.name = name,
+            Comp::E(x) => x.name = name,
+            Comp::S(x) => x.name = name,
+            Comp::B(x) => x.name = name,
+            Comp::D(x) => x.name = name,
+            Comp::U(x) => x.name = name,
+            Comp::Mos(x) => x.name = name,
+            Comp::Module(x) => x.name = name,
+            Comp::ModuleArray(x) => x.name = name,
+        }
+    }
+    /// Instance-type prefix, used in generated names
+    fn prefix(&self) -> &'static str {
+        match self {
+            Comp::V(_) => "v",
+            Comp::I(_) => "i",
+            Comp::R(_) => "r",
+            Comp::C(_) => "c",
+            Comp::L(_) => "l",
+            Comp::K(_) => "k",
+            Comp::G(_) => "g",
+            Comp::E(_) => "e",
+            Comp::S(_) => "s",
+            Comp::B(_) => "b",
+            Comp::D(_) => "d",
+            Comp::U(_) => "u",
+            Comp::Mos(_) => "m",
+            Comp::Module(_) => "x",
+            Comp::ModuleArray(_) => "xa",
+        }
+    }
+    /// Text representation for content-hashing.
+    /// As `{:?}`, but with the entries of any map-valued fields sorted by key.
+    pub(crate) fn content_key(&self) -> String {
+        match self {
+            Comp::Module(x) => module_inst_key(x),
+            Comp::ModuleArray(x) => module_array_key(x),
+            Comp::U(x) => device_inst_key(x),
+            _ => format!("{:?}", self),
+        }
+    }
+    /// Nodes connected to this instance, in port order.
+    /// Module ports are ordered by port-name, and array connections with index-placeholders are skipped.
+    pub(crate) fn nodes(&self) -> Vec<NodeRef> {
+        let sorted_conns = |ports: &std::collections::HashMap<String, String>| {
+            let mut conns: Vec<(&String, &String)> = ports.iter().filter(|(_, v)| !v.contains("{i}")).collect();
+            conns.sort();
+            conns.into_iter().map(|(_, v)| n(v.clone())).collect()
+        };
+        match self {
+            Comp::V(x) => vec![x.p.clone(), x.n.clone()],
+            Comp::I(x) => vec![x.p.clone(), x.n.clone()],
+            Comp::R(x) => vec![x.p.clone(), x.n.clone()],
+            Comp::C(x) => vec![x.p.clone(), x.n.clone()],
+            Comp::L(x) => vec![x.p.clone(), x.n.clone()],
+            Comp::K(_) => vec![],
+            Comp::G(x) => {
+                let mut nodes = vec![x.p.clone(), x.n.clone()];
+                for (cp, cn) in x.ctrl.iter() {
+                    nodes.extend(vec![cp.clone(), cn.clone()]);
+                }
+                nodes
+            }
+            Comp::E(x) => vec![x.p.clone(), x.n.clone(), x.cp.clone(), x.cn.clone()],
+            Comp::S(x) => vec![x.p.clone(), x.n.clone(), x.cp.clone(), x.cn.clone()],
+            Comp::B(x) => {
+                let mut nodes = vec![x.p.clone(), x.n.clone()];
+                if let Ok(e) = crate::comps::behavioral::parse(&x.expr) {
+                    nodes.extend(e.nodes.into_iter().map(n));
+                }
+                nodes
+            }
+            Comp::D(x) => vec![n(x.p.clone()), n(x.n.clone())],
+            Comp::U(x) => sorted_conns(&x.ports),
+            Comp::Mos(x) => vec![x.ports.d.clone(), x.ports.g.clone(), x.ports.s.clone(), x.ports.b.clone()],
+            Comp::Module(x) => sorted_conns(&x.ports),
+            Comp::ModuleArray(x) => sorted_conns(&x.ports),
+        }
+    }
+    /// Convert from protobuf-generated classes.
+    /// Panics on invalid components, e.g. mismatched Vccs controls. See the fallible `from_proto`.
+    #[deprecated(note = "Use the fallible `Comp::from_proto`")]
     pub fn from(c: CompProto) -> Self {
-        match c {
+        match Self::from_proto(c) {
+            Ok(comp) => comp,
+            Err(e) => panic!("Invalid Component: {}", e.desc),
+        }
+    }
+    /// Convert from protobuf-generated classes
+    pub fn from_proto(c: CompProto) -> SpResult<Self> {
+        let comp = match c {
             CompProto::I(i) => {
                 let x = Ii {
                     name: i.name.into(),
@@ -160,11 +438,19 @@ impl Comp {
                 Comp::I(x)
             }
             CompProto::R(r) => {
+                // Distributions are named "normal" or "uniform". Empty defaults to "normal".
+                let dist = match r.dist.as_str() {
+                    "" | "normal" => Dist::Normal,
+                    "uniform" => Dist::Uniform,
+                    d => return Err(SpError::new(format!("Invalid Distribution for {}: {}", r.name, d))),
+                };
                 let x = Ri {
                     name: r.name.into(),
                     p: n(r.p),
                     n: n(r.n),
                     g: r.g,
+                    tol: r.tol,
+                    dist,
                 };
                 Comp::R(x)
             }
@@ -174,9 +460,65 @@ impl Comp {
                     p: n(c.p),
                     n: n(c.n),
                     c: c.c,
+                    esr: c.esr,
                 };
                 Comp::C(x)
             }
+            CompProto::L(l) => Comp::L(Li {
+                name: l.name,
+                l: l.l,
+                rser: l.rser,
+                p: n(l.p),
+                n: n(l.n),
+            }),
+            CompProto::K(k) => Comp::K(Ki {
+                name: k.name,
+                l1: k.l1,
+                l2: k.l2,
+                k: k.k,
+            }),
+            CompProto::G(g) => {
+                if g.cp.is_empty() || g.cp.len() != g.cn.len() {
+                    return Err(SpError::new(format!(
+                        "Vccs {} Requires Matching, Non-Empty Controls: {} cp, {} cn",
+                        g.name,
+                        g.cp.len(),
+                        g.cn.len()
+                    )));
+                }
+                Comp::G(Gi {
+                    name: g.name,
+                    poly: if g.poly.is_empty() { vec![0.0, g.gm] } else { g.poly },
+                    p: n(g.p),
+                    n: n(g.n),
+                    ctrl: g.cp.into_iter().zip(g.cn).map(|(cp, cn)| (n(cp), n(cn))).collect(),
+                })
+            }
+            CompProto::B(b) => Comp::B(Bi {
+                name: b.name,
+                p: n(b.p),
+                n: n(b.n),
+                expr: b.expr,
+            }),
+            CompProto::S(s) => Comp::S(Si {
+                name: s.name,
+                p: n(s.p),
+                n: n(s.n),
+                cp: n(s.cp),
+                cn: n(s.cn),
+                ron: s.ron,
+                roff: s.roff,
+                von: s.von,
+                voff: s.voff,
+            }),
+            CompProto::E(e) => Comp::E(Ei {
+                name: e.name,
+                gain: e.gain,
+                p: n(e.p),
+                n: n(e.n),
+                cp: n(e.cp),
+                cn: n(e.cn),
+            }),
             CompProto::V(v) => {
                 let vs = Vi {
                     name: v.name,
@@ -210,9 +552,349 @@ impl Comp {
                 })
             }
             CompProto::D(x) => Comp::D(x),
+            CompProto::U(x) => Comp::U(x),
             CompProto::X(x) => Comp::Module(x),
+            CompProto::Xa(x) => Comp::ModuleArray(x),
+        };
+        Ok(comp)
+    }
+}
+
+/// Item of an expanded hierarchy, as passed to `FlatVisitor`s. See `Ckt::visit_flat`.
+pub(crate) enum Visit<'a> {
+    /// Component `comp`, named hierarchically as `name`, with node-names mapped through `net`
+    Comp {
+        comp: &'a Comp,
+        name: &'a str,
+        net: &'a dyn Fn(&NodeRef) -> String,
+    },
+    /// Internal signal of an expanded module instance, named hierarchically
+    Signal(&'a str),
+}
+/// Visitor of an expanded hierarchy. Returns whether to expand each visited module instance or array.
+pub(crate) type FlatVisitor<'a> = dyn FnMut(Visit<'_>) -> bool + 'a;
+
+/// Quoted GraphViz identifier, escaping any quotes and backslashes in `s`
+fn dot_id(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Convert the instances of module-definition `mdef`
+fn module_comps(mdef: &ModuleDef) -> SpResult<Vec<Comp>> {
+    mdef.comps.iter().filter_map(|i| i.comp.clone()).map(Comp::from_proto).collect()
+}
+
+/// Check the component-specific constraints of each of `comps`:
+/// mutual inductances, and the expressions of behavioral sources.
+fn validate_comps(comps: &[Comp]) -> SpResult<()> {
+    for comp in comps.iter() {
+        if let Comp::B(b) = comp {
+            crate::comps::behavioral::parse(&b.expr)?;
+        }
+    }
+    validate_mutuals(comps)
+}
+
+/// Check each mutual inductance in `comps` has a coupling coefficient within [-1, 1],
+/// and couples two inductors also in `comps`.
+fn validate_mutuals(comps: &[Comp]) -> SpResult<()> {
+    let inductors: HashSet<&str> = comps
+        .iter()
+        .filter_map(|c| match c {
+            Comp::L(l) => Some(l.name.as_str()),
+            _ => None,
+        })
+        .collect();
+    for comp in comps.iter() {
+        if let Comp::K(k) = comp {
+            if !(-1.0..=1.0).contains(&k.k) {
+                return Err(SpError::new(format!("Mutual Inductance {} Coupling Out of Range: {}", k.name, k.k)));
+            }
+            for l in [&k.l1, &k.l2].iter() {
+                if !inductors.contains(l.as_str()) {
+                    return Err(SpError::new(format!("Mutual Inductance {} References Unknown Inductor: {}", k.name, l)));
+                }
+            }
         }
     }
+    Ok(())
+}
+
+/// Validate and normalize the `ports` of each MOS (`type: M`) instance in YAML `value`, recursively.
+/// Port-names are case-insensitive, and are lower-cased in place.
+/// Each instance must connect exactly the four terminals `d`, `g`, `s`, and `b`.
+fn normalize_mos_ports(value: &mut serde_yaml::Value) -> SpResult<()> {
+    use serde_yaml::Value;
+    match value {
+        Value::Sequence(seq) => {
+            for v in seq.iter_mut() {
+                normalize_mos_ports(v)?;
+            }
+        }
+        Value::Mapping(map) => {
+            if map.get(&Value::from("type")) == Some(&Value::from("M")) {
+                let name = match map.get(&Value::from("name")) {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => "(unnamed)".to_string(),
+                };
+                let ports = match map.get_mut(&Value::from("ports")) {
+                    Some(Value::Mapping(p)) => p,
+                    _ => return Err(SpError::new(format!("Mos Instance {}: Missing Port Map", name))),
+                };
+                let mut normalized = serde_yaml::Mapping::new();
+                for (key, conn) in ports.iter() {
+                    let key = match key {
+                        Value::String(k) => k.to_lowercase(),
+                        _ => return Err(SpError::new(format!("Mos Instance {}: Invalid Port {:?}", name, key))),
+                    };
+                    if !["d", "g", "s", "b"].contains(&key.as_str()) {
+                        return Err(SpError::new(format!("Mos Instance {}: Invalid Port `{}`", name, key)));
+                    }
+                    if normalized.insert(Value::from(key.clone()), conn.clone()).is_some() {
+                        return Err(SpError::new(format!("Mos Instance {}: Duplicate Port `{}`", name, key)));
+                    }
+                }
+                for key in ["d", "g", "s", "b"].iter() {
+                    if !normalized.contains_key(&Value::from(*key)) {
+                        return Err(SpError::new(format!("Mos Instance {}: Missing Port `{}`", name, key)));
+                    }
+                }
+                *ports = normalized;
+            }
+            for (_, v) in map.iter_mut() {
+                normalize_mos_ports(v)?;
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Normalize the controls of each VCCS (`type: G`) instance in YAML `value`, recursively.
+/// Single control-pairs may be given as scalar `cp` and `cn`, which are converted to one-element lists.
+/// Instances may specify a linear transconductance `gm` or a polynomial `poly`, but not both.
+fn normalize_vccs_ctrl(value: &mut serde_yaml::Value) -> SpResult<()> {
+    use serde_yaml::Value;
+    match value {
+        Value::Sequence(seq) => {
+            for v in seq.iter_mut() {
+                normalize_vccs_ctrl(v)?;
+            }
+        }
+        Value::Mapping(map) => {
+            if map.get(&Value::from("type")) == Some(&Value::from("G")) {
+                if map.contains_key(&Value::from("gm")) && map.contains_key(&Value::from("poly")) {
+                    let name = match map.get(&Value::from("name")) {
+                        Some(Value::String(s)) => s.clone(),
+                        _ => "(unnamed)".to_string(),
+                    };
+                    return Err(SpError::new(format!("Vccs Instance {}: Specifies Both `gm` and `poly`", name)));
+                }
+                for key in ["cp", "cn"].iter() {
+                    if let Some(v) = map.get_mut(&Value::from(*key)) {
+                        if let Value::String(_) = v {
+                            *v = Value::Sequence(vec![v.clone()]);
+                        }
+                    }
+                }
+            }
+            for (_, v) in map.iter_mut() {
+                normalize_vccs_ctrl(v)?;
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Field of a parseable component `type`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompField {
+    pub name: String,
+    /// Value type: one of "string", "number", "bool", "list", "map", or "message"
+    pub kind: String,
+    pub required: bool,
+}
+/// Parseable component `type`, and its fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompType {
+    pub name: String,
+    pub fields: Vec<CompField>,
+}
+
+/// List all component `type`s parseable from YAML (or JSON, TOML), and their fields.
+/// Fields are reflected from the serde schema of each (default-valued) proto-instance,
+/// and are required if parsing fails without them.
+pub fn comp_types() -> Vec<CompType> {
+    use serde_yaml::{Mapping, Value};
+    let defaults = vec![
+        CompProto::R(Default::default()),
+        CompProto::C(Default::default()),
+        CompProto::I(Default::default()),
+        CompProto::V(Default::default()),
+        CompProto::D(Default::default()),
+        CompProto::M(Default::default()),
+        CompProto::X(Default::default()),
+        CompProto::Xa(Default::default()),
+    ];
+    let mut rv = vec![];
+    for comp in defaults.into_iter() {
+        let map: Mapping = match serde_yaml::to_value(&comp) {
+            Ok(Value::Mapping(m)) => m,
+            _ => unreachable!("Instances serialize as mappings"),
+        };
+        let tag = Value::String("type".into());
+        let name = map[&tag].as_str().unwrap().to_string();
+        let mut fields = vec![];
+        for (key, val) in map.iter().filter(|(k, _)| **k != tag) {
+            let kind = match val {
+                Value::Null => "message",
+                Value::Bool(_) => "bool",
+                Value::Number(_) => "number",
+                Value::String(_) => "string",
+                Value::Sequence(_) => "list",
+                Value::Mapping(_) => "map",
+            };
+            // Check whether we can parse without this field
+            let mut without = map.clone();
+            without.remove(key);
+            let required = serde_yaml::from_value::<CompProto>(Value::Mapping(without)).is_err();
+            fields.push(CompField {
+                name: key.as_str().unwrap().to_string(),
+                kind: kind.into(),
+                required,
+            });
+        }
+        rv.push(CompType { name, fields });
+    }
+    rv
+}
+
+/// Independent Source Type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SourceKind {
+    V,
+    I,
+}
+/// Independent Source Summary
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceInfo {
+    pub name: String,
+    pub kind: SourceKind,
+    pub dc: f64,
+    pub acm: f64,
+}
+
+/// Topology-Check Finding, naming a node which would leave the DC operating-point matrix singular
+#[derive(Debug, Clone, PartialEq)]
+pub enum TopologyWarning {
+    /// Signal with no component connections
+    Unconnected(String),
+    /// Node connected only to capacitors. Requires an initial condition, or a DC path to ground.
+    CapacitorOnly(String),
+    /// Node with no DC path to ground, e.g. one reached only through current sources or MOS gates
+    NoDcPath(String),
+}
+impl TopologyWarning {
+    /// Name of the flagged node
+    pub fn node(&self) -> &str {
+        match self {
+            TopologyWarning::Unconnected(n) => n,
+            TopologyWarning::CapacitorOnly(n) => n,
+            TopologyWarning::NoDcPath(n) => n,
+        }
+    }
+}
+/// DC connectivity of a (flattened) circuit, as collected by `Ckt::check`.
+/// Nodes are grouped by union-find, with ground at index zero.
+struct Topology {
+    index: HashMap<String, usize>,
+    /// Node names, in order of first reference
+    nodes: Vec<String>,
+    /// Union-find parent of each node
+    parent: Vec<usize>,
+    /// Number of component connections to each node
+    conns: Vec<usize>,
+    /// Whether each node connects to anything other than capacitors
+    noncap: Vec<bool>,
+}
+impl Topology {
+    fn new() -> Self {
+        let mut t = Self {
+            index: HashMap::new(),
+            nodes: vec![],
+            parent: vec![],
+            conns: vec![],
+            noncap: vec![],
+        };
+        t.node(""); // Ground
+        t
+    }
+    /// Get or create the index of node `name`
+    fn node(&mut self, name: &str) -> usize {
+        if let Some(k) = self.index.get(name) {
+            return *k;
+        }
+        let k = self.nodes.len();
+        self.index.insert(name.to_string(), k);
+        self.nodes.push(name.to_string());
+        self.parent.push(k);
+        self.conns.push(0);
+        self.noncap.push(false);
+        k
+    }
+    /// Add the connections of primitive component `comp`, with node-names mapped through `net`
+    fn add(&mut self, comp: &Comp, net: &dyn Fn(&NodeRef) -> String) {
+        // Indices into `comp.nodes()` which conduct DC between one another
+        let joined: Vec<usize> = match comp {
+            Comp::Module(_) | Comp::ModuleArray(_) => return,
+            Comp::V(_) | Comp::R(_) | Comp::L(_) | Comp::E(_) | Comp::S(_) | Comp::D(_) => vec![0, 1],
+            Comp::Mos(_) => vec![0, 2, 3], // Drain, source, and bulk. Not the gate.
+            Comp::U(x) => (0..x.ports.len()).collect(),
+            Comp::C(_) | Comp::I(_) | Comp::G(_) | Comp::B(_) | Comp::K(_) => vec![],
+        };
+        let cap = matches!(comp, Comp::C(_));
+        let nodes: Vec<usize> = comp.nodes().iter().map(|n| self.node(&net(n))).collect();
+        for k in nodes.iter() {
+            self.conns[*k] += 1;
+            self.noncap[*k] |= !cap;
+        }
+        for pair in joined.windows(2) {
+            if let (Some(a), Some(b)) = (nodes.get(pair[0]), nodes.get(pair[1])) {
+                self.join(*a, *b);
+            }
+        }
+    }
+    fn find(&mut self, mut k: usize) -> usize {
+        while self.parent[k] != k {
+            self.parent[k] = self.parent[self.parent[k]];
+            k = self.parent[k];
+        }
+        k
+    }
+    fn join(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[a] = b;
+    }
+    /// Warnings for each node not DC-connected to ground
+    fn warnings(&mut self) -> Vec<TopologyWarning> {
+        let gnd = self.find(0);
+        let mut rv = vec![];
+        for k in 1..self.nodes.len() {
+            if self.find(k) == gnd {
+                continue;
+            }
+            let name = self.nodes[k].clone();
+            rv.push(if self.conns[k] == 0 {
+                TopologyWarning::Unconnected(name)
+            } else if !self.noncap[k] {
+                TopologyWarning::CapacitorOnly(name)
+            } else {
+                TopologyWarning::NoDcPath(name)
+            });
+        }
+        rv
+    }
 }
 
 ///
@@ -235,14 +917,303 @@ impl Ckt {
             defs: Defs::default(),
         }
     }
-    /// Create a Circuit from a vector of Components
-    pub fn from_comps(comps: Vec<Comp>) -> Self {
-        Self {
-            name: String::from(""),
-            signals: Vec::new(),
-            comps: comps,
-            defs: Defs::default(),
+    /// Create a top-level Circuit from Module `module` of `ckt`, e.g. for characterizing it standalone.
+    /// Includes a single instance of `module` named `x{module}`, with each port connected to a same-named signal,
+    /// plus each of `drivers`, typically sources connected to those signals.
+    /// All of `ckt`'s definitions are copied.
+    pub fn from_module(ckt: &Ckt, module: &str, drivers: Vec<Comp>) -> SpResult<Ckt> {
+        let ports = match ckt.defs.modules.store.get(module) {
+            Some(m) => m.read().ports.clone(),
+            None => return Err(SpError::new(format!("Module Not Found: {}", module))),
+        };
+        let inst = ModuleI {
+            name: format!("x{}", module),
+            module: module.to_string(),
+            ports: ports.iter().map(|p| (p.clone(), p.clone())).collect(),
+            params: HashMap::new(),
+        };
+        let mut rv = Self {
+            name: module.to_string(),
+            signals: ports,
+            defs: ckt.defs.copy(),
+            ..Self::new()
+        };
+        rv.add(Comp::Module(inst));
+        for comp in drivers.into_iter() {
+            rv.add(comp);
+        }
+        Ok(rv)
+    }
+    /// Create a Circuit from anything iterable over Components.
+    /// Top-level signals are inferred from each named Node, in order of first reference.
+    /// Components with empty or duplicate names are renamed, as in `add`.
+    pub fn from_comps<I: IntoIterator<Item = Comp>>(comps: I) -> Self {
+        let comps: Vec<Comp> = comps.into_iter().collect();
+        let mut signals: Vec<String> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        for comp in comps.iter() {
+            for node in comp.nodes() {
+                if let NodeRef::Name(name) = node {
+                    if seen.insert(name.clone()) {
+                        signals.push(name);
+                    }
+                }
+            }
+        }
+        let mut ckt = Self {
+            signals,
+            ..Self::new()
+        };
+        for comp in comps.into_iter() {
+            ckt.add(comp);
+        }
+        ckt
+    }
+    /// Check for name conflicts, between instances or between instances and signals.
+    /// Note Voltage Sources create a (current) Variable named by their instance name.
+    /// While distinct from any same-named signal, it is then only accessible as `I(name)`.
+    pub fn validate(&self) -> SpResult<()> {
+        let signals: HashSet<&str> = self.signals.iter().map(|s| s.as_str()).collect();
+        let mut names: HashSet<&str> = HashSet::new();
+        for comp in self.comps.iter() {
+            let name = comp.name();
+            if !names.insert(name) {
+                return Err(SpError::new(format!("Duplicate Instance Name: {}", name)));
+            }
+            if signals.contains(name) {
+                return Err(SpError::new(format!("Instance Name Conflicts with Signal: {}", name)));
+            }
+        }
+        validate_comps(&self.comps)
+    }
+    /// Strict validation: `validate`, plus checks for likely netlist mistakes.
+    /// Flags top-level MOS instances with bulk at ground but source elsewhere,
+    /// typically an unintended body effect. See `tie_bulks_to_source`.
+    pub fn validate_strict(&self) -> SpResult<()> {
+        self.validate()?;
+        let flagged = self.grounded_bulks();
+        if !flagged.is_empty() {
+            return Err(SpError::new(format!(
+                "MOS Bulk at Ground with Source Elsewhere: {}",
+                flagged.join(", ")
+            )));
+        }
+        Ok(())
+    }
+    /// Connect bulk to source for each MOS instance flagged by `validate_strict`.
+    /// Returns the names of those changed.
+    pub fn tie_bulks_to_source(&mut self) -> Vec<String> {
+        let flagged = self.grounded_bulks();
+        for comp in self.comps.iter_mut() {
+            if let Comp::Mos(m) = comp {
+                if matches!(m.ports.b, Gnd) && !matches!(m.ports.s, Gnd) {
+                    m.ports.b = m.ports.s.clone();
+                }
+            }
         }
+        flagged
+    }
+    /// Check DC connectivity prior to simulation.
+    /// Flags each node with no DC path to ground, which would otherwise surface as a singular matrix.
+    /// Module instances and arrays are expanded, with nodes named hierarchically as `x1.n`.
+    /// Plugin devices are assumed to conduct between all of their ports.
+    pub fn check(&self) -> SpResult<Vec<TopologyWarning>> {
+        self.check_with(&Options::default())
+    }
+    /// Check DC connectivity, as for `check`, naming hierarchical nodes with `opts.hier_separator`
+    pub fn check_with(&self, opts: &Options) -> SpResult<Vec<TopologyWarning>> {
+        let mut topo = Topology::new();
+        for signal in self.signals.iter() {
+            topo.node(signal);
+        }
+        self.visit_flat(&opts.hier_separator, &mut |v| {
+            match v {
+                Visit::Signal(signal) => {
+                    topo.node(signal);
+                }
+                Visit::Comp { comp, net, .. } => topo.add(comp, net),
+            }
+            true
+        })?;
+        Ok(topo.warnings())
+    }
+    /// Visit each component of the expanded hierarchy, along with a mapping of its node-names, and each internal signal.
+    /// Module instances and arrays are visited, then expanded should `visit` return `true`.
+    /// Nodes and instances are named hierarchically, joined by `sep`, as `x1.n` for `sep = "."`.
+    pub(crate) fn visit_flat(&self, sep: &str, visit: &mut FlatVisitor<'_>) -> SpResult<()> {
+        let top = |n: &NodeRef| n.to_string();
+        self.visit_comps(self.comps.iter().collect(), "", &top, sep, visit)
+    }
+    fn visit_comps(&self, comps: Vec<&Comp>, prefix: &str, net: &dyn Fn(&NodeRef) -> String, sep: &str, visit: &mut FlatVisitor<'_>) -> SpResult<()> {
+        for comp in comps.into_iter() {
+            let name = format!("{}{}", prefix, comp.name());
+            if !visit(Visit::Comp { comp, name: &name, net }) {
+                continue;
+            }
+            match comp {
+                Comp::Module(x) => self.visit_module(&x.module, &name, &x.ports, net, sep, visit)?,
+                Comp::ModuleArray(x) => {
+                    for i in 0..x.width as usize {
+                        let idx = i.to_string();
+                        let ports: HashMap<String, String> = x.ports.iter().map(|(k, v)| (k.clone(), v.replace("{i}", &idx))).collect();
+                        self.visit_module(&x.module, &format!("{}[{}]", name, i), &ports, net, sep, visit)?;
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+    /// Visit the signals and components of an instance of `module` named `name`, as for `visit_comps`.
+    fn visit_module(
+        &self,
+        module: &str,
+        name: &str,
+        ports: &HashMap<String, String>,
+        net: &dyn Fn(&NodeRef) -> String,
+        sep: &str,
+        visit: &mut FlatVisitor<'_>,
+    ) -> SpResult<()> {
+        let mdef = match self.defs.modules.store.get(module) {
+            Some(m) => m,
+            None => return Err(SpError::new(format!("Module Not Found: {}", module))),
+        };
+        let mdef = mdef.read();
+        // Ports connect to our nets; all other names are local to the instance
+        let inner = |n: &NodeRef| {
+            let s = n.to_string();
+            match ports.get(&s) {
+                Some(outer) if mdef.ports.contains(&s) => net(&NodeRef::Name(outer.clone())),
+                _ if s.is_empty() => s,
+                _ => format!("{}{}{}", name, sep, s),
+            }
+        };
+        for signal in mdef.signals.iter() {
+            visit(Visit::Signal(&format!("{}{}{}", name, sep, signal)));
+        }
+        let comps = module_comps(&mdef)?;
+        self.visit_comps(comps.iter().collect(), &format!("{}{}", name, sep), &inner, sep, visit)
+    }
+    /// Names of MOS instances with bulk at ground but source elsewhere
+    fn grounded_bulks(&self) -> Vec<String> {
+        let mut rv = vec![];
+        for comp in self.comps.iter() {
+            if let Comp::Mos(m) = comp {
+                if matches!(m.ports.b, Gnd) && !matches!(m.ports.s, Gnd) {
+                    rv.push(m.name.clone());
+                }
+            }
+        }
+        rv
+    }
+    /// List the top-level independent sources, in instance order.
+    /// Each can be swept by name, via `SweepParam::Instance`.
+    /// Sources within module definitions are not included.
+    pub fn independent_sources(&self) -> Vec<SourceInfo> {
+        let mut rv = vec![];
+        for comp in self.comps.iter() {
+            let (name, kind, dc, acm) = match comp {
+                Comp::V(v) => (&v.name, SourceKind::V, v.vdc, v.acm),
+                Comp::I(i) => (&i.name, SourceKind::I, i.dc, i.acm),
+                _ => continue,
+            };
+            rv.push(SourceInfo {
+                name: name.clone(),
+                kind,
+                dc,
+                acm,
+            });
+        }
+        rv
+    }
+    /// GraphViz (`dot`) representation, for visualizing connectivity.
+    /// Nets become graph-nodes, with ground named `gnd`. Two-terminal components become edges from `p` to `n`,
+    /// labeled with their name, type, and value. Other components become box-shaped graph-nodes, with an edge to each net.
+    /// With `expand`, module instances are replaced by their contents, named hierarchically as `x1.r1`.
+    /// Otherwise, and for instance arrays, each is a single box.
+    pub fn to_dot(&self, expand: bool) -> SpResult<String> {
+        self.to_dot_with(expand, &Options::default())
+    }
+    /// GraphViz representation, as for `to_dot`, naming hierarchical nets and instances with `opts.hier_separator`
+    pub fn to_dot_with(&self, expand: bool, opts: &Options) -> SpResult<String> {
+        let mut nets: Vec<String> = self.signals.clone();
+        let mut lines: Vec<String> = vec![];
+        self.visit_flat(&opts.hier_separator, &mut |v| {
+            let (comp, name, net) = match v {
+                Visit::Signal(signal) => {
+                    nets.push(signal.to_string());
+                    return true;
+                }
+                Visit::Comp { comp, name, net } => (comp, name, net),
+            };
+            if expand && matches!(comp, Comp::Module(_)) {
+                return true;
+            }
+            let gname = |n: &NodeRef| match net(n) {
+                s if s.is_empty() => "gnd".to_string(),
+                s => s,
+            };
+            let value = match comp {
+                Comp::V(x) => Some(format!("V, dc={}", x.vdc)),
+                Comp::I(x) => Some(format!("I, dc={}", x.dc)),
+                Comp::R(x) => Some(format!("R, g={}", x.g)),
+                Comp::C(x) => Some(format!("C, c={}", x.c)),
+                Comp::L(x) => Some(format!("L, l={}", x.l)),
+                Comp::D(x) => Some(format!("D, model={}", x.model)),
+                _ => None,
+            };
+            match value {
+                Some(value) => {
+                    let nodes = comp.nodes();
+                    let (p, n) = (gname(&nodes[0]), gname(&nodes[1]));
+                    let label = dot_id(&format!("{} ({})", name, value));
+                    lines.push(format!("{} -> {} [label={}]", dot_id(&p), dot_id(&n), label));
+                    nets.extend(vec![p, n]);
+                }
+                None => {
+                    let label = dot_id(&format!("{} ({})", name, comp.prefix().to_uppercase()));
+                    lines.push(format!("{} [shape=box, label={}]", dot_id(name), label));
+                    for node in comp.nodes().iter() {
+                        let n = gname(node);
+                        lines.push(format!("{} -> {}", dot_id(name), dot_id(&n)));
+                        nets.push(n);
+                    }
+                }
+            }
+            false
+        })?;
+        let mut seen = HashSet::new();
+        nets.retain(|n| seen.insert(n.clone()));
+
+        let mut rv = format!("digraph {} {{\n", dot_id(&self.name));
+        for net in nets.iter() {
+            rv.push_str(&format!("    {};\n", dot_id(net)));
+        }
+        for line in lines.iter() {
+            rv.push_str(&format!("    {};\n", line));
+        }
+        rv.push_str("}\n");
+        Ok(rv)
+    }
+    /// Hash of the circuit's structural content: components, their connections and values,
+    /// signals, and model and parameter definitions.
+    /// Independent of the order in which each were added, and stable across runs,
+    /// e.g. for keying cached simulation results. The circuit `name` is not included.
+    pub fn content_hash(&self) -> u64 {
+        let mut items: Vec<String> = self.comps.iter().map(|c| format!("comp:{}", c.content_key())).collect();
+        items.extend(self.signals.iter().map(|s| format!("signal:{}", s)));
+        items.extend(self.defs.content_keys());
+        items.sort();
+        // FNV-1a, over each item and a separating zero-byte.
+        // Used rather than `std`'s hashers, whose output may change between releases.
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for item in items.iter() {
+            for byte in item.bytes().chain(std::iter::once(0)) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        hash
     }
     /// Decode from bytes, via proto definitions
     pub fn decode(bytes_: &[u8]) -> SpResult<Self> { 
@@ -256,15 +1227,48 @@ impl Ckt {
     }
     /// Add anything convertible into `Comp`,
     /// typically the enum-associated structs `Vi` et al.
+    /// Components with empty or already-used names are given a unique generated name,
+    /// of the form `r$3` for the resistor at index three, and recorded in `aliases`.
     pub fn add<C: Into<Comp>>(&mut self, comp: C) {
-        self.comps.push(comp.into());
+        let mut comp = comp.into();
+        let names: HashSet<&str> = self.comps.iter().map(|c| c.name()).collect();
+        if comp.name().is_empty() || names.contains(comp.name()) {
+            let mut idx = self.comps.len();
+            let mut name = format!("{}${}", comp.prefix(), idx);
+            while names.contains(name.as_str()) {
+                idx += 1;
+                name = format!("{}${}", comp.prefix(), idx);
+            }
+            self.defs.aliases.insert(name.clone(), comp.name().to_string());
+            comp.rename(name);
+        }
+        self.comps.push(comp);
+    }
+    /// Generated instance-names, mapped to the (empty or duplicate) names originally requested of `add`
+    pub fn aliases(&self) -> &HashMap<String, String> {
+        &self.defs.aliases
     }
     /// Convert from YAML string  
+    /// Validates the port-connections of each MOS instance along the way.
     pub fn from_yaml(y: &str) -> SpResult<Self> {
         use textwrap::dedent;
-        let proto: CircuitProto = serde_yaml::from_str(&dedent(y)).unwrap();
+        let mut value: serde_yaml::Value = match serde_yaml::from_str(&dedent(y)) {
+            Ok(v) => v,
+            Err(e) => return Err(SpError::new(format!("YAML Parse Error: {}", e))),
+        };
+        normalize_mos_ports(&mut value)?;
+        normalize_vccs_ctrl(&mut value)?;
+        let proto: CircuitProto = match serde_yaml::from_value(value) {
+            Ok(p) => p,
+            Err(e) => return Err(SpError::new(format!("YAML Parse Error: {}", e))),
+        };
         Self::from_proto(proto)
     }
+    /// Convert from SPICE netlist string.
+    /// See `netlist` for the supported subset of cards.
+    pub fn from_spice(s: &str) -> SpResult<Self> {
+        Self::from_proto(super::netlist::parse(s)?)
+    }
     /// Convert from TOML string  
     pub fn from_toml(y: &str) -> SpResult<Self> {
         use textwrap::dedent;
@@ -312,7 +1316,10 @@ impl Ckt {
                     defs.diodes.add_model(&x.name.clone(), DiodeModel::from(x))
                 }
                 DefProto::Diodeinst(x) => defs.diodes.add_inst(&x.name.clone(), x),
+                DefProto::Corner(x) => defs.add_corner(x),
                 DefProto::Module(x) => {
+                    let comps = module_comps(&x)?;
+                    validate_comps(&comps)?;
                     defs.modules.add(x);
                 }
             }
@@ -321,11 +1328,12 @@ impl Ckt {
         let mut comps: Vec<Comp> = vec![];
         for opt in cs_.into_iter() {
             if let Some(c) = opt.comp {
-                comps.push(Comp::from(c));
+                comps.push(Comp::from_proto(c)?);
             } else {
                 return Err(SpError::new("Invalid Component"));
             }
         }
+        validate_comps(&comps)?;
         Ok(Ckt { comps, defs, name, signals })
     }
 }
@@ -346,6 +1354,29 @@ mod tests {
         Ok(())
     }
     #[test]
+    fn test_comp_types() -> TestResult {
+        let types = comp_types();
+        let names: Vec<&str> = types.iter().map(|t| &t.name as &str).collect();
+        assert(names).eq(vec!["R", "C", "I", "V", "D", "M", "X", "Xa"])?;
+        let get = |name: &str| types.iter().find(|t| t.name == name).unwrap();
+        let field = |t: &CompType, name: &str| t.fields.iter().find(|f| f.name == name).unwrap().clone();
+
+        let r = get("R");
+        assert(field(r, "g").kind).eq("number".to_string())?;
+        assert(field(r, "g").required).eq(true)?;
+        assert(field(r, "p").kind).eq("string".to_string())?;
+        assert(field(r, "n").kind).eq("string".to_string())?;
+
+        let m = get("M");
+        let fnames: Vec<&str> = m.fields.iter().map(|f| &f.name as &str).collect();
+        assert(fnames).eq(vec!["name", "model", "params", "ports"])?;
+        assert(field(m, "ports").kind).eq("message".to_string())?;
+        assert(field(m, "ports").required).eq(false)?;
+        assert(field(m, "model").kind).eq("string".to_string())?;
+        assert(field(m, "params").required).eq(true)?;
+        Ok(())
+    }
+    #[test]
     fn test_from_yaml() -> TestResult {
         let ckt = Ckt::from_yaml(
             r#"
@@ -354,13 +1385,58 @@ mod tests {
             comps:
               - {type: R, name: r1, p: a, n: "", g: 0.001 }
               - {type: C, name: r2, p: a, n: "", c: 0.001 }
-              - {type: M, name: mq, params: noparams, model: nomodel, ports: {d: b, g: a } }
+              - {type: M, name: mq, params: noparams, model: nomodel, ports: {d: b, g: a, s: "", b: "" } }
                 "#,
         )?;
         assert(ckt.comps.len()).eq(3)?;
         Ok(())
     }
     #[test]
+    fn test_from_yaml_mos_ports() -> TestResult {
+        let yaml = |ports: &str| {
+            format!(
+                r#"
+                name: tbd
+                comps:
+                  - {{type: M, name: mq, params: noparams, model: nomodel, ports: {} }}
+                "#,
+                ports
+            )
+        };
+        let err = |ports: &str| match Ckt::from_yaml(&yaml(ports)) {
+            Ok(_) => "".to_string(),
+            Err(e) => e.desc,
+        };
+        // Missing and misspelled terminals produce errors naming the instance and port
+        assert(err("{d: d, g: g, s: s}")).eq("Mos Instance mq: Missing Port `b`".to_string())?;
+        assert(err("{d: d, g: g, s: s, bb: b}")).eq("Mos Instance mq: Invalid Port `bb`".to_string())?;
+        assert(err("{d: d, g: g, s: s, b: b, B: b}")).eq("Mos Instance mq: Duplicate Port `b`".to_string())?;
+
+        // Port names are normalized to lower-case
+        let ckt = Ckt::from_yaml(&yaml("{D: d, G: g, S: s, B: b}"))?;
+        match &ckt.comps[0] {
+            Comp::Mos(m) => assert(m.ports.b.to_string()).eq("b".to_string())?,
+            _ => return Err(SpError::new("Expected Mos")),
+        }
+        // Including those within Module definitions
+        let ckt = r#"
+            name: tbd
+            defs:
+            - type: Module
+              name: inv
+              ports: [inp, out, vdd, vss]
+              params: {}
+              signals: []
+              comps:
+              - {type: M, name: p, ports: {g: inp, d: out, s: vdd}, params: default, model: pmos }
+        "#;
+        match Ckt::from_yaml(ckt) {
+            Ok(_) => return Err(SpError::new("Expected Error")),
+            Err(e) => assert(e.desc).eq("Mos Instance p: Missing Port `b`".to_string())?,
+        }
+        Ok(())
+    }
+    #[test]
     fn test_from_toml() -> TestResult {
         let ckt = Ckt::from_toml(
             r#"
@@ -435,4 +1511,67 @@ mod tests {
         assert(ckt.comps.len()).eq(6)?;
         Ok(())
     }
+    /// GraphViz export of the I-R-R divider, plus a collapsed and expanded module instance
+    #[test]
+    fn test_to_dot() -> TestResult {
+        let yaml = |extra: &str| {
+            format!(
+                r#"
+                name: tbd
+                defs:
+                - type: Module
+                  name: half
+                  ports: [p, n]
+                  params: {{}}
+                  signals: [mid]
+                  comps:
+                  - {{type: R, name: ra, p: p, n: mid, g: 1e-3 }}
+                  - {{type: R, name: rb, p: mid, n: n, g: 1e-3 }}
+                signals: [vdd, div]
+                comps:
+                  - {{type: I, name: i1, p: vdd, n: "",  dc: 1e-3 }}
+                  - {{type: R, name: r1, p: vdd, n: div, g: 1e-3 }}
+                  - {{type: R, name: r2, p: div, n: "",  g: 1e-3 }}
+                  {}
+                "#,
+                extra
+            )
+        };
+        let dot = Ckt::from_yaml(&yaml(""))?.to_dot(false)?;
+        assert(dot.starts_with("digraph \"tbd\" {")).eq(true)?;
+        for net in ["vdd", "div", "gnd"].iter() {
+            assert(dot.contains(&format!("    \"{}\";", net))).eq(true)?;
+        }
+        assert(dot.matches(" -> ").count()).eq(3)?;
+        assert(dot.contains(r#""gnd" -> "vdd""#)).eq(false)?;
+        assert(dot.contains(r#""vdd" -> "gnd" [label="i1 (I, dc=0.001)"]"#)).eq(true)?;
+        assert(dot.contains(r#""vdd" -> "div" [label="r1 (R, g=0.001)"]"#)).eq(true)?;
+        assert(dot.contains(r#""div" -> "gnd" [label="r2 (R, g=0.001)"]"#)).eq(true)?;
+
+        // Module instances, collapsed into a box
+        let ckt = Ckt::from_yaml(&yaml(r#"- {type: X, name: x1, module: half, ports: {p: div, n: ""}, params: {} }"#))?;
+        let dot = ckt.to_dot(false)?;
+        assert(dot.contains(r#""x1" [shape=box, label="x1 (X)"]"#)).eq(true)?;
+        assert(dot.matches(" -> ").count()).eq(5)?;
+        // And expanded into their contents
+        let dot = ckt.to_dot(true)?;
+        assert(dot.contains(r#""div" -> "x1.mid" [label="x1.ra (R, g=0.001)"]"#)).eq(true)?;
+        assert(dot.contains(r#""x1.mid" -> "gnd" [label="x1.rb (R, g=0.001)"]"#)).eq(true)?;
+        assert(dot.matches(" -> ").count()).eq(5)?;
+        // Hierarchical names use `opts.hier_separator`
+        let opts = Options {
+            hier_separator: "/".into(),
+            ..Default::default()
+        };
+        let dot = ckt.to_dot_with(true, &opts)?;
+        assert(dot.contains(r#""div" -> "x1/mid" [label="x1/ra (R, g=0.001)"]"#)).eq(true)?;
+        assert(dot.contains(r#"    "x1/mid";"#)).eq(true)?;
+
+        // Quotes in names are escaped
+        let ckt = Ckt::from_comps(vec![Comp::r("r\"1", 1e-3, "a\"b", Gnd)]);
+        let dot = ckt.to_dot(false)?;
+        assert(dot.contains(r#"    "a\"b";"#)).eq(true)?;
+        assert(dot.contains(r#""a\"b" -> "gnd" [label="r\"1 (R, g=0.001)"]"#)).eq(true)?;
+        Ok(())
+    }
 }
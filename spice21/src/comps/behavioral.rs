@@ -0,0 +1,427 @@
+//!
+//! # Behavioral Sources
+//!
+//! Current sources defined by an arithmetic expression of node voltages, e.g.
+//!
+//! ```yaml
+//! - {type: B, name: b1, p: out, n: "", expr: "v(a) * v(a) * 1e-3" }
+//! ```
+//!
+//! Expressions support numeric literals, `+ - * /`, unary negation, parentheses,
+//! `pow(base, exponent)`, and node-voltage references `v(node)`.
+//!
+//! The Laplace variable `s` (or equivalently `jw`) makes a source frequency-dependent,
+//! e.g. the ideal integrator `v(a) / s`.
+//! AC analysis evaluates `s` at each frequency, linearized around the DC operating point.
+//! DC and transient analyses evaluate `s` as zero, and sources without a finite value there,
+//! such as the ideal integrator, carry no current.
+//!
+use num::Complex;
+
+use super::{make_matrix_elem, Component};
+use crate::analysis::{AnalysisInfo, Options, Stamps, VarIndex, Variables};
+use crate::sparse21::{Eindex, Matrix};
+use crate::{sperror, SpNum, SpResult};
+
+/// Expression Tree
+/// Node-voltages `V` index into the `nodes` of their `BExpr`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Num(f64),
+    V(usize),
+    /// Laplace variable `s`
+    S,
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Pow(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Evaluate, given values `v` of each referenced node-voltage, at DC (`s` = 0)
+    fn eval(&self, v: &[f64]) -> f64 {
+        match self {
+            Expr::Num(x) => *x,
+            Expr::V(k) => v[*k],
+            Expr::S => 0.0,
+            Expr::Neg(a) => -a.eval(v),
+            Expr::Add(a, b) => a.eval(v) + b.eval(v),
+            Expr::Sub(a, b) => a.eval(v) - b.eval(v),
+            Expr::Mul(a, b) => a.eval(v) * b.eval(v),
+            Expr::Div(a, b) => a.eval(v) / b.eval(v),
+            Expr::Pow(a, b) => a.eval(v).powf(b.eval(v)),
+        }
+    }
+    /// Whether any term references the Laplace variable `s`
+    fn has_s(&self) -> bool {
+        match self {
+            Expr::Num(_) | Expr::V(_) => false,
+            Expr::S => true,
+            Expr::Neg(a) => a.has_s(),
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) | Expr::Div(a, b) | Expr::Pow(a, b) => a.has_s() || b.has_s(),
+        }
+    }
+    /// Whether DC evaluation at `v` divides by a zero-valued, `s`-dependent denominator, i.e. has a pole at `s` = 0
+    fn dc_pole(&self, v: &[f64]) -> bool {
+        match self {
+            Expr::Num(_) | Expr::V(_) | Expr::S => false,
+            Expr::Neg(a) => a.dc_pole(v),
+            Expr::Div(a, b) if b.has_s() && b.eval(v) == 0.0 => true,
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) | Expr::Div(a, b) | Expr::Pow(a, b) => a.dc_pole(v) || b.dc_pole(v),
+        }
+    }
+    /// Evaluate with complex node-voltages `v`, at Laplace variable `s`
+    fn eval_ac(&self, v: &[Complex<f64>], s: Complex<f64>) -> Complex<f64> {
+        match self {
+            Expr::Num(x) => Complex::new(*x, 0.0),
+            Expr::V(k) => v[*k],
+            Expr::S => s,
+            Expr::Neg(a) => -a.eval_ac(v, s),
+            Expr::Add(a, b) => a.eval_ac(v, s) + b.eval_ac(v, s),
+            Expr::Sub(a, b) => a.eval_ac(v, s) - b.eval_ac(v, s),
+            Expr::Mul(a, b) => a.eval_ac(v, s) * b.eval_ac(v, s),
+            Expr::Div(a, b) => a.eval_ac(v, s) / b.eval_ac(v, s),
+            Expr::Pow(a, b) => a.eval_ac(v, s).powc(b.eval_ac(v, s)),
+        }
+    }
+}
+
+/// Parsed Expression, and the (unique, in order of first reference) names of the nodes it references
+#[derive(Debug, Clone, PartialEq)]
+pub struct BExpr {
+    pub expr: Expr,
+    pub nodes: Vec<String>,
+}
+
+impl BExpr {
+    /// Evaluate, given values `v` of each of `nodes`
+    pub fn eval(&self, v: &[f64]) -> f64 {
+        self.expr.eval(v)
+    }
+    /// Evaluate with complex values `v` of each of `nodes`, at Laplace variable `s`
+    pub fn eval_ac(&self, v: &[Complex<f64>], s: Complex<f64>) -> Complex<f64> {
+        self.expr.eval_ac(v, s)
+    }
+}
+
+/// Parse expression-string `s`
+pub fn parse(s: &str) -> SpResult<BExpr> {
+    let mut p = Parser {
+        chars: s.chars().collect(),
+        pos: 0,
+        nodes: vec![],
+    };
+    let expr = p.expr()?;
+    p.skip_ws();
+    if p.pos < p.chars.len() {
+        return Err(p.err("Unexpected Character"));
+    }
+    Ok(BExpr { expr, nodes: p.nodes })
+}
+
+/// Recursive-descent expression parser
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    nodes: Vec<String>,
+}
+
+impl Parser {
+    fn err(&self, msg: &str) -> crate::SpError {
+        let s: String = self.chars.iter().collect();
+        sperror(format!("Invalid Expression \"{}\": {} at position {}", s, msg, self.pos))
+    }
+    fn skip_ws(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+    /// Peek at the next non-whitespace character
+    fn peek(&mut self) -> Option<char> {
+        self.skip_ws();
+        self.chars.get(self.pos).cloned()
+    }
+    fn expect(&mut self, c: char) -> SpResult<()> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.err(&format!("Expected '{}'", c)))
+        }
+    }
+    /// expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> SpResult<Expr> {
+        let mut lhs = self.term()?;
+        loop {
+            match self.peek() {
+                Some('+') => {
+                    self.pos += 1;
+                    lhs = Expr::Add(Box::new(lhs), Box::new(self.term()?));
+                }
+                Some('-') => {
+                    self.pos += 1;
+                    lhs = Expr::Sub(Box::new(lhs), Box::new(self.term()?));
+                }
+                _ => return Ok(lhs),
+            }
+        }
+    }
+    /// term := unary (('*' | '/') unary)*
+    fn term(&mut self) -> SpResult<Expr> {
+        let mut lhs = self.unary()?;
+        loop {
+            match self.peek() {
+                Some('*') => {
+                    self.pos += 1;
+                    lhs = Expr::Mul(Box::new(lhs), Box::new(self.unary()?));
+                }
+                Some('/') => {
+                    self.pos += 1;
+                    lhs = Expr::Div(Box::new(lhs), Box::new(self.unary()?));
+                }
+                _ => return Ok(lhs),
+            }
+        }
+    }
+    /// unary := '-' unary | primary
+    fn unary(&mut self) -> SpResult<Expr> {
+        if self.peek() == Some('-') {
+            self.pos += 1;
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+    /// primary := number | 's' | 'jw' | 'v(' node ')' | 'pow(' expr ',' expr ')' | '(' expr ')'
+    fn primary(&mut self) -> SpResult<Expr> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let e = self.expr()?;
+                self.expect(')')?;
+                Ok(e)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) if c.is_alphabetic() => {
+                let start = self.pos;
+                while self.pos < self.chars.len() && self.chars[self.pos].is_alphanumeric() {
+                    self.pos += 1;
+                }
+                let name: String = self.chars[start..self.pos].iter().collect();
+                if self.peek() != Some('(') {
+                    return match name.to_lowercase().as_str() {
+                        "s" | "jw" => Ok(Expr::S),
+                        _ => {
+                            self.pos = start;
+                            Err(self.err(&format!("Unknown Variable \"{}\"", name)))
+                        }
+                    };
+                }
+                self.expect('(')?;
+                match name.to_lowercase().as_str() {
+                    "v" => self.node(),
+                    "pow" => {
+                        let base = self.expr()?;
+                        self.expect(',')?;
+                        let exp = self.expr()?;
+                        self.expect(')')?;
+                        Ok(Expr::Pow(Box::new(base), Box::new(exp)))
+                    }
+                    _ => {
+                        self.pos = start;
+                        Err(self.err(&format!("Unknown Function \"{}\"", name)))
+                    }
+                }
+            }
+            _ => Err(self.err("Expected Value")),
+        }
+    }
+    /// Node-name inside `v( )`, through its closing parenthesis
+    fn node(&mut self) -> SpResult<Expr> {
+        self.skip_ws();
+        let start = self.pos;
+        while self.pos < self.chars.len() && self.chars[self.pos] != ')' {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect::<String>().trim().to_string();
+        if name.is_empty() {
+            return Err(self.err("Empty Node Name"));
+        }
+        self.expect(')')?;
+        let k = match self.nodes.iter().position(|n| *n == name) {
+            Some(k) => k,
+            None => {
+                self.nodes.push(name);
+                self.nodes.len() - 1
+            }
+        };
+        Ok(Expr::V(k))
+    }
+    /// Numeric literal, with optional fraction and exponent
+    fn number(&mut self) -> SpResult<Expr> {
+        let start = self.pos;
+        let digits = |p: &mut Parser| {
+            while p.pos < p.chars.len() && (p.chars[p.pos].is_ascii_digit() || p.chars[p.pos] == '.') {
+                p.pos += 1;
+            }
+        };
+        digits(self);
+        if self.pos < self.chars.len() && (self.chars[self.pos] == 'e' || self.chars[self.pos] == 'E') {
+            self.pos += 1;
+            if self.pos < self.chars.len() && (self.chars[self.pos] == '+' || self.chars[self.pos] == '-') {
+                self.pos += 1;
+            }
+            digits(self);
+        }
+        let s: String = self.chars[start..self.pos].iter().collect();
+        match s.parse::<f64>() {
+            Ok(x) => Ok(Expr::Num(x)),
+            Err(_) => {
+                self.pos = start;
+                Err(self.err("Invalid Number"))
+            }
+        }
+    }
+}
+
+/// Behavioral Current Source
+/// Current `i`, flowing from `p` to `n` through the source, is expression `expr` of node-voltages `vars`.
+/// Partial derivatives, for the Newton and AC linearizations, are evaluated by finite differences.
+/// `op` holds the committed operating-point values of `vars`, about which AC analysis linearizes.
+pub(crate) struct Bsrc {
+    expr: BExpr,
+    p: Option<VarIndex>,
+    n: Option<VarIndex>,
+    vars: Vec<Option<VarIndex>>,
+    matps: Vec<[Option<Eindex>; 2]>,
+    op: Vec<f64>,
+    guess: Vec<f64>,
+}
+
+impl Bsrc {
+    pub(crate) fn new(expr: BExpr, p: Option<VarIndex>, n: Option<VarIndex>, vars: Vec<Option<VarIndex>>) -> Self {
+        Bsrc {
+            expr,
+            p,
+            n,
+            matps: vec![[None; 2]; vars.len()],
+            op: vec![0.0; vars.len()],
+            guess: vec![0.0; vars.len()],
+            vars,
+        }
+    }
+    /// Current, and its partial derivative with respect to each of `vars`, at variable-values `x`
+    fn eval(&self, x: &Variables<f64>) -> (f64, Vec<f64>, Vec<f64>) {
+        let mut v: Vec<f64> = self.vars.iter().map(|k| x.get(*k)).collect();
+        let i = self.expr.eval(&v);
+        let mut gs = vec![];
+        for k in 0..v.len() {
+            let v0 = v[k];
+            let h = 1e-6 * (1.0 + v0.abs());
+            v[k] = v0 + h;
+            gs.push((self.expr.eval(&v) - i) / h);
+            v[k] = v0;
+        }
+        if (!i.is_finite() || gs.iter().any(|g| !g.is_finite())) && self.expr.expr.dc_pole(&v) {
+            // No finite DC value, e.g. an ideal integrator. Carry no current.
+            // Other non-finite values are returned as-is, and fail the solver's convergence checks.
+            return (0.0, vec![0.0; v.len()], v);
+        }
+        (i, gs, v)
+    }
+    /// Complex transconductances, around the operating point `op`, at Laplace variable `s`
+    fn eval_ac(&self, s: Complex<f64>) -> Vec<Complex<f64>> {
+        let mut v: Vec<Complex<f64>> = self.op.iter().map(|x| Complex::new(*x, 0.0)).collect();
+        let i = self.expr.eval_ac(&v, s);
+        let mut gs = vec![];
+        for k in 0..v.len() {
+            let v0 = v[k];
+            let h = 1e-6 * (1.0 + v0.re.abs());
+            v[k] = v0 + h;
+            gs.push((self.expr.eval_ac(&v, s) - i) / h);
+            v[k] = v0;
+        }
+        gs
+    }
+    fn g_stamps<T: SpNum>(&self, gs: &[T]) -> Vec<(Option<Eindex>, T)> {
+        let mut g = vec![];
+        for (m, gk) in self.matps.iter().zip(gs.iter()) {
+            g.extend(vec![(m[0], *gk), (m[1], T::zero() - *gk)]);
+        }
+        g
+    }
+}
+
+impl Component for Bsrc {
+    fn commit(&mut self) {
+        self.op = self.guess.clone();
+    }
+    fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
+        for (m, v) in self.matps.iter_mut().zip(self.vars.iter()) {
+            *m = [make_matrix_elem(mat, self.p, *v), make_matrix_elem(mat, self.n, *v)];
+        }
+    }
+    fn load(&mut self, guess: &Variables<f64>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<f64> {
+        let (i, gs, v) = self.eval(guess);
+        // Linearize around `v`: i = irhs + sum(g * v)
+        let irhs = i - gs.iter().zip(v.iter()).map(|(g, v)| g * v).sum::<f64>();
+        let g = self.g_stamps(&gs);
+        self.guess = v;
+        Stamps {
+            g,
+            b: vec![(self.p, -irhs), (self.n, irhs)],
+        }
+    }
+    fn residual(&self, x: &Variables<f64>, _opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        let (i, _, _) = self.eval(x);
+        Some(vec![(self.p, i), (self.n, -i)])
+    }
+    fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
+        let an_st = match an {
+            AnalysisInfo::AC(_, state) => state,
+            _ => panic!("Invalid AC AnalysisInfo"),
+        };
+        let gs = self.eval_ac(Complex::new(0.0, an_st.omega));
+        Stamps {
+            g: self.g_stamps(&gs),
+            b: vec![],
+        }
+    }
+    fn linear(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert::assert;
+    use crate::spresult::TestResult;
+
+    #[test]
+    fn test_parse() -> TestResult {
+        let e = parse("v(a) * v(a) * 1e-3 - pow(v(b), 2) / 4 + -(1.5)")?;
+        assert(e.nodes.clone()).eq(vec!["a".to_string(), "b".to_string()])?;
+        assert(e.eval(&[2.0, 3.0])).isclose(4e-3 - 2.25 - 1.5, 1e-12)?;
+        // Precedence and associativity
+        assert(parse("1 - 2 - 3")?.eval(&[])).eq(-4.0)?;
+        assert(parse("2 + 3 * 4")?.eval(&[])).eq(14.0)?;
+        assert(parse("8 / 4 / 2")?.eval(&[])).eq(1.0)?;
+        // Laplace variable `s`, zero at DC
+        let e = parse("v(a) / (1 + s * 1e-3) + jw")?;
+        assert(e.eval(&[2.0])).eq(2.0)?;
+        let w = Complex::new(0.0, 1e3);
+        assert(e.eval_ac(&[Complex::new(2.0, 0.0)], w)).eq(Complex::new(1.0, -1.0) + w)?;
+        // DC poles, from `s`-dependent denominators only
+        assert(parse("v(a) / s")?.expr.dc_pole(&[1.0])).eq(true)?;
+        assert(parse("1 / (v(a) * s)")?.expr.dc_pole(&[1.0])).eq(true)?;
+        assert(parse("1 / v(a)")?.expr.dc_pole(&[0.0])).eq(false)?;
+        assert(parse("v(a) / (1 + s)")?.expr.dc_pole(&[1.0])).eq(false)?;
+        // Errors
+        for s in ["", "v()", "1 +", "foo(1)", "(1", "1 2", "pow(1)", "x + 1"].iter() {
+            assert(parse(s).is_err()).eq(true)?;
+        }
+        Ok(())
+    }
+}
@@ -110,11 +110,11 @@ pub(crate) fn derive(model: &Bsim4ModelVals) -> Bsim4ModelDerivedParams {
     } else {
         if model.cjs > 0.0 {
             model_derived.SunitAreaTempJctCap = 0.0;
-            println!("Temperature effect has caused cjs to be negative. Cjs is clamped to zero.\n",);
+            log::warn!("Temperature effect has caused cjs to be negative. Cjs is clamped to zero.");
         }
         if model.cjd > 0.0 {
             model_derived.DunitAreaTempJctCap = 0.0;
-            println!("Temperature effect has caused cjd to be negative. Cjd is clamped to zero.\n",);
+            log::warn!("Temperature effect has caused cjd to be negative. Cjd is clamped to zero.");
         }
     }
     T0 = model.tcjsw * delTemp;
@@ -124,11 +124,11 @@ pub(crate) fn derive(model: &Bsim4ModelVals) -> Bsim4ModelDerivedParams {
     } else {
         if model.cjsws > 0.0 {
             model_derived.SunitLengthSidewallTempJctCap = 0.0;
-            println!("Temperature effect has caused cjsws to be negative. Cjsws is clamped to zero.\n",);
+            log::warn!("Temperature effect has caused cjsws to be negative. Cjsws is clamped to zero.");
         }
         if model.cjswd > 0.0 {
             model_derived.DunitLengthSidewallTempJctCap = 0.0;
-            println!("Temperature effect has caused cjswd to be negative. Cjswd is clamped to zero.\n",);
+            log::warn!("Temperature effect has caused cjswd to be negative. Cjswd is clamped to zero.");
         }
     }
     T0 = model.tcjswg * delTemp;
@@ -138,45 +138,45 @@ pub(crate) fn derive(model: &Bsim4ModelVals) -> Bsim4ModelDerivedParams {
     } else {
         if model.cjswgs > 0.0 {
             model_derived.SunitLengthGateSidewallTempJctCap = 0.0;
-            println!("Temperature effect has caused cjswgs to be negative. Cjswgs is clamped to zero.\n",);
+            log::warn!("Temperature effect has caused cjswgs to be negative. Cjswgs is clamped to zero.");
         }
         if model.cjswgd > 0.0 {
             model_derived.DunitLengthGateSidewallTempJctCap = 0.0;
-            println!("Temperature effect has caused cjswgd to be negative. Cjswgd is clamped to zero.\n",);
+            log::warn!("Temperature effect has caused cjswgd to be negative. Cjswgd is clamped to zero.");
         }
     }
 
     model_derived.PhiBS = model.pbs - model.tpb * delTemp;
     if model_derived.PhiBS < 0.01 {
         model_derived.PhiBS = 0.01;
-        println!("Temperature effect has caused pbs to be less than 0.01. Pbs is clamped to 0.01.\n",);
+        log::warn!("Temperature effect has caused pbs to be less than 0.01. Pbs is clamped to 0.01.");
     }
     model_derived.PhiBD = model.pbd - model.tpb * delTemp;
     if model_derived.PhiBD < 0.01 {
         model_derived.PhiBD = 0.01;
-        println!("Temperature effect has caused pbd to be less than 0.01. Pbd is clamped to 0.01.\n",);
+        log::warn!("Temperature effect has caused pbd to be less than 0.01. Pbd is clamped to 0.01.");
     }
 
     model_derived.PhiBSWS = model.pbsws - model.tpbsw * delTemp;
     if model_derived.PhiBSWS <= 0.01 {
         model_derived.PhiBSWS = 0.01;
-        println!("Temperature effect has caused pbsws to be less than 0.01. Pbsws is clamped to 0.01.\n",);
+        log::warn!("Temperature effect has caused pbsws to be less than 0.01. Pbsws is clamped to 0.01.");
     }
     model_derived.PhiBSWD = model.pbswd - model.tpbsw * delTemp;
     if model_derived.PhiBSWD <= 0.01 {
         model_derived.PhiBSWD = 0.01;
-        println!("Temperature effect has caused pbswd to be less than 0.01. Pbswd is clamped to 0.01.\n",);
+        log::warn!("Temperature effect has caused pbswd to be less than 0.01. Pbswd is clamped to 0.01.");
     }
 
     model_derived.PhiBSWGS = model.pbswgs - model.tpbswg * delTemp;
     if model_derived.PhiBSWGS <= 0.01 {
         model_derived.PhiBSWGS = 0.01;
-        println!("Temperature effect has caused pbswgs to be less than 0.01. Pbswgs is clamped to 0.01.\n");
+        log::warn!("Temperature effect has caused pbswgs to be less than 0.01. Pbswgs is clamped to 0.01.");
     }
     model_derived.PhiBSWGD = model.pbswgd - model.tpbswg * delTemp;
     if model_derived.PhiBSWGD <= 0.01 {
         model_derived.PhiBSWGD = 0.01;
-        println!("Temperature effect has caused pbswgd to be less than 0.01. Pbswgd is clamped to 0.01.\n");
+        log::warn!("Temperature effect has caused pbswgd to be less than 0.01. Pbswgd is clamped to 0.01.");
     } /* End of junction capacitance */
 
     /* GEDL current reverse bias */
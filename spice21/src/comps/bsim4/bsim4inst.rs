@@ -449,21 +449,21 @@ pub(crate) fn from(
 
         if T1 < 0.0 {
             T1 = 0.0;
-            println!("Warning: Rdw at current temperature is negative; set to 0.\n");
+            log::warn!("Rdw at current temperature is negative; set to 0.");
         }
         if T2 < 0.0 {
             T2 = 0.0;
-            println!("Warning: Rdwmin at current temperature is negative; set to 0.\n");
+            log::warn!("Rdwmin at current temperature is negative; set to 0.");
         }
         size_params.rd0 = T1 / PowWeffWr;
         size_params.rdwmin = T2 / PowWeffWr;
         if T3 < 0.0 {
             T3 = 0.0;
-            println!("Warning: Rsw at current temperature is negative; set to 0.\n");
+            log::warn!("Rsw at current temperature is negative; set to 0.");
         }
         if T4 < 0.0 {
             T4 = 0.0;
-            println!("Warning: Rswmin at current temperature is negative; set to 0.\n");
+            log::warn!("Rswmin at current temperature is negative; set to 0.");
         }
         size_params.rs0 = T3 / PowWeffWr;
         size_params.rswmin = T4 / PowWeffWr;
@@ -477,11 +477,11 @@ pub(crate) fn from(
         size_params.u0temp = size_params.u0 * T5 * pow(model_derived.TempRatio, size_params.ute);
         if size_params.eu < 0.0 {
             size_params.eu = 0.0;
-            println!("Warning: eu has been negative; reset to 0.0.\n");
+            log::warn!("eu has been negative; reset to 0.0.");
         }
         if size_params.ucs < 0.0 {
             size_params.ucs = 0.0;
-            println!("Warning: ucs has been negative; reset to 0.0.\n");
+            log::warn!("ucs has been negative; reset to 0.0.");
         }
 
         size_params.vfbsdoff = size_params.vfbsdoff * (1.0 + size_params.tvfbsdoff * delTemp);
@@ -579,25 +579,25 @@ pub(crate) fn from(
         size_params.k2 = if let Some(k2) = model.k2Opt {k2 + model.lk2 * Inv_L + model.wk2 * Inv_W + model.pk2 * Inv_LW} else { -0.0186 };
         if model.k1Opt.is_some() || model.k2Opt.is_some() {
             if model.k1Opt.is_none() {
-                println!("Warning: k1 should be specified with k2.\n");
+                log::warn!("k1 should be specified with k2.");
             }
             if model.k2Opt.is_none() {
-                println!("Warning: k2 should be specified with k1.\n");
+                log::warn!("k2 should be specified with k1.");
             }
             if model.nsubGiven {
-                println!("Warning: nsub is ignored because k1 or k2 is given.\n",);
+                log::warn!("nsub is ignored because k1 or k2 is given.");
             }
             if model.xtGiven {
-                println!("Warning: xt is ignored because k1 or k2 is given.\n",);
+                log::warn!("xt is ignored because k1 or k2 is given.");
             }
             if model.vbxGiven {
-                println!("Warning: vbx is ignored because k1 or k2 is given.\n",);
+                log::warn!("vbx is ignored because k1 or k2 is given.");
             }
             if model.gamma1Given {
-                println!("Warning: gamma1 is ignored because k1 or k2 is given.\n",);
+                log::warn!("gamma1 is ignored because k1 or k2 is given.");
             }
             if model.gamma2Given {
-                println!("Warning: gamma2 is ignored because k1 or k2 is given.\n",);
+                log::warn!("gamma2 is ignored because k1 or k2 is given.");
             }
         } else {
             if !model.vbxGiven {
@@ -789,11 +789,11 @@ pub(crate) fn from(
         kvsat = model.kvsat;
         if model.kvsat < -1.0 {
             kvsat = -1.0;
-            println!("Warning: KVSAT = {} is too small; -1.0 is used.\n", model.kvsat,);
+            log::warn!("KVSAT = {} is too small; -1.0 is used.", model.kvsat,);
         }
         if model.kvsat > 1.0 {
             kvsat = 1.0;
-            println!("Warning: KVSAT = {} is too big; 1.0 is used.\n", model.kvsat,);
+            log::warn!("KVSAT = {} is too big; 1.0 is used.", model.kvsat,);
         }
         let nfi = intp.nf as usize;
         for i in 0..nfi {
@@ -846,23 +846,23 @@ pub(crate) fn from(
                     - (0.05 * T1 + 0.0025 * model.scref) * exp(-20.0 * T1 * T2))
                     / Wdrn;
             } else {
-                println!("Warning: No WPE as none of SCA, SCB, SCC, SC is given and/or SC not positive.\n");
+                log::warn!("No WPE as none of SCA, SCB, SCC, SC is given and/or SC not positive.");
             }
         }
         if intp.sca < 0.0 {
-            println!("Warning: SCA = {} is negative. Set to 0.0.\n", intp.sca);
+            log::warn!("SCA = {} is negative. Set to 0.0.", intp.sca);
             intp.sca = 0.0;
         }
         if intp.scb < 0.0 {
-            println!("Warning: SCB = {} is negative. Set to 0.0.\n", intp.scb);
+            log::warn!("SCB = {} is negative. Set to 0.0.", intp.scb);
             intp.scb = 0.0;
         }
         if intp.scc < 0.0 {
-            println!("Warning: SCC = {} is negative. Set to 0.0.\n", intp.scc);
+            log::warn!("SCC = {} is negative. Set to 0.0.", intp.scc);
             intp.scc = 0.0;
         }
         if intp.sc < 0.0 {
-            println!("Warning: SC = {} is negative. Set to 0.0.\n", intp.sc);
+            log::warn!("SC = {} is negative. Set to 0.0.", intp.sc);
             intp.sc = 0.0;
         }
         sceff = intp.sca + model.web * intp.scb + model.wec * intp.scc;
@@ -871,7 +871,7 @@ pub(crate) fn from(
         T3 = 1.0 + size_params.ku0we * sceff;
         if T3 <= 0.0 {
             T3 = 0.0;
-            println!("Warning: ku0we = {} is negatively too high. Negative mobility! \n", size_params.ku0we,);
+            log::warn!("ku0we = {} is negatively too high. Negative mobility! ", size_params.ku0we,);
         }
         intp.u0temp *= T3;
     }
@@ -1012,7 +1012,7 @@ pub(crate) fn from(
     } else {
         intp.grgeltd = 1.0e3; /* mho */
         if model.rgatemod != 0 {
-            println!("Warning: The gate conductance reset to 1.0e3 mho.\n");
+            log::warn!("The gate conductance reset to 1.0e3 mho.");
         }
     }
 
@@ -1023,7 +1023,7 @@ pub(crate) fn from(
 
     intp.Pseff = if let Some(val) = inst.ps {
         if val < 0.0 {
-            println!("Warning: Source Perimeter is specified as negative, it is set to zero.\n");
+            log::warn!("Source Perimeter is specified as negative, it is set to zero.");
             0.0
         } else if model.permod == 0 {
             val
@@ -1035,11 +1035,11 @@ pub(crate) fn from(
     };
     if intp.Pseff < 0.0 {
         intp.Pseff = 0.0;
-        println!("Warning: Pseff is negative, it is set to zero.\n");
+        log::warn!("Pseff is negative, it is set to zero.");
     }
     intp.Pdeff = if let Some(val) = inst.pd {
         if val < 0.0 {
-            println!("Warning: Drain Perimeter is specified as negative, it is set to zero.\n");
+            log::warn!("Drain Perimeter is specified as negative, it is set to zero.");
             0.0
         } else if model.permod == 0 {
             val
@@ -1051,18 +1051,18 @@ pub(crate) fn from(
     };
     if intp.Pdeff < 0.0 {
         intp.Pdeff = 0.0;
-        println!("Warning: Pdeff is negative, it is set to zero.\n");
+        log::warn!("Pdeff is negative, it is set to zero.");
     }
 
     intp.Aseff = if let Some(val) = inst.r#as { val } else { as_calc };
     if intp.Aseff < 0.0 {
         intp.Aseff = 0.0;
-        println!("Warning: Aseff is negative, it is set to zero.\n");
+        log::warn!("Aseff is negative, it is set to zero.");
     }
     intp.Adeff = if let Some(val) = inst.ad { val } else { ad_calc };
     if intp.Adeff < 0.0 {
         intp.Adeff = 0.0;
-        println!("Warning: Adeff is negative, it is set to zero.\n");
+        log::warn!("Adeff is negative, it is set to zero.");
     }
 
     // Source & Drain Resistances
@@ -1156,14 +1156,14 @@ pub(crate) fn from(
                 T2 = model.ijthsrev / SourceSatCurrent;
                 if T2 < 1.0 {
                     T2 = 10.0;
-                    println!("Warning: ijthsrev too small and set to 10 times IsbSat.\n",);
+                    log::warn!("ijthsrev too small and set to 10 times IsbSat.");
                 }
                 intp.vjsmRev = -model.bvs - Nvtms * log((T2 - 1.0) / model.xjbvs);
                 T1 = model.xjbvs * exp(-(model.bvs + intp.vjsmRev) / Nvtms);
                 intp.IVjsmRev = SourceSatCurrent * (1.0 + T1);
                 intp.SslpRev = -SourceSatCurrent * T1 / Nvtms;
             }
-            _ => println!("Specified dioMod {} not matched\n", model.diomod),
+            _ => log::warn!("Specified dioMod {} not matched", model.diomod),
         }
     }
 
@@ -1206,14 +1206,14 @@ pub(crate) fn from(
                 T2 = model.ijthdrev / DrainSatCurrent;
                 if T2 < 1.0 {
                     T2 = 10.0;
-                    println!("Warning: ijthdrev too small and set to 10 times IdbSat.\n",);
+                    log::warn!("ijthdrev too small and set to 10 times IdbSat.");
                 }
                 intp.vjdmRev = -model.bvd - Nvtmd * log((T2 - 1.0) / model.xjbvd); /* bugfix */
                 T1 = model.xjbvd * exp(-(model.bvd + intp.vjdmRev) / Nvtmd);
                 intp.IVjdmRev = DrainSatCurrent * (1.0 + T1);
                 intp.DslpRev = -DrainSatCurrent * T1 / Nvtmd;
             }
-            _ => println!("Specified dioMod {} not matched\n", model.diomod),
+            _ => log::warn!("Specified dioMod {} not matched", model.diomod),
         }
     }
 
@@ -1480,7 +1480,7 @@ fn BSIM4PAeffGeo(nf: f64, geo: usize, minSD: usize, Weffcj: f64, DMCG: f64, DMCI
             return (Ps, Pd, As, Ad);
         }
         _ => {
-            println!("Warning: Specified GEO = {} not matched\n", geo);
+            log::warn!("Specified GEO = {} not matched", geo);
             return (0.0, 0.0, 0.0, 0.0);
         }
     }
@@ -1520,7 +1520,7 @@ fn BSIM4RdsEndSha(Weffcj: f64, Rsh: f64, DMCG: f64, DMCI: f64, DMDG: f64, nuEnd:
             }
             3 | 4 | 6 => {
                 if DMCG == 0.0 {
-                    println!("DMCG can not be equal to zero\n");
+                    log::warn!("DMCG can not be equal to zero");
                 }
                 if nuEnd == 0.0 || DMCG == 0.0 {
                     0.0
@@ -1529,7 +1529,7 @@ fn BSIM4RdsEndSha(Weffcj: f64, Rsh: f64, DMCG: f64, DMCI: f64, DMDG: f64, nuEnd:
                 }
             }
             _ => {
-                println!("Warning: Specified RGEO = {} not matched\n", rgeo);
+                log::warn!("Specified RGEO = {} not matched", rgeo);
                 0.0
             }
         }
@@ -1544,7 +1544,7 @@ fn BSIM4RdsEndSha(Weffcj: f64, Rsh: f64, DMCG: f64, DMCI: f64, DMDG: f64, nuEnd:
             }
             2 | 4 | 8 => {
                 if DMCG == 0.0 {
-                    println!("DMCG can not be equal to zero\n");
+                    log::warn!("DMCG can not be equal to zero");
                 }
                 if nuEnd == 0.0 || DMCG == 0.0 {
                     0.0
@@ -1553,7 +1553,7 @@ fn BSIM4RdsEndSha(Weffcj: f64, Rsh: f64, DMCG: f64, DMCI: f64, DMDG: f64, nuEnd:
                 }
             }
             _ => {
-                println!("Warning: Specified RGEO = {} not matched\n", rgeo);
+                log::warn!("Specified RGEO = {} not matched", rgeo);
                 0.0
             }
         }
@@ -1572,7 +1572,7 @@ fn BSIM4RdsEndIso(Weffcj: f64, Rsh: f64, DMCG: f64, DMCI: f64, DMDG: f64, nuEnd:
             }
             3 | 4 | 6 => {
                 if ((DMCG + DMCI) == 0.0) {
-                    println!("(DMCG + DMCI) can not be equal to zero\n");
+                    log::warn!("(DMCG + DMCI) can not be equal to zero");
                 }
                 if ((nuEnd == 0.0) || ((DMCG + DMCI) == 0.0)) {
                     0.0
@@ -1581,7 +1581,7 @@ fn BSIM4RdsEndIso(Weffcj: f64, Rsh: f64, DMCG: f64, DMCI: f64, DMDG: f64, nuEnd:
                 }
             }
             _ => {
-                println!("Warning: Specified RGEO = {} not matched\n", rgeo);
+                log::warn!("Specified RGEO = {} not matched", rgeo);
                 0.0
             }
         }
@@ -1596,7 +1596,7 @@ fn BSIM4RdsEndIso(Weffcj: f64, Rsh: f64, DMCG: f64, DMCI: f64, DMDG: f64, nuEnd:
             }
             2 | 4 | 8 => {
                 if ((DMCG + DMCI) == 0.0) {
-                    println!("(DMCG + DMCI) can not be equal to zero\n");
+                    log::warn!("(DMCG + DMCI) can not be equal to zero");
                 }
                 if ((nuEnd == 0.0) || ((DMCG + DMCI) == 0.0)) {
                     0.0
@@ -1605,7 +1605,7 @@ fn BSIM4RdsEndIso(Weffcj: f64, Rsh: f64, DMCG: f64, DMCI: f64, DMDG: f64, nuEnd:
                 }
             }
             _ => {
-                println!("Warning: Specified RGEO = {} not matched\n", rgeo);
+                log::warn!("Specified RGEO = {} not matched", rgeo);
                 0.0
             }
         }
@@ -1730,7 +1730,7 @@ fn BSIM4RdseffGeo(nf: f64, geo: usize, rgeo: usize, minSD: usize, Weffcj: f64, R
             }
         }
         _ => {
-            println!("Warning: Specified GEO = {} not matched\n", geo);
+            log::warn!("Specified GEO = {} not matched", geo);
         }
     }
 
@@ -1742,7 +1742,7 @@ fn BSIM4RdseffGeo(nf: f64, geo: usize, rgeo: usize, minSD: usize, Weffcj: f64, R
         Rint * Rend / (Rint + Rend)
     };
     if rv == 0.0 {
-        println!("Warning: Zero resistance returned from RdseffGeo\n");
+        log::warn!("Zero resistance returned from RdseffGeo");
     }
 
     return rv;
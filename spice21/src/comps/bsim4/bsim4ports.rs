@@ -30,6 +30,7 @@ impl Bsim4Ports<Option<VarIndex>> {
     /// Instance path is required for generation of internal node-names. 
     pub(crate) fn from<T: SpNum>(
         path: String,
+        sep: &str,
         terms: &MosPorts<Option<VarIndex>>,
         model: &Bsim4ModelVals,
         intp: &Bsim4InternalParams,
@@ -45,27 +46,27 @@ impl Bsim4Ports<Option<VarIndex>> {
         // Terminal resistances
         // FIXME: can potentially filter our the noise-mode dependence only when doing noise analysis
         let dNodePrime = if model.rdsmod != 0 || (model.tnoimod == 1) {
-            let name = format!("{}.{}", path, "drain");
+            let name = format!("{}{}{}", path, sep, "drain");
             Some(vars.add(name, VarKind::V))
         } else {
             dNode
         };
         let sNodePrime = if model.rdsmod != 0 || (model.tnoimod == 1) {
-            let name = format!("{}.{}", path, "source");
+            let name = format!("{}{}{}", path, sep, "source");
             Some(vars.add(name, VarKind::V))
         } else {
             sNode
         };
         // Gate Resistance
         let gNodePrime = if intp.rgatemod > 0 {
-            let name = format!("{}.{}", path, "gate");
+            let name = format!("{}{}{}", path, sep, "gate");
             Some(vars.add(name, VarKind::V))
         } else {
             gNodeExt
         };
 
         let gNodeMid = if intp.rgatemod == 3 {
-            let name = format!("{}.{}", path, "midgate");
+            let name = format!("{}{}{}", path, sep, "midgate");
             Some(vars.add(name, VarKind::V))
         } else {
             gNodeExt
@@ -73,13 +74,13 @@ impl Bsim4Ports<Option<VarIndex>> {
 
         /* internal body nodes for body resistance model */
         let (dbNode, bNodePrime, sbNode) = if intp.rbodymod == 1 || intp.rbodymod == 2 {
-            let name = format!("{}.{}", path, "dbody");
+            let name = format!("{}{}{}", path, sep, "dbody");
             let dbNode = Some(vars.add(name, VarKind::V));
 
-            let name = format!("{}.{}", path, "body");
+            let name = format!("{}{}{}", path, sep, "body");
             let bNodePrime = Some(vars.add(name, VarKind::V));
 
-            let name = format!("{}.{}", path, "sbody");
+            let name = format!("{}{}{}", path, sep, "sbody");
             let sbNode = Some(vars.add(name, VarKind::V));
 
             (dbNode, bNodePrime, sbNode)
@@ -89,7 +90,7 @@ impl Bsim4Ports<Option<VarIndex>> {
 
         // NQS charge node
         let qNode = if intp.trnqsmod != 0 {
-            let name = format!("{}.{}", path, "charge");
+            let name = format!("{}{}{}", path, sep, "charge");
             Some(vars.add(name, VarKind::Q))
         } else {
             None
@@ -5,10 +5,12 @@ use super::*;
 
 use crate::analysis::{AnalysisInfo, Stamps, TranState, VarIndex, Variables, Options};
 use crate::comps::consts::*;
+use crate::comps::limiting::{fetlim, limvds, pnjlim};
 use crate::comps::mos::MosType;
 use crate::comps::Component;
 use crate::sparse21::{Eindex, Matrix};
 use crate::SpNum;
+use num::Complex;
 
 /// BSIM4 MOSFET Solver
 // #[derive(Default)]
@@ -458,63 +460,63 @@ impl Bsim4 {
         let von = self.guess.von;
 
         if self.guess.vds >= 0.0 {
-            vgs = DEVfetlim(vgs, self.guess.vgs, von);
+            vgs = fetlim(vgs, self.guess.vgs, von);
             vds = vgs - vgd;
-            vds = DEVlimvds(vds, self.guess.vds);
+            vds = limvds(vds, self.guess.vds);
             vgd = vgs - vds;
             if self.model.rgatemod == 3 {
-                vges = DEVfetlim(vges, self.guess.vges, von);
-                vgms = DEVfetlim(vgms, self.guess.vgms, von);
+                vges = fetlim(vges, self.guess.vges, von);
+                vgms = fetlim(vgms, self.guess.vgms, von);
                 vged = vges - vds;
                 vgmd = vgms - vds;
             } else if (self.model.rgatemod == 1) || (self.model.rgatemod == 2) {
-                vges = DEVfetlim(vges, self.guess.vges, von);
+                vges = fetlim(vges, self.guess.vges, von);
                 vged = vges - vds;
             }
             if self.model.rdsmod != 0 {
-                vdes = DEVlimvds(vdes, self.guess.vdes);
-                vses = -DEVlimvds(-vses, -self.guess.vses);
+                vdes = limvds(vdes, self.guess.vdes);
+                vses = -limvds(-vses, -self.guess.vses);
             }
         } else {
-            vgd = DEVfetlim(vgd, vgdo, von);
+            vgd = fetlim(vgd, vgdo, von);
             vds = vgs - vgd;
-            vds = -DEVlimvds(-vds, -self.guess.vds);
+            vds = -limvds(-vds, -self.guess.vds);
             vgs = vgd + vds;
 
             if self.model.rgatemod == 3 {
-                vged = DEVfetlim(vged, vgedo, von);
+                vged = fetlim(vged, vgedo, von);
                 vges = vged + vds;
-                vgmd = DEVfetlim(vgmd, vgmdo, von);
+                vgmd = fetlim(vgmd, vgmdo, von);
                 vgms = vgmd + vds;
             }
             if (self.model.rgatemod == 1) || (self.model.rgatemod == 2) {
-                vged = DEVfetlim(vged, vgedo, von);
+                vged = fetlim(vged, vgedo, von);
                 vges = vged + vds;
             }
 
             if self.model.rdsmod != 0 {
-                vdes = -DEVlimvds(-vdes, -self.guess.vdes);
-                vses = DEVlimvds(vses, self.guess.vses);
+                vdes = -limvds(-vdes, -self.guess.vdes);
+                vses = limvds(vses, self.guess.vses);
             }
         }
 
         if vds >= 0.0 {
-            vbs = DEVpnjlim(vbs, self.guess.vbs, VT_REF, self.model_derived.vcrit);
+            vbs = pnjlim(vbs, self.guess.vbs, VT_REF, self.model_derived.vcrit);
             vbd = vbs - vds;
             if self.model.rbodymod != 0 {
-                vdbs = DEVpnjlim(vdbs, self.guess.vdbs, VT_REF, self.model_derived.vcrit);
+                vdbs = pnjlim(vdbs, self.guess.vdbs, VT_REF, self.model_derived.vcrit);
                 vdbd = vdbs - vds;
-                vsbs = DEVpnjlim(vsbs, self.guess.vsbs, VT_REF, self.model_derived.vcrit);
+                vsbs = pnjlim(vsbs, self.guess.vsbs, VT_REF, self.model_derived.vcrit);
             }
         } else {
-            vbd = DEVpnjlim(vbd, self.guess.vbd, VT_REF, self.model_derived.vcrit);
+            vbd = pnjlim(vbd, self.guess.vbd, VT_REF, self.model_derived.vcrit);
             vbs = vbd + vds;
             if self.model.rbodymod != 0 {
-                vdbd = DEVpnjlim(vdbd, self.guess.vdbd, VT_REF, self.model_derived.vcrit);
+                vdbd = pnjlim(vdbd, self.guess.vdbd, VT_REF, self.model_derived.vcrit);
                 vdbs = vdbd + vds;
                 let vsbdo = self.guess.vsbs - self.guess.vds;
                 let vsbd = vsbs - vds;
-                let vsbd2 = DEVpnjlim(vsbd, vsbdo, VT_REF, self.model_derived.vcrit);
+                let vsbd2 = pnjlim(vsbd, vsbdo, VT_REF, self.model_derived.vcrit);
                 vsbs = vsbd2 + vds;
             }
         }
@@ -3755,6 +3757,9 @@ impl Bsim4 {
 }
 
 impl Component for Bsim4 {
+    fn linear(&self) -> bool {
+        false
+    }
     fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
         self.create_matps(mat)
     }
@@ -3765,6 +3770,32 @@ impl Component for Bsim4 {
     fn commit(&mut self) {
         self.op = self.guess.clone();
     }
+    /// AC load, linearized around our committed operating point.
+    /// Conductances are those of the DC stamps.
+    /// Admittances are the capacitance terms of `cap_op`, scaled by `omega`.
+    fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
+        let omega = match an {
+            AnalysisInfo::AC(_opts, state) => state.omega,
+            _ => panic!("Invalid AC AnalysisInfo"),
+        };
+        // Stamp the op-point twice: once with all capacitances zeroed, and once with them set (to `ag0 = 1`).
+        // Stamps are linear in the capacitance terms, and the two share an element-order,
+        // so their difference is the capacitance matrix.
+        let mut dcop = self.op.clone();
+        self.cap_op(&mut dcop, 0.0);
+        let mut capop = self.op.clone();
+        self.cap_op(&mut capop, 1.0);
+        let dc = self.stamp_op(&dcop);
+        let caps = self.stamp_op(&capop);
+
+        let g = dc
+            .g
+            .iter()
+            .zip(caps.g.iter())
+            .map(|((e, g), (_, gc))| (*e, Complex::new(*g, omega * (gc - g))))
+            .collect();
+        Stamps { g, b: vec![] }
+    }
 }
 
 /// compute poly depletion effect
@@ -3786,92 +3817,6 @@ fn polyDepletion(phi: f64, ngate: f64, epsgate: f64, coxe: f64, Vgs: f64) -> (f6
     }
 }
 
-/// Vds limiting
-fn DEVlimvds(vnew: f64, vold: f64) -> f64 {
-    if vold >= 3.5 {
-        if vnew > vold {
-            return vnew.min((3.0 * vold) + 2.0);
-        }
-        if vnew < 3.5 {
-            return vnew.max(2.0);
-        }
-    } else {
-        if vnew > vold {
-            return vnew.min(4.0);
-        } else {
-            return vnew.max(-0.5);
-        }
-    }
-    return vnew;
-}
-/// Inter-iteration voltage limiting
-fn DEVfetlim(vnew: f64, vold: f64, vto: f64) -> f64 {
-    let vtsthi = (2.0 * (vold - vto)).abs() + 2.0;
-    let vtstlo = vtsthi / 2.0 + 2.0;
-    let vtox = vto + 3.5;
-    let delv = vnew - vold;
-
-    if vold >= vto {
-        if vold >= vtox {
-            if delv <= 0.0 {
-                /* going off */
-                if vnew >= vtox {
-                    if -delv > vtstlo {
-                        return vold - vtstlo;
-                    }
-                } else {
-                    return MAX(vnew, vto + 2.0);
-                }
-            } else {
-                /* staying on */
-                if delv >= vtsthi {
-                    return vold + vtsthi;
-                }
-            }
-        } else {
-            /* middle region */
-            if delv <= 0.0 {
-                /* decreasing */
-                return MAX(vnew, vto - 0.5);
-            } else {
-                /* increasing */
-                return MIN(vnew, vto + 4.0);
-            }
-        }
-    } else {
-        /* off */
-        if delv <= 0.0 {
-            if -delv > vtsthi {
-                return vold - vtsthi;
-            }
-        } else {
-            let vtemp = vto + 0.5;
-            if vnew <= vtemp {
-                if delv > vtstlo {
-                    return vold + vtstlo;
-                }
-            } else {
-                return vtemp;
-            }
-        }
-    }
-    return vnew;
-}
-/// P-N Junction Limiting
-fn DEVpnjlim(vnew: f64, vold: f64, vt: f64, vcrit: f64) -> f64 {
-    if vnew > vcrit && abs(vnew - vold) > (vt + vt) {
-        if vold > 0.0 {
-            let arg = 1.0 + (vnew - vold) / vt;
-            if arg > 0.0 {
-                return vold + vt * log(arg);
-            }
-            return vcrit;
-        }
-        return vt * log(vnew / vt);
-    }
-    return vnew;
-}
-
 impl Bsim4 {
     pub(crate) fn new(ports: Bsim4Ports<Option<VarIndex>>, model: Bsim4ModelEntry, inst: Bsim4InstEntry) -> Self {
         Self {
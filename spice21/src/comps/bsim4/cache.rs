@@ -58,6 +58,14 @@ impl Bsim4Cache {
     pub(crate) fn add_inst(&mut self, inst: Bsim4InstSpecs) {
         self.insts.insert(inst.name.clone(), inst);
     }
+    /// Copy of our model and instance definitions, without any cached combinations
+    pub(crate) fn copy(&self) -> Self {
+        Self {
+            models: self.models.clone(),
+            insts: self.insts.clone(),
+            cache: HashMap::new(),
+        }
+    }
     pub(crate) fn get(&mut self, model_name: &String, inst_name: &String) -> Option<(Bsim4ModelEntry, Bsim4InstEntry)> {
         if let Some(e) = self.cache.get(&(model_name.clone(), inst_name.clone())) {
             return Some(e.clone()); // FIXME: pointers
@@ -20,87 +20,87 @@ pub(crate) fn resolve(specs: &Bsim4ModelSpecs) -> Bsim4ModelVals {
     vals.mobmod = if let Some(val) = specs.mobmod { val as usize } else { 0 };
     if vals.mobmod > 6 {
         vals.mobmod = 0;
-        println!("Warning: mobmod has been set to its default value: 0.\n");
+        log::warn!("mobmod has been set to its default value: 0.");
     }
     vals.diomod = if let Some(val) = specs.diomod { val as usize } else { 1 };
     if vals.diomod > 2 {
         vals.diomod = 1;
-        println!("Warning: diomod has been set to its default value: 1.\n");
+        log::warn!("diomod has been set to its default value: 1.");
     }
     vals.capmod = if let Some(val) = specs.capmod { val as usize } else { 2 };
     if vals.capmod > 2 {
         vals.capmod = 2;
-        println!("Warning: capmod has been set to its default value: 2.\n");
+        log::warn!("capmod has been set to its default value: 2.");
     }
     vals.rdsmod = if let Some(val) = specs.rdsmod { val as usize } else { 0 };
     if vals.rdsmod > 1 {
         vals.rdsmod = 0;
-        println!("Warning: rdsmod has been set to its default value: 0.\n");
+        log::warn!("rdsmod has been set to its default value: 0.");
     }
     vals.rbodymod = if let Some(val) = specs.rbodymod { val as usize } else { 0 };
     if vals.rbodymod > 2 {
         vals.rbodymod = 0;
-        println!("Warning: rbodymod has been set to its default value: 0.\n");
+        log::warn!("rbodymod has been set to its default value: 0.");
     }
     vals.rgatemod = if let Some(val) = specs.rgatemod { val as usize } else { 0 };
     if vals.rgatemod > 3 {
         vals.rgatemod = 0;
-        println!("Warning: rgatemod has been set to its default value: 0.\n");
+        log::warn!("rgatemod has been set to its default value: 0.");
     }
     vals.permod = if let Some(val) = specs.permod { val as usize } else { 1 };
     if vals.permod > 1 {
         vals.permod = 1;
-        println!("Warning: permod has been set to its default value: 1.\n");
+        log::warn!("permod has been set to its default value: 1.");
     }
     vals.fnoimod = if let Some(val) = specs.fnoimod { val as usize } else { 1 };
     if vals.fnoimod > 1 {
         vals.fnoimod = 1;
-        println!("Warning: fnoimod has been set to its default value: 1.\n");
+        log::warn!("fnoimod has been set to its default value: 1.");
     }
     vals.tnoimod = if let Some(val) = specs.tnoimod { val as usize } else { 0 };
     if vals.tnoimod > 2 {
         vals.tnoimod = 0;
-        println!("Warning: tnoimod has been set to its default value: 0.\n");
+        log::warn!("tnoimod has been set to its default value: 0.");
     }
     vals.trnqsmod = if let Some(val) = specs.trnqsmod { val as usize } else { 0 };
     if vals.trnqsmod > 1 {
         vals.trnqsmod = 0;
-        println!("Warning: trnqsmod has been set to its default value: 0.\n");
+        log::warn!("trnqsmod has been set to its default value: 0.");
     }
     vals.acnqsmod = if let Some(val) = specs.acnqsmod { val as usize } else { 0 };
     if vals.acnqsmod > 1 {
         vals.acnqsmod = 0;
-        println!("Warning: acnqsmod has been set to its default value: 0.\n");
+        log::warn!("acnqsmod has been set to its default value: 0.");
     }
     vals.mtrlmod = if let Some(val) = specs.mtrlmod { val as usize } else { 0 };
     if vals.mtrlmod > 1 {
         vals.mtrlmod = 0;
-        println!("Warning: mtrlmod has been set to its default value: 0.\n");
+        log::warn!("mtrlmod has been set to its default value: 0.");
     }
     vals.mtrlcompatmod = if let Some(val) = specs.mtrlcompatmod { val as usize } else { 0 };
     if vals.mtrlcompatmod > 1 {
         vals.mtrlcompatmod = 0;
-        println!("Warning: mtrlcompatmod has been set to its default value: 0.\n");
+        log::warn!("mtrlcompatmod has been set to its default value: 0.");
     }
     vals.igcmod = if let Some(val) = specs.igcmod { val as usize } else { 0 };
     if vals.igcmod > 2 {
         vals.igcmod = 0;
-        println!("Warning: igcmod has been set to its default value: 0.\n");
+        log::warn!("igcmod has been set to its default value: 0.");
     }
     vals.igbmod = if let Some(val) = specs.igbmod { val as usize } else { 0 };
     if vals.igbmod > 1 {
         vals.igbmod = 0;
-        println!("Warning: igbmod has been set to its default value: 0.\n");
+        log::warn!("igbmod has been set to its default value: 0.");
     }
     vals.tempmod = if let Some(val) = specs.tempmod { val as usize } else { 0 };
     if vals.tempmod > 3 {
         vals.tempmod = 0;
-        println!("Warning: tempmod has been set to its default value: 0.\n");
+        log::warn!("tempmod has been set to its default value: 0.");
     }
     vals.wpemod = if let Some(val) = specs.wpemod { val as usize } else { 0 };
     if vals.wpemod > 1 {
         vals.wpemod = 0;
-        println!("Warning: wpemod has been set to its default value: 0.\n");
+        log::warn!("wpemod has been set to its default value: 0.");
     }
 
     // FIXME: range check these
@@ -1270,75 +1270,75 @@ pub(crate) fn resolve(specs: &Bsim4ModelSpecs) -> Bsim4ModelVals {
     // Value Range-Limiting and Related Stern Warnings
     if vals.pbs < 0.1 {
         vals.pbs = 0.1;
-        println!("Given pbs is less than 0.1. Pbs is set to 0.1.\n");
+        log::warn!("Given pbs is less than 0.1. Pbs is set to 0.1.");
     }
     if vals.pbsws < 0.1 {
         vals.pbsws = 0.1;
-        println!("Given pbsws is less than 0.1. Pbsws is set to 0.1.\n",);
+        log::warn!("Given pbsws is less than 0.1. Pbsws is set to 0.1.");
     }
     if vals.pbswgs < 0.1 {
         vals.pbswgs = 0.1;
-        println!("Given pbswgs is less than 0.1. Pbswgs is set to 0.1.\n",);
+        log::warn!("Given pbswgs is less than 0.1. Pbswgs is set to 0.1.");
     }
     if vals.pbd < 0.1 {
         vals.pbd = 0.1;
-        println!("Given pbd is less than 0.1. Pbd is set to 0.1.\n");
+        log::warn!("Given pbd is less than 0.1. Pbd is set to 0.1.");
     }
     if vals.pbswd < 0.1 {
         vals.pbswd = 0.1;
-        println!("Given pbswd is less than 0.1. Pbswd is set to 0.1.\n",);
+        log::warn!("Given pbswd is less than 0.1. Pbswd is set to 0.1.");
     }
     if vals.pbswgd < 0.1 {
         vals.pbswgd = 0.1;
-        println!("Given pbswgd is less than 0.1. Pbswgd is set to 0.1.\n",);
+        log::warn!("Given pbswgd is less than 0.1. Pbswgd is set to 0.1.");
     }
     if vals.ijthdfwd <= 0.0 {
         vals.ijthdfwd = 0.0;
-        println!("Ijthdfwd reset to %g.\n"); //vals.ijthdfwd);
+        log::warn!("Ijthdfwd reset to %g."); //vals.ijthdfwd);
     }
     if vals.ijthsfwd <= 0.0 {
         vals.ijthsfwd = 0.0;
-        println!("Ijthsfwd reset to %g.\n"); //vals.ijthsfwd);
+        log::warn!("Ijthsfwd reset to %g."); //vals.ijthsfwd);
     }
     if vals.ijthdrev <= 0.0 {
         vals.ijthdrev = 0.0;
-        println!("Ijthdrev reset to %g.\n"); //vals.ijthdrev);
+        log::warn!("Ijthdrev reset to %g."); //vals.ijthdrev);
     }
     if vals.ijthsrev <= 0.0 {
         vals.ijthsrev = 0.0;
-        println!("Ijthsrev reset to %g.\n"); //vals.ijthsrev);
+        log::warn!("Ijthsrev reset to %g."); //vals.ijthsrev);
     }
     if vals.xjbvd <= 0.0 && (vals.diomod == 2 || vals.diomod == 0) {
         vals.xjbvd = 0.0;
-        println!("Xjbvd reset to %g.\n"); //vals.xjbvd);
+        log::warn!("Xjbvd reset to %g."); //vals.xjbvd);
     }
     if vals.xjbvs <= 0.0 && (vals.diomod == 2 || vals.diomod == 0) {
         vals.xjbvs = 0.0;
-        println!("Xjbvs reset to %g.\n"); //vals.xjbvs);
+        log::warn!("Xjbvs reset to %g."); //vals.xjbvs);
     }
     if vals.bvd <= 0.0 {
         vals.bvd = 0.0;
-        println!("BVD reset to %g.\n"); //vals.bvd);
+        log::warn!("BVD reset to %g."); //vals.bvd);
     }
     if vals.bvs <= 0.0 {
         vals.bvs = 0.0;
-        println!("BVS reset to %g.\n"); //vals.bvs);
+        log::warn!("BVS reset to %g."); //vals.bvs);
     }
     if vals.jtweff < 0.0 {
         vals.jtweff = 0.0;
-        println!("TAT width dependence effect is negative. Jtweff is clamped to zero.\n",);
+        log::warn!("TAT width dependence effect is negative. Jtweff is clamped to zero.");
     }
     if vals.cjsws < 0.0 {
         vals.cjsws = 0.0;
-        println!("CJSWS is negative. Cjsws is clamped to zero.\n");
+        log::warn!("CJSWS is negative. Cjsws is clamped to zero.");
     }
     if vals.cjswd < 0.0 {
         vals.cjswd = 0.0;
-        println!("CJSWD is negative. Cjswd is clamped to zero.\n");
+        log::warn!("CJSWD is negative. Cjswd is clamped to zero.");
     }
     if vals.wlod < 0.0 {
         vals.wlod = 0.0;
-        println!("Warning: WLOD = %g is less than 0. 0.0 is used\n");
+        log::warn!("WLOD = %g is less than 0. 0.0 is used");
     }
 
     // Derived params
@@ -4,10 +4,12 @@ use crate::comps::mos::MosType;
 use crate::sparse21::{Eindex, Matrix};
 
 impl Bsim4 {
-    /// Convert operating-point into matrix stamps
+    /// Convert our guessed operating-point into matrix stamps
     pub(crate) fn stamp(&self) -> Stamps<f64> {
-        // Extract the operating-point object
-        let newop = &self.guess;
+        self.stamp_op(&self.guess)
+    }
+    /// Convert operating-point `newop` into matrix stamps
+    pub(crate) fn stamp_op(&self, newop: &Bsim4OpPoint) -> Stamps<f64> {
 
         let mut ceqdrn: f64;
         let mut ceqbd: f64;
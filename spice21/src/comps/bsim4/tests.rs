@@ -196,3 +196,54 @@ fn test_bsim4_tran1() -> TestResult {
 
     Ok(())
 }
+
+/// Bulk admittance of an off NMOS, dominated by its reverse-biased drain-body junction capacitance.
+/// Below the corners of its (default, 50 Ohm) body resistances, `rbodymod=1` must match `rbodymod=0`.
+#[test]
+fn test_bsim4_rbodymod_ac() -> TestResult {
+    use crate::analysis::{ac, AcOptions};
+    use crate::circuit::*;
+    use NodeRef::Gnd;
+
+    let ckt = |rbodymod: usize| {
+        let mut ckt = Ckt::from_comps(vec![
+            Comp::Mos(Mosi {
+                name: "m1".into(),
+                ports: [Gnd, Gnd, Gnd, n("b")].into(),
+                model: "nmos".into(),
+                params: "junc".into(),
+            }),
+            Comp::V(Vi {
+                name: "v1".into(),
+                vdc: -0.5,
+                acm: 1.0,
+                p: n("b"),
+                n: Gnd,
+            }),
+        ]);
+        let mut model = Bsim4ModelSpecs::new(MosType::NMOS);
+        model.rbodymod = Some(rbodymod);
+        ckt.defs.bsim4.add_model("nmos", model);
+        ckt.defs.bsim4.add_inst(Bsim4InstSpecs {
+            name: "junc".into(),
+            ad: Some(1e-10),
+            pd: Some(40e-6),
+            ..Default::default()
+        });
+        ckt
+    };
+    let args = || AcOptions {
+        fstart: 1_000_000,
+        fstop: 100_000_000,
+        npts: 6,
+    };
+    let base = ac(ckt(0), None, Some(args()))?;
+    let rbody = ac(ckt(1), None, Some(args()))?;
+    let (i0, i1) = (&base.map["v1"], &rbody.map["v1"]);
+    assert(i1.len()).eq(i0.len())?;
+    for (a, b) in i0.iter().zip(i1.iter()) {
+        assert(a.im.abs()).gt(0.0)?;
+        assert((a.im - b.im).abs()).lt(1e-3 * a.im.abs())?;
+    }
+    Ok(())
+}
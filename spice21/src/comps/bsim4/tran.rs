@@ -1,6 +1,8 @@
 use super::{Bsim4, Bsim4OpPoint};
 use crate::analysis::TranState;
 
+const NQS_SCALING_FACTOR: f64 = 1.0e-9; // FIXME: lump this in an NQS thing
+
 impl Bsim4 {
     ///
     /// Given the DC portion of an operating point,
@@ -9,16 +11,91 @@ impl Bsim4 {
     /// with its (admittedly ill-defined) DC and charge parameters set.
     ///
     pub(crate) fn tran_op(&self, newop: &mut Bsim4OpPoint, tran_state: &TranState) {
-        let nqs_scaling_factor = 1.0e-9; // FIXME: lump this in an NQS thing
+        // TODO: the BSIM4 reference implementation essentially bakes numerical integration in here,
+        // ignoring the circuit/ analysis integration method.
+        // All of these impedances are calculated as g = C/dt, e.g. using Backward Euler.
+        // Figure out whether this is the implementation intent, or just for reference.
+        self.cap_op(newop, 1.0 / tran_state.dt);
 
-        // Initially zero all capacitances and their impedances
-        // Many complicated paths through the code below do not assure they are otherwise initialized.
-        
-        let mut ceqqjd = 0.0;
-        let mut ceqqjs = 0.0;
         let mut cqcheq = 0.0;
         let mut cqdef = 0.0;
+        let Bsim4OpPoint { qdef, vbs, vbd, vgb, vgmb, vbs_jct, vbd_jct, .. } = *newop;
+        let Bsim4OpPoint {
+            gcggb, gcgdb, gcgsb, gcdgb, gcdgmb, gcddb, gcdbdb, gcdsb, gcbgb, gcbgmb, gcbdb, gcbsb, gcgmdb, gcgmsb, gcgmgmb, gcsbsb, ..
+        } = *newop;
+        let Bsim4OpPoint {
+            gqdef, ggtg, ggtd, ggts, dxpart, ddxpart_dVg, ddxpart_dVd, ddxpart_dVs, gcqgb, gcqdb, gcqsb, ..
+        } = *newop;
+
+        // We borrow the BSIM4 reference implementation's practice here,
+        // of only using numerical integration to calculate `i = dq/dt`,
+        // Ignoring the impedance and RHS terms it calculates.
+        let (_g, i, _r) = tran_state.integrate(newop.qb - self.op.qb, 0.0, 0.0, self.op.cqb);
+        newop.cqb = i;
+        let (_g, i, _r) = tran_state.integrate(newop.qg - self.op.qg, 0.0, 0.0, self.op.cqg);
+        newop.cqg = i;
+        let (_g, i, _r) = tran_state.integrate(newop.qd - self.op.qd, 0.0, 0.0, self.op.cqd);
+        newop.cqd = i;
+        if self.model.trnqsmod != 0 {
+            newop.qcdump = qdef * NQS_SCALING_FACTOR;
+            let (_g, i, _r) = tran_state.integrate(newop.qcdump - self.op.qcdump, 0.0, 0.0, self.op.cqcdump);
+            newop.cqcdump = i;
+        }
+        if self.model.rgatemod == 3 {
+            let (_g, i, _r) = tran_state.integrate(newop.qgmid - self.op.qgmid, 0.0, 0.0, self.op.cqgmid);
+            newop.cqgmid = i;
+        }
+        if self.model.rbodymod != 0 {
+            let (_g, i, _r) = tran_state.integrate(newop.qbs - self.op.qbs, 0.0, 0.0, self.op.cqbs);
+            newop.cqbs = i;
+            let (_g, i, _r) = tran_state.integrate(newop.qbd - self.op.qbd, 0.0, 0.0, self.op.cqbd);
+            newop.cqbd = i;
+        }
+
+        // Calculate equivalent RHS current
+        let cqgate = newop.cqg;
+        let cqbody = newop.cqb;
+        let cqdrn = newop.cqd;
+
+        let mut ceqqg = cqgate - gcggb * vgb + gcgdb * vbd + gcgsb * vbs;
+        let mut ceqqd = cqdrn - gcdgb * vgb - gcdgmb * vgmb + (gcddb + gcdbdb) * vbd - gcdbdb * vbd_jct + gcdsb * vbs;
+        let mut ceqqb = cqbody - gcbgb * vgb - gcbgmb * vgmb + gcbdb * vbd + gcbsb * vbs;
+
+        if self.model.rgatemod == 3 {
+            newop.ceqqgmid = newop.cqgmid + gcgmdb * vbd + gcgmsb * vbs - gcgmgmb * vgmb;
+        }
+        if self.model.rbodymod != 0 {
+            newop.ceqqjs = newop.cqbs + gcsbsb * vbs_jct;
+            newop.ceqqjd = newop.cqbd + gcdbdb * vbd_jct;
+        }
+
+        if self.model.trnqsmod != 0 {
+            let (_g, i, _r) = tran_state.integrate(newop.qcheq - self.op.qcheq, 0.0, 0.0, self.op.cqcheq);
+            newop.cqcheq = i;
+
+            let T0 = ggtg * vgb - ggtd * vbd - ggts * vbs;
+            ceqqg += T0;
+            let T1 = qdef * newop.gtau;
+            ceqqd -= dxpart * T0 + T1 * (ddxpart_dVg * vgb - ddxpart_dVd * vbd - ddxpart_dVs * vbs);
+            cqdef = newop.cqcdump - gqdef * qdef;
+            cqcheq = newop.cqcheq - (gcqgb * vgb - gcqdb * vbd - gcqsb * vbs) + T0;
+        }
+
+        newop.cqcheq = cqcheq;
+        newop.cqdef = cqdef;
+        newop.ceqqg = ceqqg;
+        newop.ceqqd = ceqqd;
+        newop.ceqqb = ceqqb;
+    }
 
+    ///
+    /// Add the capacitance (charge-derivative) terms of operating-point `newop`, each scaled by `ag0`.
+    /// Transient analysis uses `ag0 = 1/dt`, producing companion-model conductances.
+    /// AC analysis uses `ag0 = 1`, producing the capacitances themselves.
+    ///
+    pub(crate) fn cap_op(&self, newop: &mut Bsim4OpPoint, ag0: f64) {
+        // Initially zero all capacitances and their impedances
+        // Many complicated paths through the code below do not assure they are otherwise initialized.
         let mut gcdgb = 0.0;
         let mut gcddb = 0.0;
         let mut gcdsb = 0.0;
@@ -66,24 +143,9 @@ impl Bsim4 {
         let mut dsxpart_dVb = 0.0;
         let mut dsxpart_dVs = 0.0;
 
-        let Bsim4OpPoint { qdrn, qgdo, qgso, qdef, .. } = *newop;
+        let Bsim4OpPoint { qdrn, qdef, .. } = *newop;
         let Bsim4OpPoint { cgdo, cgso, .. } = *newop;
-        let Bsim4OpPoint {
-            vbs,
-            vbd,
-            vgb,
-            vgmb,
-            vbs_jct,
-            vbd_jct,
-            ..
-        } = *newop;
-
-        // TODO: the BSIM4 reference implementation essentially bakes numerical integration in here,
-        // ignoring the circuit/ analysis integration method.
-        // All of these impedances are calculated as g = C/dt, e.g. using Backward Euler.
-        // Figure out whether this is the implementation intent, or just for reference.
 
-        let ag0 = 1.0 / tran_state.dt;
         if newop.mode > 0 {
             if self.model.trnqsmod == 0 {
                 if self.model.rgatemod == 3 {
@@ -161,7 +223,7 @@ impl Bsim4 {
             } else {
                 let qcheq = newop.qchqs;
                 let CoxWL = self.model_derived.coxe * self.size_params.weffCV * self.intp.nf * self.size_params.leffCV;
-                let T0 = qdef * nqs_scaling_factor / CoxWL;
+                let T0 = qdef * NQS_SCALING_FACTOR / CoxWL;
 
                 ggtg = T0 * newop.gcrgg;
                 newop.gtg = ggtg;
@@ -171,7 +233,7 @@ impl Bsim4 {
                 newop.gts = ggts;
                 ggtb = T0 * newop.gcrgb;
                 newop.gtb = ggtb;
-                gqdef = nqs_scaling_factor * ag0;
+                gqdef = NQS_SCALING_FACTOR * ag0;
 
                 gcqgb = newop.cqgb * ag0;
                 gcqdb = newop.cqdb * ag0;
@@ -338,7 +400,7 @@ impl Bsim4 {
             } else {
                 let qcheq = newop.qchqs;
                 let CoxWL = self.model_derived.coxe * self.size_params.weffCV * self.intp.nf * self.size_params.leffCV;
-                let T0 = qdef * nqs_scaling_factor / CoxWL;
+                let T0 = qdef * NQS_SCALING_FACTOR / CoxWL;
                 ggtg = T0 * newop.gcrgg;
                 newop.gtg = ggtg;
                 ggts = T0 * newop.gcrgd;
@@ -347,7 +409,7 @@ impl Bsim4 {
                 newop.gtd = ggtd;
                 ggtb = T0 * newop.gcrgb;
                 newop.gtb = ggtb;
-                gqdef = nqs_scaling_factor * ag0;
+                gqdef = NQS_SCALING_FACTOR * ag0;
 
                 gcqgb = newop.cqgb * ag0;
                 gcqdb = newop.cqsb * ag0;
@@ -441,76 +503,17 @@ impl Bsim4 {
             }
         } 
 
-        // We borrow the BSIM4 reference implementation's practice here,
-        // of only using numerical integration to calculate `i = dq/dt`,
-        // Ignoring the impedance and RHS terms it calculates.
-        let (_g, i, _r) = tran_state.integrate(newop.qb - self.op.qb, 0.0, 0.0, self.op.cqb);
-        newop.cqb = i;
-        let (_g, i, _r) = tran_state.integrate(newop.qg - self.op.qg, 0.0, 0.0, self.op.cqg);
-        newop.cqg = i;
-        let (_g, i, _r) = tran_state.integrate(newop.qd - self.op.qd, 0.0, 0.0, self.op.cqd);
-        newop.cqd = i;
-        if self.model.trnqsmod != 0 {
-            newop.qcdump = qdef * nqs_scaling_factor;
-            let (_g, i, _r) = tran_state.integrate(newop.qcdump - self.op.qcdump, 0.0, 0.0, self.op.cqcdump);
-            newop.cqcdump = i;
-        }
-        if self.model.rgatemod == 3 {
-            let (_g, i, _r) = tran_state.integrate(newop.qgmid - self.op.qgmid, 0.0, 0.0, self.op.cqgmid);
-            newop.cqgmid = i;
-        }
-        if self.model.rbodymod != 0 {
-            let (_g, i, _r) = tran_state.integrate(newop.qbs - self.op.qbs, 0.0, 0.0, self.op.cqbs);
-            newop.cqbs = i;
-            let (_g, i, _r) = tran_state.integrate(newop.qbd - self.op.qbd, 0.0, 0.0, self.op.cqbd);
-            newop.cqbd = i;
-        }
-
-        // Calculate equivalent RHS current
-        let cqgate = newop.cqg;
-        let cqbody = newop.cqb;
-        let cqdrn = newop.cqd;
-
-        let mut ceqqg = cqgate - gcggb * vgb + gcgdb * vbd + gcgsb * vbs;
-        let mut ceqqd = cqdrn - gcdgb * vgb - gcdgmb * vgmb + (gcddb + gcdbdb) * vbd - gcdbdb * vbd_jct + gcdsb * vbs;
-        let mut ceqqb = cqbody - gcbgb * vgb - gcbgmb * vgmb + gcbdb * vbd + gcbsb * vbs;
-
-        if self.model.rgatemod == 3 {
-            newop.ceqqgmid = newop.cqgmid + gcgmdb * vbd + gcgmsb * vbs - gcgmgmb * vgmb;
-        }
-        if self.model.rbodymod != 0 {
-            newop.ceqqjs = newop.cqbs + gcsbsb * vbs_jct;
-            newop.ceqqjd = newop.cqbd + gcdbdb * vbd_jct;
-        }
-
-        if self.model.trnqsmod != 0 {
-            let (_g, i, _r) = tran_state.integrate(newop.qcheq - self.op.qcheq, 0.0, 0.0, self.op.cqcheq);
-            newop.cqcheq = i;
-
-            let T0 = ggtg * vgb - ggtd * vbd - ggts * vbs;
-            ceqqg += T0;
-            let T1 = qdef * newop.gtau;
-            ceqqd -= dxpart * T0 + T1 * (ddxpart_dVg * vgb - ddxpart_dVd * vbd - ddxpart_dVs * vbs);
-            cqdef = newop.cqcdump - gqdef * qdef;
-            cqcheq = newop.cqcheq - (gcqgb * vgb - gcqdb * vbd - gcqsb * vbs) + T0;
-        }
-
         newop.gqdef = gqdef;
         newop.ggtg = ggtg;
         newop.ggtd = ggtd;
         newop.ggts = ggts;
         newop.ggtb = ggtb;
         newop.gcsbsb = gcsbsb;
-        newop.gcsbsb = gcsbsb;
+        newop.gcdbdb = gcdbdb;
         newop.gcqsb = gcqsb;
         newop.gcqdb = gcqdb;
         newop.gcqgb = gcqgb;
-        newop.cqcheq = cqcheq;
-        newop.cqdef = cqdef;
         newop.gcqbb = gcqbb;
-        newop.ceqqg = ceqqg;
-        newop.ceqqd = ceqqd;
-        newop.ceqqb = ceqqb;
         newop.gcgbb = gcgbb;
         newop.gcsbb = gcsbb;
         newop.gcssb = gcssb;
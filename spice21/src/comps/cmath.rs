@@ -13,12 +13,6 @@ pub(crate) fn exp(a: f64) -> f64 {
 pub(crate) fn MAX(a: f64, b: f64) -> f64 {
     a.max(b)
 }
-pub(crate) fn MIN(a: f64, b: f64) -> f64 {
-    a.min(b)
-}
-pub(crate) fn abs(a: f64) -> f64 {
-    a.abs()
-}
 pub(crate) fn atan(a: f64) -> f64 {
     a.atan()
 }
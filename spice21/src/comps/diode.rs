@@ -4,7 +4,8 @@
 use std::collections::HashMap;
 
 use super::consts;
-use super::{make_matrix_elem, Component};
+use super::limiting::pnjlim;
+use super::{make_matrix_elem, safe_exp, Component};
 use crate::analysis::{AnalysisInfo, Options, Stamps, VarIndex, VarKind, Variables};
 use crate::defs::DefPtr;
 use crate::proto;
@@ -33,6 +34,7 @@ attr!(
         (ibv, f64, 1e-3, "Current at reverse breakdown voltage"), //
         (rs, f64, 0.0, "Ohmic resistance"),
         (cj0, f64, 0.0, "Junction capacitance"),
+        (ikf, f64, 0.0, "Forward knee current"), // FIXME: Optional, default effectively +inf
         // Removed, redudant params:
         // (cjo, f64, 0.0, "Junction capacitance"),
         // (cond, f64, 0.0, "Ohmic conductance"),
@@ -47,6 +49,10 @@ impl DiodeModel {
     pub(crate) fn has_bv(&self) -> bool {
         self.bv != 0.0
     }
+    /// Boolean indication of a high-injection knee current
+    pub(crate) fn has_ikf(&self) -> bool {
+        self.ikf != 0.0
+    }
     /// Derive a `DiodeModel` from (`Option`-based) `proto::DiodeModel`
     /// Apply defaults for all unspecified fields
     pub(crate) fn from(specs: proto::DiodeModel) -> Self {
@@ -66,6 +72,7 @@ impl DiodeModel {
             ibv: if let Some(val) = specs.ibv { val } else { 1e-3 },
             rs: if let Some(val) = specs.rs { val } else { 0.0 },
             cj0: if let Some(val) = specs.cj0 { val } else { 0.0 },
+            ikf: if let Some(val) = specs.ikf { val } else { 0.0 },
         }
     }
 }
@@ -98,10 +105,10 @@ pub struct DiodePorts {
     pub r: Option<VarIndex>,
 }
 impl DiodePorts {
-    pub(crate) fn from<P: Clone + Into<Option<VarIndex>>, T: SpNum>(path: String, model: &DiodeModel, p: P, n: P, vars: &mut Variables<T>) -> Self {
+    pub(crate) fn from<P: Clone + Into<Option<VarIndex>>, T: SpNum>(path: String, sep: &str, model: &DiodeModel, p: P, n: P, vars: &mut Variables<T>) -> Self {
         // Internal resistance node addition
         let r = if model.has_rs() {
-            let name = format!("{}.{}", path, "r");
+            let name = format!("{}{}{}", path, sep, "r");
             Some(vars.add(name, VarKind::V))
         } else {
             p.clone().into()
@@ -139,7 +146,8 @@ pub struct DiodeIntParams {
     pub f1: f64,
     pub f2: f64,
     pub f3: f64,
-    pub bv: f64, // Breakdown Voltage
+    pub bv: f64,  // Breakdown Voltage
+    pub ikf: f64, // Area-scaled forward knee current
 }
 impl DiodeIntParams {
     /// Derive Diode internal parameters from model, instance, and circuit options.
@@ -176,7 +184,7 @@ impl DiodeIntParams {
         let f1 = vjunc * (1.0 - (1.0 - model.m * xfc).exp()) / (1.0 - model.m);
         let dep_threshold = model.fc * model.vj;
         let vte = model.n * vt;
-        let vcrit = vte * (vte / (2.0 as f64).sqrt() / isat);
+        let vcrit = vte * (vte / (2.0 as f64).sqrt() / isat).ln();
 
         let mut bv = model.bv;
         if model.has_bv() {
@@ -194,6 +202,7 @@ impl DiodeIntParams {
         let gspr = gs * area;
         let cz = model.cj0 * area;
         let cz2 = cz / f2;
+        let ikf = model.ikf * area;
 
         DiodeIntParams {
             vt,
@@ -208,6 +217,7 @@ impl DiodeIntParams {
             f2,
             f3,
             bv,
+            ikf,
         }
     }
 }
@@ -226,25 +236,34 @@ pub struct Diode {
 impl Diode {
     /// Voltage limiting
     fn limit(&self, vd: f64, past: Option<f64>) -> f64 {
-        let vnew = vd;
         let vold = if let Some(v) = past { v } else { self.guess.vd };
         let intp = &*self.intp.read();
-        // Typical case - unchanged
-        if vnew <= intp.vcrit || (vnew - vold).abs() <= 2.0 * intp.vte {
-            return vnew;
-        }
-        // Limiting cases
-        if vold > 0.0 {
-            let arg = 1.0 + (vnew - vold) / intp.vte;
-            if arg > 0.0 {
-                return vold + intp.vte * arg.ln();
+        pnjlim(vd, vold, intp.vte, intp.vcrit)
+    }
+    /// DC junction current and conductance at junction voltage `vd`
+    fn dc_current(model: &DiodeModel, intp: &DiodeIntParams, vd: f64, gmin: f64) -> (f64, f64) {
+        if !model.has_bv() || vd >= -intp.bv {
+            // Regular (non-breakdown) operation
+            let (e, de) = safe_exp(vd / intp.vte);
+            let (mut i, mut g) = (intp.isat * (e - 1.0), intp.isat * de / intp.vte);
+            if model.has_ikf() && i > 0.0 {
+                // High-level injection, above the knee current `ikf`
+                let sqrt_ikf = (i / intp.ikf).sqrt();
+                g *= (1.0 + sqrt_ikf / 2.0) / (1.0 + sqrt_ikf).powi(2);
+                i /= 1.0 + sqrt_ikf;
             }
-            return intp.vcrit;
+            (i + gmin * vd, g + gmin)
+        } else {
+            // Breakdown - vd < BV
+            let (e, de) = safe_exp((vd - intp.bv) / intp.vte);
+            (-intp.isat * e + gmin * vd, intp.isat * de / intp.vte + gmin)
         }
-        return intp.vte * (vnew / intp.vte).ln();
     }
 }
 impl Component for Diode {
+    fn linear(&self) -> bool {
+        false
+    }
     fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
         self.matps.pp = make_matrix_elem(mat, self.ports.p, self.ports.p);
         self.matps.pr = make_matrix_elem(mat, self.ports.p, self.ports.r);
@@ -286,22 +305,17 @@ impl Component for Diode {
         // Extract our differential voltage
         let mut vd = guess.get(self.ports.r) - guess.get(self.ports.n);
         // Apply inter-estimate limits
-        if model.has_bv() && vd < (10.0 * intp.vte - intp.bv).min(0.0) {
-            let vtemp = self.limit(-intp.bv, Some(intp.bv - self.guess.vd));
-            vd = vtemp - intp.bv;
-        } else {
-            vd = self.limit(vd, None);
+        if opts.limiting {
+            if model.has_bv() && vd < (10.0 * intp.vte - intp.bv).min(0.0) {
+                // Limit the (positive) voltage beyond breakdown
+                let vtemp = self.limit(-(vd + intp.bv), Some(-(self.guess.vd + intp.bv)));
+                vd = -(vtemp + intp.bv);
+            } else {
+                vd = self.limit(vd, None);
+            }
         }
         // Calculate diode current and its derivative, conductance
-        let (mut id, mut gd) = if !model.has_bv() || vd >= -intp.bv {
-            // Regular (non-breakdown) operation
-            let e = (vd / intp.vte).exp();
-            (intp.isat * (e - 1.0) + gmin * vd, intp.isat * e / intp.vte + gmin)
-        } else {
-            // Breakdown - vd < BV
-            let e = ((vd - intp.bv) / intp.vte).exp();
-            (-intp.isat * e + gmin * vd, intp.isat * e / intp.vte + gmin)
-        };
+        let (mut id, mut gd) = Self::dc_current(model, intp, vd, gmin);
 
         // Charge Storage Calculations
         let (qd, cd) = if vd < intp.dep_threshold {
@@ -352,6 +366,24 @@ impl Component for Diode {
             b: vec![(self.ports.r, -irhs), (self.ports.n, irhs)],
         };
     }
+    fn residual(&self, x: &Variables<f64>, opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        let model = &*self.model.read();
+        let intp = &*self.intp.read();
+        let (vp, vr, vn) = (x.get(self.ports.p), x.get(self.ports.r), x.get(self.ports.n));
+        let (id, _) = Self::dc_current(model, intp, vr - vn, opts.gmin);
+        let ir = intp.gspr * (vp - vr);
+        Some(vec![(self.ports.p, ir), (self.ports.r, id - ir), (self.ports.n, -id)])
+    }
+    fn op_point(&self) -> Vec<(&'static str, f64)> {
+        let op = &self.op;
+        vec![("vd", op.vd), ("id", op.id), ("gd", op.gd), ("cd", op.cd), ("charge", op.charge), ("p", op.p)]
+    }
+    fn power(&self, x: &Variables<f64>) -> Option<f64> {
+        // Junction power, plus that of any series resistance
+        let intp = &*self.intp.read();
+        let vrs = x.get(self.ports.p) - x.get(self.ports.r);
+        Some(self.op.p + intp.gspr * vrs * vrs)
+    }
 }
 
 /// Simplified Diode Model, Level "Zero"
@@ -367,6 +399,9 @@ pub(crate) struct Diode0 {
     np: Option<Eindex>,
 }
 impl Component for Diode0 {
+    fn linear(&self) -> bool {
+        false
+    }
     fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
         self.pp = make_matrix_elem(mat, self.p, self.p);
         self.pn = make_matrix_elem(mat, self.p, self.n);
@@ -377,8 +412,9 @@ impl Component for Diode0 {
         let vp = guess.get(self.p);
         let vn = guess.get(self.n);
         let vd = (vp - vn).max(-1.5).min(1.5);
-        let i = self.isat * ((vd / self.vt).exp() - 1.0);
-        let gd = (self.isat / self.vt) * (vd / self.vt).exp();
+        let (e, de) = safe_exp(vd / self.vt);
+        let i = self.isat * (e - 1.0);
+        let gd = (self.isat / self.vt) * de;
         let irhs = i - vd * gd;
 
         return Stamps {
@@ -401,6 +437,16 @@ use crate::defs::{CacheEntry, ModelInstanceCache};
 ///
 pub(crate) type DiodeDefs = ModelInstanceCache<DiodeModel, DiodeInstParams, DiodeCacheEntry>;
 
+impl DiodeDefs {
+    /// Re-derive internal parameters of every instance, e.g. after a change in circuit temperature
+    pub(crate) fn rederive(&self, opts: &Options) {
+        for entry in self.cache.values() {
+            let intp = DiodeIntParams::derive(&entry.model.read(), &entry.inst.read(), opts);
+            *entry.intp.write() = intp;
+        }
+    }
+}
+
 ///
 /// # Diode Cache Entry
 /// Includes the internal/ derived, instance, and model parameters
@@ -0,0 +1,138 @@
+//!
+//! # Component Test Harness
+//!
+//! Loads a single `Component` at a set of fixed node voltages,
+//! for unit-testing its `Stamps` without a full circuit solve.
+//!
+
+use num::Complex;
+
+use super::Component;
+use crate::analysis::{AcOptions, AcState, AnalysisInfo, Options, Stamps, VarIndex, Variables};
+use crate::sparse21::Matrix;
+
+/// Matrix entry at which AC and finite-difference DC conductances disagree
+#[derive(Debug)]
+pub(crate) struct StampMismatch {
+    pub(crate) row: String,
+    pub(crate) col: String,
+    pub(crate) ac: f64,
+    pub(crate) fd: f64,
+}
+
+/// Single-Component Stamp Harness
+pub(crate) struct StampHarness {
+    pub(crate) vars: Variables<f64>,
+    pub(crate) mat: Matrix<f64>,
+}
+impl StampHarness {
+    /// Create a harness with voltage-variables named `names`, all initially zero
+    pub(crate) fn new(names: &[&str]) -> Self {
+        let mut vars = Variables::new();
+        for name in names.iter() {
+            vars.addv(name.to_string());
+        }
+        Self { vars, mat: Matrix::new() }
+    }
+    /// Retrieve the variable named `name`. Panics if not present.
+    pub(crate) fn var(&self, name: &str) -> Option<VarIndex> {
+        match self.vars.find(name) {
+            Some(v) => Some(v),
+            None => panic!("StampHarness Variable Not Found: {}", name),
+        }
+    }
+    /// Set the voltage of variable `name` to `val`
+    pub(crate) fn set(&mut self, name: &str, val: f64) {
+        let v = self.var(name).unwrap();
+        self.vars.set(v, val);
+    }
+    /// Create `comp`'s matrix elements, and load it at our current voltages
+    pub(crate) fn load<C: Component>(&mut self, comp: &mut C, an: &AnalysisInfo, opts: &Options) -> Stamps<f64> {
+        comp.create_matrix_elems(&mut self.mat);
+        comp.load(&self.vars, an, opts)
+    }
+    /// Sum `stamps` into dense (matrix, rhs) form, indexed by variable
+    pub(crate) fn dense(&mut self, stamps: &Stamps<f64>) -> (Vec<Vec<f64>>, Vec<f64>) {
+        let n = self.vars.len();
+        self.mat.reset();
+        for (ei, val) in stamps.g.iter() {
+            if let Some(ei) = ei {
+                self.mat.update(*ei, *val);
+            }
+        }
+        let mut g = vec![vec![0.0; n]; n];
+        for (r, row) in g.iter_mut().enumerate() {
+            for (c, val) in row.iter_mut().enumerate() {
+                if let Some(x) = self.mat.get(r, c) {
+                    *val = x;
+                }
+            }
+        }
+        let mut b = vec![0.0; n];
+        for (vi, val) in stamps.b.iter() {
+            if let Some(vi) = vi {
+                b[vi.0] += *val;
+            }
+        }
+        (g, b)
+    }
+    /// Net current out of each variable, `G*x - b`, from `comp`'s DC stamps at our current voltages.
+    /// Requires `comp`'s matrix elements, e.g. from a prior `load`.
+    pub(crate) fn currents<C: Component>(&mut self, comp: &mut C, opts: &Options) -> Vec<f64> {
+        let stamps = comp.load(&self.vars, &AnalysisInfo::OP, opts);
+        let (g, b) = self.dense(&stamps);
+        let x: Vec<f64> = (0..self.vars.len()).map(|i| self.vars.get(Some(VarIndex(i)))).collect();
+        g.iter().zip(b.iter()).map(|(row, bi)| row.iter().zip(x.iter()).map(|(gi, xi)| gi * xi).sum::<f64>() - bi).collect()
+    }
+    /// Compare the real part of `comp`'s AC stamps against a central finite-difference
+    /// of its DC currents, about our current voltages.
+    /// Returns each matrix entry differing by more than `abstol + reltol * |fd|`.
+    pub(crate) fn check_ac<C: Component>(&mut self, comp: &mut C, opts: &Options, dv: f64, reltol: f64, abstol: f64) -> Vec<StampMismatch> {
+        let n = self.vars.len();
+        // Load and commit the operating point, then collect its AC stamps
+        self.load(comp, &AnalysisInfo::OP, opts);
+        comp.commit();
+        let mut cmat = Matrix::<Complex<f64>>::new();
+        comp.create_matrix_elems(&mut cmat);
+        let acopts = AcOptions { fstart: 1, fstop: 1, npts: 1 };
+        let state = AcState { omega: 1.0 };
+        let cguess = Variables::<Complex<f64>>::new();
+        let stamps = comp.load_ac(&cguess, &AnalysisInfo::AC(&acopts, &state), opts);
+        for (ei, val) in stamps.g.iter() {
+            if let Some(ei) = ei {
+                cmat.update(*ei, *val);
+            }
+        }
+        // Finite-difference each column of the DC Jacobian
+        let mut fd = vec![vec![0.0; n]; n];
+        for c in 0..n {
+            let x0 = self.vars.get(Some(VarIndex(c)));
+            self.vars.set(VarIndex(c), x0 + dv);
+            let ip = self.currents(comp, opts);
+            self.vars.set(VarIndex(c), x0 - dv);
+            let im = self.currents(comp, opts);
+            self.vars.set(VarIndex(c), x0);
+            for (r, row) in fd.iter_mut().enumerate() {
+                row[c] = (ip[r] - im[r]) / (2.0 * dv);
+            }
+        }
+        // Restore the DC stamps' matrix-pointers, and our nominal op-point
+        self.load(comp, &AnalysisInfo::OP, opts);
+
+        let mut rv = vec![];
+        for (r, row) in fd.iter().enumerate() {
+            for (c, fdval) in row.iter().enumerate() {
+                let ac = cmat.get(r, c).map_or(0.0, |v| v.re);
+                if (ac - fdval).abs() > abstol + reltol * fdval.abs() {
+                    rv.push(StampMismatch {
+                        row: self.vars.name(VarIndex(r)).to_string(),
+                        col: self.vars.name(VarIndex(c)).to_string(),
+                        ac,
+                        fd: *fdval,
+                    });
+                }
+            }
+        }
+        rv
+    }
+}
@@ -0,0 +1,119 @@
+//!
+//! # Newton Voltage Limiting
+//!
+//! Inter-iteration limits on the terminal voltages of nonlinear devices, as in SPICE's `DEVpnjlim`, `DEVfetlim`, and `DEVlimvds`.
+//! Each takes a device's newly-proposed voltage `vnew` and its voltage from the prior Newton iteration `vold`,
+//! and returns a damped `vnew`. Devices then linearize around the limited voltages.
+//!
+
+/// PN-Junction Voltage Limiting, as SPICE's `pnjlim`.
+/// Limits the Newton update of junction voltage from `vold` to `vnew`,
+/// for a junction with (emission-scaled) thermal voltage `vt` and critical voltage `vcrit`.
+pub(crate) fn pnjlim(vnew: f64, vold: f64, vt: f64, vcrit: f64) -> f64 {
+    // Typical case - unchanged
+    if vnew <= vcrit || (vnew - vold).abs() <= 2.0 * vt {
+        return vnew;
+    }
+    // Limiting cases
+    if vold > 0.0 {
+        let arg = 1.0 + (vnew - vold) / vt;
+        if arg > 0.0 {
+            return vold + vt * arg.ln();
+        }
+        return vcrit;
+    }
+    vt * (vnew / vt).ln()
+}
+
+/// FET Gate Voltage Limiting, as SPICE's `fetlim`.
+/// Limits the update of gate-source voltage from `vold` to `vnew`, for a device with threshold `vto`.
+/// Steps are largest well above threshold, and smallest when turning on.
+pub(crate) fn fetlim(vnew: f64, vold: f64, vto: f64) -> f64 {
+    let vtsthi = (2.0 * (vold - vto)).abs() + 2.0;
+    let vtstlo = vtsthi / 2.0 + 2.0;
+    let vtox = vto + 3.5;
+    let delv = vnew - vold;
+
+    if vold >= vto {
+        if vold >= vtox {
+            if delv <= 0.0 {
+                // Going off
+                if vnew >= vtox {
+                    if -delv > vtstlo {
+                        return vold - vtstlo;
+                    }
+                } else {
+                    return vnew.max(vto + 2.0);
+                }
+            } else if delv >= vtsthi {
+                // Staying on
+                return vold + vtsthi;
+            }
+        } else if delv <= 0.0 {
+            // Middle region, decreasing
+            return vnew.max(vto - 0.5);
+        } else {
+            // Middle region, increasing
+            return vnew.min(vto + 4.0);
+        }
+    } else if delv <= 0.0 {
+        // Off, and going further off
+        if -delv > vtsthi {
+            return vold - vtsthi;
+        }
+    } else {
+        // Off, and turning on
+        let vtemp = vto + 0.5;
+        if vnew <= vtemp {
+            if delv > vtstlo {
+                return vold + vtstlo;
+            }
+        } else {
+            return vtemp;
+        }
+    }
+    vnew
+}
+
+/// FET Drain-Source Voltage Limiting, as SPICE's `limvds`.
+/// Limits the update of (non-negative) drain-source voltage from `vold` to `vnew`.
+pub(crate) fn limvds(vnew: f64, vold: f64) -> f64 {
+    if vold >= 3.5 {
+        if vnew > vold {
+            return vnew.min(3.0 * vold + 2.0);
+        }
+        if vnew < 3.5 {
+            return vnew.max(2.0);
+        }
+    } else if vnew > vold {
+        return vnew.min(4.0);
+    } else {
+        return vnew.max(-0.5);
+    }
+    vnew
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert::assert;
+    use crate::spresult::TestResult;
+
+    #[test]
+    fn test_limits() -> TestResult {
+        // Small steps pass through unchanged
+        assert(pnjlim(0.71, 0.7, 0.025, 0.6)).eq(0.71)?;
+        assert(fetlim(1.1, 1.0, 0.5)).eq(1.1)?;
+        assert(limvds(1.1, 1.0)).eq(1.1)?;
+        // Junctions step logarithmically beyond `vcrit`
+        let v = pnjlim(5.0, 0.7, 0.025, 0.6);
+        assert(v).isclose(0.7 + 0.025 * (1.0 + 4.3 / 0.025f64).ln(), 1e-12)?;
+        // Devices turning on stop just past threshold
+        assert(fetlim(5.0, 0.0, 0.5)).eq(1.0)?;
+        // Drain-source voltages are clamped
+        assert(limvds(10.0, 1.0)).eq(4.0)?;
+        assert(limvds(-2.0, 1.0)).eq(-0.5)?;
+        assert(limvds(50.0, 5.0)).eq(17.0)?;
+        Ok(())
+    }
+}
@@ -9,7 +9,7 @@ use num::Complex;
 use std::marker::PhantomData;
 use std::ops::{Index, IndexMut};
 
-use super::analysis::{AnalysisInfo, Options, Stamps, VarIndex, Variables};
+use super::analysis::{AnalysisInfo, ChargeInteg, NoiseSource, Options, Stamps, VarIndex, Variables};
 use super::sparse21::{Eindex, Matrix};
 use crate::{SpNum, SpResult};
 
@@ -19,6 +19,11 @@ pub mod mos;
 pub mod bsim4;
 pub mod diode;
 pub mod cmath;
+pub mod limiting;
+pub mod plugin;
+pub mod behavioral;
+#[cfg(test)]
+pub(crate) mod harness;
 
 /// Constants
 pub mod consts {
@@ -62,7 +67,15 @@ pub(crate) enum ComponentSolver<'a> {
     Vsrc(Vsrc),
     Isrc(Isrc),
     Capacitor(Capacitor),
+    Inductor(Inductor),
+    Mutual(Mutual),
     Resistor(Resistor),
+    Vccs(Vccs),
+    Vcvs(Vcvs),
+    Vswitch(Vswitch),
+    Multiplied(Multiplied<'a>),
+    Plugin(plugin::Plugin),
+    Bsrc(behavioral::Bsrc),
     Diode0(diode::Diode0),
     Diode(diode::Diode),
     Mos0(mos::Mos0),
@@ -86,17 +99,64 @@ pub(crate) trait Component {
     fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
         panic!("AC Not Implemented For This Component!")
     }
+    /// Small-signal noise sources at frequency `f`, around our last committed operating point.
+    /// Noise-free components return an empty list.
+    fn load_noise(&self, _f: f64, _opts: &Options) -> Vec<NoiseSource> {
+        vec![]
+    }
     /// DC, Tran, and all real-valued analysis load method
     fn load(&mut self, guess: &Variables<f64>, an: &AnalysisInfo, opts: &Options) -> Stamps<f64>;
+    /// Matrix-free DC residual, i.e. the nonlinear `f(x)` at variable-values `x`.
+    /// Evaluated directly from device equations, rather than from linearized `Stamps`.
+    /// Returns (variable, value) contributions: currents leaving each node, and errors in any branch equations.
+    /// Components without such an evaluation return `None`.
+    fn residual(&self, _x: &Variables<f64>, _opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        None
+    }
+    /// Instantaneous power dissipation at variable-values `x`, in Watts.
+    /// Components without a power calculation return `None`.
+    fn power(&self, _x: &Variables<f64>) -> Option<f64> {
+        None
+    }
+    /// Named operating-point quantities, from our last committed operating point, e.g. for debug reports.
+    /// Components without internal operating-point state return an empty list.
+    fn op_point(&self) -> Vec<(&'static str, f64)> {
+        vec![]
+    }
+    /// Boolean indication of whether our latest (uncommitted) guess changed state discontinuously
+    /// from our last committed operating point, e.g. a switch toggling.
+    /// Transient analysis cuts such steps, to locate the change in time.
+    fn switched(&self) -> bool {
+        false
+    }
+    /// Boolean indication of whether our stamps are independent of the present guess,
+    /// i.e. whether a single linear solve suffices. Nonlinear devices return `false`.
+    fn linear(&self) -> bool {
+        true
+    }
     /// Create matrix elements, adding them to mutable Matrix `mat`
     fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>);
 }
 
+/// Value of an independent source with nominal value `val`.
+/// Scaled by `opts.src_factor` in DC operating points, e.g. during source stepping.
+fn src_value(val: f64, an: &AnalysisInfo, opts: &Options) -> f64 {
+    match an {
+        AnalysisInfo::OP => val * opts.src_factor,
+        _ => val,
+    }
+}
+
+/// Voltage Source
+/// Adds branch-current variable `ivar`, positive flowing into terminal `p`.
+/// AC magnitude `acm` applies only in AC analysis; DC and transient use `v`.
+/// With `v` = 0, serves as an ammeter; the branch-current rows and columns
+/// have no diagonal entries, and rely on the solver's pivoting.
 pub struct Vsrc {
-    v: f64,
+    pub(crate) v: f64,
     acm: f64,
-    p: Option<VarIndex>,
-    n: Option<VarIndex>,
+    pub(crate) p: Option<VarIndex>,
+    pub(crate) n: Option<VarIndex>,
     ivar: VarIndex,
     pi: Option<Eindex>,
     ip: Option<Eindex>,
@@ -130,12 +190,17 @@ impl Component for Vsrc {
         self.ni = make_matrix_elem(mat, self.n, Some(self.ivar));
         self.in_ = make_matrix_elem(mat, Some(self.ivar), self.n);
     }
-    fn load(&mut self, _guess: &Variables<f64>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<f64> {
+    fn load(&mut self, _guess: &Variables<f64>, an: &AnalysisInfo, opts: &Options) -> Stamps<f64> {
         return Stamps {
             g: vec![(self.pi, 1.0), (self.ip, 1.0), (self.ni, -1.0), (self.in_, -1.0)],
-            b: vec![(Some(self.ivar), self.v)],
+            b: vec![(Some(self.ivar), src_value(self.v, an, opts))],
         };
     }
+    fn residual(&self, x: &Variables<f64>, _opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        let i = x.get(Some(self.ivar));
+        let vd = x.get(self.p) - x.get(self.n);
+        Some(vec![(self.p, i), (self.n, -i), (Some(self.ivar), vd - self.v)])
+    }
     fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
         return Stamps {
             g: vec![
@@ -160,13 +225,52 @@ pub struct Capacitor {
     np: Option<Eindex>,
     op: CapOpPoint,
     guess: CapOpPoint,
+    esr: Option<SeriesRes>,
+}
+
+/// Series resistance of a Capacitor or Inductor.
+/// Connects external terminal `p` to the element's (internal) positive terminal.
+#[derive(Default)]
+struct SeriesRes {
+    g: f64,
+    p: Option<VarIndex>,
+    pp: Option<Eindex>,
+    pi: Option<Eindex>,
+    ip: Option<Eindex>,
+    ii: Option<Eindex>,
+}
+
+impl SeriesRes {
+    fn new(r: f64, p: Option<VarIndex>) -> Self {
+        SeriesRes {
+            g: 1.0 / r,
+            p,
+            ..Default::default()
+        }
+    }
+    /// Create matrix elements, to internal node `i`
+    fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>, i: Option<VarIndex>) {
+        self.pp = make_matrix_elem(mat, self.p, self.p);
+        self.pi = make_matrix_elem(mat, self.p, i);
+        self.ip = make_matrix_elem(mat, i, self.p);
+        self.ii = make_matrix_elem(mat, i, i);
+    }
+    fn stamps(&self) -> Vec<(Option<Eindex>, f64)> {
+        let g = self.g;
+        vec![(self.pp, g), (self.ii, g), (self.pi, -g), (self.ip, -g)]
+    }
+    /// DC residual, with internal node `i`
+    fn residual(&self, x: &Variables<f64>, i: Option<VarIndex>) -> Vec<(Option<VarIndex>, f64)> {
+        let cur = self.g * (x.get(self.p) - x.get(i));
+        vec![(self.p, cur), (i, -cur)]
+    }
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Default)]
 struct CapOpPoint {
     v: f64,
     q: f64,
-    i: f64,
+    companion: ChargeInteg,
 }
 
 impl Capacitor {
@@ -178,12 +282,25 @@ impl Capacitor {
             ..Default::default()
         }
     }
+    /// Add series resistance `r`, from external terminal `p` to our (now internal) positive terminal.
+    pub fn with_esr(mut self, r: f64, p: Option<VarIndex>) -> Capacitor {
+        self.esr = Some(SeriesRes::new(r, p));
+        self
+    }
+    /// Series-resistance matrix stamps
+    fn esr_stamps(&self) -> Vec<(Option<Eindex>, f64)> {
+        self.esr.as_ref().map_or(vec![], SeriesRes::stamps)
+    }
     fn q(&self, v: f64) -> f64 {
         return self.c * v;
     }
     fn dq_dv(&self, _v: f64) -> f64 {
         return self.c;
     }
+    /// Transient companion model (equivalent conductance and current) of the most recently committed time-step
+    pub fn companion(&self) -> ChargeInteg {
+        self.op.companion.clone()
+    }
 }
 
 impl Component for Capacitor {
@@ -192,10 +309,13 @@ impl Component for Capacitor {
         self.pn = make_matrix_elem(mat, self.p, self.n);
         self.np = make_matrix_elem(mat, self.n, self.p);
         self.nn = make_matrix_elem(mat, self.n, self.n);
+        if let Some(ref mut e) = self.esr {
+            e.create_matrix_elems(mat, self.p);
+        }
     }
     /// Load our last guess as the new operating point
     fn commit(&mut self) {
-        self.op = self.guess;
+        self.op = self.guess.clone();
     }
     fn load(&mut self, guess: &Variables<f64>, an: &AnalysisInfo, _opts: &Options) -> Stamps<f64> {
         let vd = guess.get(self.p) - guess.get(self.n);
@@ -205,36 +325,252 @@ impl Component for Capacitor {
             AnalysisInfo::OP => {
                 // FIXME: calculating this during DCOP, so we copy cleanly afterward
                 // Should probably just find a way to calculate it then
-                self.guess = CapOpPoint { v: vd, q: q, i: 0.0 };
-                return Stamps::new();
+                self.guess = CapOpPoint {
+                    v: vd,
+                    q: q,
+                    companion: ChargeInteg::default(),
+                };
+                return Stamps {
+                    g: self.esr_stamps(),
+                    b: vec![],
+                };
             }
             AnalysisInfo::TRAN(_, state) => {
-                let (g, i, rhs) = state.integrate(q - self.op.q, self.dq_dv(vd), vd, self.op.i);
-                self.guess = CapOpPoint { v: vd, q: q, i: i };
+                let companion = state.integq(q - self.op.q, self.dq_dv(vd), vd, &self.op.companion);
+                let ChargeInteg { g, rhs, .. } = companion;
+                self.guess = CapOpPoint { v: vd, q: q, companion };
 
+                let mut g = vec![(self.pp, g), (self.nn, g), (self.pn, -g), (self.np, -g)];
+                g.extend(self.esr_stamps());
                 return Stamps {
-                    g: vec![(self.pp, g), (self.nn, g), (self.pn, -g), (self.np, -g)],
+                    g,
                     b: vec![(self.p, -rhs), (self.n, rhs)],
                 };
             }
             AnalysisInfo::AC(_o, _s) => panic!("HOW WE GET HERE?!?"),
         }
     }
+    fn residual(&self, x: &Variables<f64>, _opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        // Open circuit in DC, other than any series resistance
+        Some(self.esr.as_ref().map_or(vec![], |e| e.residual(x, self.p)))
+    }
     fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
         let an_st = match an {
             AnalysisInfo::AC(_, state) => state,
             _ => panic!("Invalid AC AnalysisInfo"),
         };
         let c = self.dq_dv(0.0);
-        return Stamps {
-            g: vec![
-                (self.pp, Complex::new(0.0, an_st.omega * c)),
-                (self.nn, Complex::new(0.0, an_st.omega * c)),
-                (self.pn, Complex::new(0.0, -an_st.omega * c)),
-                (self.np, Complex::new(0.0, -an_st.omega * c)),
-            ],
-            b: vec![],
+        let mut g = vec![
+            (self.pp, Complex::new(0.0, an_st.omega * c)),
+            (self.nn, Complex::new(0.0, an_st.omega * c)),
+            (self.pn, Complex::new(0.0, -an_st.omega * c)),
+            (self.np, Complex::new(0.0, -an_st.omega * c)),
+        ];
+        g.extend(self.esr_stamps().into_iter().map(|(e, g)| (e, Complex::new(g, 0.0))));
+        return Stamps { g, b: vec![] };
+    }
+}
+
+/// Inductor
+/// Adds current-variable `ivar`, flowing from `p` to `n` through the inductor.
+/// Its branch equation is `v(p) - v(n) = d(flux)/dt`, for flux `l * i`:
+/// a short in DC, and integrated as a charge-element in transient.
+pub struct Inductor {
+    l: f64,
+    p: Option<VarIndex>,
+    n: Option<VarIndex>,
+    ivar: VarIndex,
+    pi: Option<Eindex>,
+    ip: Option<Eindex>,
+    ni: Option<Eindex>,
+    in_: Option<Eindex>,
+    ii: Option<Eindex>,
+    op: IndOpPoint,
+    guess: IndOpPoint,
+    rser: Option<SeriesRes>,
+}
+
+#[derive(Clone, Default)]
+struct IndOpPoint {
+    flux: f64,
+    /// Flux integration, with the inductor voltage in the role of current
+    companion: ChargeInteg,
+}
+
+impl Inductor {
+    pub fn new(l: f64, p: Option<VarIndex>, n: Option<VarIndex>, ivar: VarIndex) -> Inductor {
+        Inductor {
+            l,
+            p,
+            n,
+            ivar,
+            pi: None,
+            ip: None,
+            ni: None,
+            in_: None,
+            ii: None,
+            op: IndOpPoint::default(),
+            guess: IndOpPoint::default(),
+            rser: None,
+        }
+    }
+    /// Add series resistance `r`, from external terminal `p` to our (now internal) positive terminal.
+    pub fn with_rser(mut self, r: f64, p: Option<VarIndex>) -> Inductor {
+        self.rser = Some(SeriesRes::new(r, p));
+        self
+    }
+    /// Incidence stamps, common to all analyses
+    fn incidence(&self) -> Vec<(Option<Eindex>, f64)> {
+        let mut g = vec![(self.pi, 1.0), (self.ip, 1.0), (self.ni, -1.0), (self.in_, -1.0)];
+        g.extend(self.rser.as_ref().map_or(vec![], SeriesRes::stamps));
+        g
+    }
+}
+
+impl Component for Inductor {
+    fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
+        let i = Some(self.ivar);
+        self.pi = make_matrix_elem(mat, self.p, i);
+        self.ip = make_matrix_elem(mat, i, self.p);
+        self.ni = make_matrix_elem(mat, self.n, i);
+        self.in_ = make_matrix_elem(mat, i, self.n);
+        self.ii = make_matrix_elem(mat, i, i);
+        if let Some(ref mut r) = self.rser {
+            r.create_matrix_elems(mat, self.p);
+        }
+    }
+    fn commit(&mut self) {
+        self.op = self.guess.clone();
+    }
+    fn load(&mut self, guess: &Variables<f64>, an: &AnalysisInfo, _opts: &Options) -> Stamps<f64> {
+        let i = guess.get(Some(self.ivar));
+        let flux = self.l * i;
+        match *an {
+            AnalysisInfo::OP => {
+                self.guess = IndOpPoint {
+                    flux,
+                    companion: ChargeInteg::default(),
+                };
+                Stamps {
+                    g: self.incidence(),
+                    b: vec![],
+                }
+            }
+            AnalysisInfo::TRAN(_, state) => {
+                // Integrate flux as a charge, with the inductor voltage in the role of current
+                let companion = state.integq(flux - self.op.flux, self.l, i, &self.op.companion);
+                let ChargeInteg { g, rhs, .. } = companion;
+                self.guess = IndOpPoint { flux, companion };
+                let mut g = vec![(self.ii, -g)];
+                g.extend(self.incidence());
+                Stamps {
+                    g,
+                    b: vec![(Some(self.ivar), rhs)],
+                }
+            }
+            AnalysisInfo::AC(_o, _s) => panic!("HOW WE GET HERE?!?"),
+        }
+    }
+    fn residual(&self, x: &Variables<f64>, _opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        // Short circuit in DC
+        let i = x.get(Some(self.ivar));
+        let vd = x.get(self.p) - x.get(self.n);
+        let mut r = vec![(self.p, i), (self.n, -i), (Some(self.ivar), vd)];
+        r.extend(self.rser.as_ref().map_or(vec![], |s| s.residual(x, self.p)));
+        Some(r)
+    }
+    fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
+        let an_st = match an {
+            AnalysisInfo::AC(_, state) => state,
+            _ => panic!("Invalid AC AnalysisInfo"),
         };
+        let mut g: Vec<(Option<Eindex>, Complex<f64>)> = self.incidence().into_iter().map(|(e, v)| (e, Complex::new(v, 0.0))).collect();
+        g.push((self.ii, Complex::new(0.0, -an_st.omega * self.l)));
+        Stamps { g, b: vec![] }
+    }
+}
+
+/// Mutual Inductance
+/// Couples the branch equations of two `Inductor`s, with current-variables `i1` and `i2`.
+/// Adds flux `m * i2` to the first and `m * i1` to the second.
+pub struct Mutual {
+    m: f64,
+    i1: VarIndex,
+    i2: VarIndex,
+    e12: Option<Eindex>,
+    e21: Option<Eindex>,
+    op: MutOpPoint,
+    guess: MutOpPoint,
+}
+
+/// Mutual flux and voltage, added to each of the coupled inductors
+#[derive(Clone, Default)]
+struct MutOpPoint {
+    flux: [f64; 2],
+    companion: [ChargeInteg; 2],
+}
+
+impl Mutual {
+    pub fn new(m: f64, i1: VarIndex, i2: VarIndex) -> Mutual {
+        Mutual {
+            m,
+            i1,
+            i2,
+            e12: None,
+            e21: None,
+            op: MutOpPoint::default(),
+            guess: MutOpPoint::default(),
+        }
+    }
+}
+
+impl Component for Mutual {
+    fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
+        self.e12 = make_matrix_elem(mat, Some(self.i1), Some(self.i2));
+        self.e21 = make_matrix_elem(mat, Some(self.i2), Some(self.i1));
+    }
+    fn commit(&mut self) {
+        self.op = self.guess.clone();
+    }
+    fn load(&mut self, guess: &Variables<f64>, an: &AnalysisInfo, _opts: &Options) -> Stamps<f64> {
+        let (i1, i2) = (guess.get(Some(self.i1)), guess.get(Some(self.i2)));
+        let flux = [self.m * i2, self.m * i1];
+        match *an {
+            AnalysisInfo::OP => {
+                self.guess = MutOpPoint {
+                    flux,
+                    companion: Default::default(),
+                };
+                Stamps { g: vec![], b: vec![] }
+            }
+            AnalysisInfo::TRAN(_, state) => {
+                // Integrate each mutual flux as in `Inductor`, controlled by the other's current
+                let c1 = state.integq(flux[0] - self.op.flux[0], self.m, i2, &self.op.companion[0]);
+                let c2 = state.integq(flux[1] - self.op.flux[1], self.m, i1, &self.op.companion[1]);
+                let stamps = Stamps {
+                    g: vec![(self.e12, -c1.g), (self.e21, -c2.g)],
+                    b: vec![(Some(self.i1), c1.rhs), (Some(self.i2), c2.rhs)],
+                };
+                self.guess = MutOpPoint { flux, companion: [c1, c2] };
+                stamps
+            }
+            AnalysisInfo::AC(_o, _s) => panic!("HOW WE GET HERE?!?"),
+        }
+    }
+    fn residual(&self, _x: &Variables<f64>, _opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        // No contribution in DC
+        Some(vec![])
+    }
+    fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
+        let an_st = match an {
+            AnalysisInfo::AC(_, state) => state,
+            _ => panic!("Invalid AC AnalysisInfo"),
+        };
+        let g = Complex::new(0.0, -an_st.omega * self.m);
+        Stamps {
+            g: vec![(self.e12, g), (self.e21, g)],
+            b: vec![],
+        }
     }
 }
 
@@ -308,6 +644,25 @@ impl Component for Resistor {
             b: vec![],
         };
     }
+    fn residual(&self, x: &Variables<f64>, _opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        use TwoTerm::{N, P};
+        let i = self.g * (x.get(self.terms[P]) - x.get(self.terms[N]));
+        Some(vec![(self.terms[P], i), (self.terms[N], -i)])
+    }
+    fn load_noise(&self, _f: f64, opts: &Options) -> Vec<NoiseSource> {
+        // Thermal noise, 4kTG
+        use TwoTerm::{N, P};
+        vec![NoiseSource {
+            p: self.terms[P],
+            n: self.terms[N],
+            psd: 4.0 * consts::KB * opts.temp * self.g,
+        }]
+    }
+    fn power(&self, x: &Variables<f64>) -> Option<f64> {
+        use TwoTerm::{N, P};
+        let v = x.get(self.terms[P]) - x.get(self.terms[N]);
+        Some(self.g * v * v)
+    }
     fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
         use TwoTerm::{N, P};
         return Stamps {
@@ -325,24 +680,424 @@ impl Component for Resistor {
 #[derive(Default)]
 pub struct Isrc {
     i: f64,
+    acm: f64,
     p: Option<VarIndex>,
     n: Option<VarIndex>,
 }
 
 impl Isrc {
     pub fn new(i: f64, p: Option<VarIndex>, n: Option<VarIndex>) -> Isrc {
-        Isrc { i, p, n }
+        Isrc { i, acm: 0.0, p, n }
+    }
+    /// Add AC magnitude `acm`
+    pub fn with_acm(mut self, acm: f64) -> Isrc {
+        self.acm = acm;
+        self
     }
 }
 
 impl Component for Isrc {
+    fn update(&mut self, val: f64) {
+        self.i = val;
+    }
     fn create_matrix_elems<T: SpNum>(&mut self, _mat: &mut Matrix<T>) {}
-    fn load(&mut self, _guess: &Variables<f64>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<f64> {
+    fn load(&mut self, _guess: &Variables<f64>, an: &AnalysisInfo, opts: &Options) -> Stamps<f64> {
+        let i = src_value(self.i, an, opts);
         return Stamps {
             g: vec![],
-            b: vec![(self.p, self.i), (self.n, -self.i)],
+            b: vec![(self.p, i), (self.n, -i)],
         };
     }
+    fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
+        let acm = Complex::new(self.acm, 0.0);
+        Stamps {
+            g: vec![],
+            b: vec![(self.p, acm), (self.n, -acm)],
+        }
+    }
+    fn residual(&self, _x: &Variables<f64>, _opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        Some(vec![(self.p, -self.i), (self.n, self.i)])
+    }
+}
+
+/// Voltage-Controlled Current Source
+/// Current `i`, flowing from `p` to `n` through the source, is a polynomial (as SPICE's `POLY`)
+/// of one or more controlling voltages. Coefficients are ordered by increasing degree:
+/// the constant term, then each linear term, then each product of two controls
+/// (e.g. `vc1^2, vc1*vc2, vc2^2`), and so on.
+pub struct Vccs {
+    coeffs: Vec<f64>,
+    exps: Vec<Vec<u32>>,
+    p: Option<VarIndex>,
+    n: Option<VarIndex>,
+    ctrl: Vec<(Option<VarIndex>, Option<VarIndex>)>,
+    matps: Vec<[Option<Eindex>; 4]>,
+    op: Vec<f64>,
+    guess: Vec<f64>,
+}
+
+impl Vccs {
+    pub fn new(coeffs: Vec<f64>, p: Option<VarIndex>, n: Option<VarIndex>, ctrl: Vec<(Option<VarIndex>, Option<VarIndex>)>) -> Vccs {
+        let exps = poly_exponents(ctrl.len(), coeffs.len());
+        Vccs {
+            coeffs,
+            exps,
+            p,
+            n,
+            matps: vec![[None; 4]; ctrl.len()],
+            op: vec![0.0; ctrl.len()],
+            guess: vec![0.0; ctrl.len()],
+            ctrl,
+        }
+    }
+    /// Current and its partial derivatives with respect to each control, at control-voltages `vc`
+    fn eval(&self, vc: &[f64]) -> (f64, Vec<f64>) {
+        let mut i = 0.0;
+        let mut gs = vec![0.0; vc.len()];
+        for (c, e) in self.coeffs.iter().zip(self.exps.iter()) {
+            let term: f64 = vc.iter().zip(e.iter()).map(|(v, k)| v.powi(*k as i32)).product();
+            i += c * term;
+            for d in 0..vc.len() {
+                if e[d] > 0 {
+                    let dterm: f64 = vc
+                        .iter()
+                        .zip(e.iter())
+                        .enumerate()
+                        .map(|(j, (v, k))| if j == d { *k as f64 * v.powi(*k as i32 - 1) } else { v.powi(*k as i32) })
+                        .product();
+                    gs[d] += c * dterm;
+                }
+            }
+        }
+        (i, gs)
+    }
+    fn vc(&self, x: &Variables<f64>) -> Vec<f64> {
+        self.ctrl.iter().map(|(cp, cn)| x.get(*cp) - x.get(*cn)).collect()
+    }
+    /// Conductance stamps of transconductances `gs`
+    fn g_stamps(&self, gs: &[f64]) -> Vec<(Option<Eindex>, f64)> {
+        let mut g = vec![];
+        for (m, gk) in self.matps.iter().zip(gs.iter()) {
+            g.extend(vec![(m[0], *gk), (m[1], -gk), (m[2], -gk), (m[3], *gk)]);
+        }
+        g
+    }
+}
+
+/// Exponents of each term of a SPICE-style polynomial in `ndim` variables, for its first `nterms` coefficients.
+/// Terms are ordered by degree, and within each degree by their (non-decreasing) lists of variable-indices.
+fn poly_exponents(ndim: usize, nterms: usize) -> Vec<Vec<u32>> {
+    let mut rv = vec![vec![0; ndim]];
+    if ndim == 0 {
+        return rv;
+    }
+    let mut combos: Vec<Vec<usize>> = vec![vec![]];
+    while rv.len() < nterms {
+        // Extend each combination of the prior degree by each variable no lower than its last
+        let mut next = vec![];
+        for c in combos.iter() {
+            for d in *c.last().unwrap_or(&0)..ndim {
+                let mut n = c.clone();
+                n.push(d);
+                next.push(n);
+            }
+        }
+        for c in next.iter() {
+            let mut e = vec![0; ndim];
+            for d in c.iter() {
+                e[*d] += 1;
+            }
+            rv.push(e);
+        }
+        combos = next;
+    }
+    rv.truncate(nterms);
+    rv
+}
+
+impl Component for Vccs {
+    fn commit(&mut self) {
+        self.op = self.guess.clone();
+    }
+    fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
+        for (m, (cp, cn)) in self.matps.iter_mut().zip(self.ctrl.iter()) {
+            *m = [
+                make_matrix_elem(mat, self.p, *cp),
+                make_matrix_elem(mat, self.p, *cn),
+                make_matrix_elem(mat, self.n, *cp),
+                make_matrix_elem(mat, self.n, *cn),
+            ];
+        }
+    }
+    fn load(&mut self, guess: &Variables<f64>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<f64> {
+        let vc = self.vc(guess);
+        let (i, gs) = self.eval(&vc);
+        // Linearize around `vc`: i = irhs + sum(g * vc)
+        let irhs = i - gs.iter().zip(vc.iter()).map(|(g, v)| g * v).sum::<f64>();
+        let g = self.g_stamps(&gs);
+        self.guess = gs;
+        Stamps {
+            g,
+            b: vec![(self.p, -irhs), (self.n, irhs)],
+        }
+    }
+    fn residual(&self, x: &Variables<f64>, _opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        let (i, _) = self.eval(&self.vc(x));
+        Some(vec![(self.p, i), (self.n, -i)])
+    }
+    fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
+        let g = self.g_stamps(&self.op);
+        Stamps {
+            g: g.into_iter().map(|(e, g)| (e, Complex::new(g, 0.0))).collect(),
+            b: vec![],
+        }
+    }
+    fn linear(&self) -> bool {
+        self.exps.iter().all(|e| e.iter().sum::<u32>() <= 1)
+    }
+}
+
+/// Voltage-Controlled Voltage Source
+/// Adds current-variable `ivar`, flowing from `p` to `n` through the source, as for `Vsrc`.
+/// Its branch equation is `v(p) - v(n) = gain * (v(cp) - v(cn))`.
+pub struct Vcvs {
+    gain: f64,
+    p: Option<VarIndex>,
+    n: Option<VarIndex>,
+    cp: Option<VarIndex>,
+    cn: Option<VarIndex>,
+    ivar: VarIndex,
+    pi: Option<Eindex>,
+    ip: Option<Eindex>,
+    ni: Option<Eindex>,
+    in_: Option<Eindex>,
+    icp: Option<Eindex>,
+    icn: Option<Eindex>,
+}
+
+impl Vcvs {
+    pub fn new(gain: f64, p: Option<VarIndex>, n: Option<VarIndex>, cp: Option<VarIndex>, cn: Option<VarIndex>, ivar: VarIndex) -> Vcvs {
+        Vcvs {
+            gain,
+            p,
+            n,
+            cp,
+            cn,
+            ivar,
+            pi: None,
+            ip: None,
+            ni: None,
+            in_: None,
+            icp: None,
+            icn: None,
+        }
+    }
+    /// Matrix stamps, common to all analyses
+    fn g_stamps(&self) -> Vec<(Option<Eindex>, f64)> {
+        vec![
+            (self.pi, 1.0),
+            (self.ip, 1.0),
+            (self.ni, -1.0),
+            (self.in_, -1.0),
+            (self.icp, -self.gain),
+            (self.icn, self.gain),
+        ]
+    }
+}
+
+impl Component for Vcvs {
+    fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
+        let i = Some(self.ivar);
+        self.pi = make_matrix_elem(mat, self.p, i);
+        self.ip = make_matrix_elem(mat, i, self.p);
+        self.ni = make_matrix_elem(mat, self.n, i);
+        self.in_ = make_matrix_elem(mat, i, self.n);
+        self.icp = make_matrix_elem(mat, i, self.cp);
+        self.icn = make_matrix_elem(mat, i, self.cn);
+    }
+    fn load(&mut self, _guess: &Variables<f64>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<f64> {
+        Stamps {
+            g: self.g_stamps(),
+            b: vec![],
+        }
+    }
+    fn residual(&self, x: &Variables<f64>, _opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        let i = x.get(Some(self.ivar));
+        let vd = x.get(self.p) - x.get(self.n);
+        let vc = x.get(self.cp) - x.get(self.cn);
+        Some(vec![(self.p, i), (self.n, -i), (Some(self.ivar), vd - self.gain * vc)])
+    }
+    fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
+        Stamps {
+            g: self.g_stamps().into_iter().map(|(e, g)| (e, Complex::new(g, 0.0))).collect(),
+            b: vec![],
+        }
+    }
+}
+
+/// Voltage-Controlled Switch
+/// Conductance from `p` to `n` is `gon` when on, and `goff` when off.
+/// Turns on as the control voltage `v(cp) - v(cn)` rises above `von`, and off as it falls below `voff`.
+/// Between the two, it holds its committed state, providing hysteresis for `von > voff`.
+pub struct Vswitch {
+    gon: f64,
+    goff: f64,
+    von: f64,
+    voff: f64,
+    terms: TwoTerminals,
+    cp: Option<VarIndex>,
+    cn: Option<VarIndex>,
+    matps: TwoTermMatrixPointers,
+    op: bool,
+    guess: bool,
+}
+
+impl Vswitch {
+    pub fn new(ron: f64, roff: f64, von: f64, voff: f64, p: Option<VarIndex>, n: Option<VarIndex>, cp: Option<VarIndex>, cn: Option<VarIndex>) -> Vswitch {
+        Vswitch {
+            gon: 1.0 / ron,
+            goff: 1.0 / roff,
+            von,
+            voff,
+            terms: TwoTerminals([p, n]),
+            cp,
+            cn,
+            matps: TwoTermMatrixPointers([[None; 2]; 2]),
+            op: false,
+            guess: false,
+        }
+    }
+    /// On/off state at variable-values `x`, given our committed state
+    fn state(&self, x: &Variables<f64>) -> bool {
+        let vc = x.get(self.cp) - x.get(self.cn);
+        if vc > self.von {
+            true
+        } else if vc < self.voff {
+            false
+        } else {
+            self.op
+        }
+    }
+    fn g(&self, on: bool) -> f64 {
+        if on {
+            self.gon
+        } else {
+            self.goff
+        }
+    }
+    fn g_stamps(&self, g: f64) -> Vec<(Option<Eindex>, f64)> {
+        use TwoTerm::{N, P};
+        vec![
+            (self.matps[(P, P)], g),
+            (self.matps[(N, N)], g),
+            (self.matps[(P, N)], -g),
+            (self.matps[(N, P)], -g),
+        ]
+    }
+}
+
+impl Component for Vswitch {
+    fn commit(&mut self) {
+        self.op = self.guess;
+    }
+    fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
+        use TwoTerm::{N, P};
+        for l in [P, N].iter() {
+            for r in [P, N].iter() {
+                self.matps[(*l, *r)] = make_matrix_elem(mat, self.terms[*l], self.terms[*r]);
+            }
+        }
+    }
+    fn load(&mut self, guess: &Variables<f64>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<f64> {
+        self.guess = self.state(guess);
+        Stamps {
+            g: self.g_stamps(self.g(self.guess)),
+            b: vec![],
+        }
+    }
+    fn residual(&self, x: &Variables<f64>, _opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        use TwoTerm::{N, P};
+        let i = self.g(self.state(x)) * (x.get(self.terms[P]) - x.get(self.terms[N]));
+        Some(vec![(self.terms[P], i), (self.terms[N], -i)])
+    }
+    fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
+        let g = self.g_stamps(self.g(self.op));
+        Stamps {
+            g: g.into_iter().map(|(e, g)| (e, Complex::new(g, 0.0))).collect(),
+            b: vec![],
+        }
+    }
+    fn linear(&self) -> bool {
+        false
+    }
+    fn switched(&self) -> bool {
+        self.guess != self.op
+    }
+}
+
+/// Multiplied Component
+/// Represents `m` identical, parallel copies of `comp`, sharing all of its nodes, including any internal ones.
+/// All stamps, residuals, and powers are scaled by `m`; branch-current variables are those of each copy.
+pub(crate) struct Multiplied<'a> {
+    pub(crate) m: f64,
+    pub(crate) comp: Box<ComponentSolver<'a>>,
+}
+
+impl<'a> Multiplied<'a> {
+    fn scale<T: SpNum + From<f64>>(&self, stamps: Stamps<T>) -> Stamps<T> {
+        let m = T::from(self.m);
+        Stamps {
+            g: stamps.g.into_iter().map(|(e, v)| (e, v * m)).collect(),
+            b: stamps.b.into_iter().map(|(e, v)| (e, v * m)).collect(),
+        }
+    }
+}
+
+impl<'a> Component for Multiplied<'a> {
+    fn commit(&mut self) {
+        self.comp.commit()
+    }
+    fn update(&mut self, val: f64) {
+        self.comp.update(val)
+    }
+    fn validate(&self) -> SpResult<()> {
+        self.comp.validate()
+    }
+    fn load_ac(&mut self, guess: &Variables<Complex<f64>>, an: &AnalysisInfo, opts: &Options) -> Stamps<Complex<f64>> {
+        let stamps = self.comp.load_ac(guess, an, opts);
+        self.scale(stamps)
+    }
+    fn load(&mut self, guess: &Variables<f64>, an: &AnalysisInfo, opts: &Options) -> Stamps<f64> {
+        let stamps = self.comp.load(guess, an, opts);
+        self.scale(stamps)
+    }
+    fn residual(&self, x: &Variables<f64>, opts: &Options) -> Option<Vec<(Option<VarIndex>, f64)>> {
+        let res = self.comp.residual(x, opts)?;
+        Some(res.into_iter().map(|(v, r)| (v, r * self.m)).collect())
+    }
+    fn power(&self, x: &Variables<f64>) -> Option<f64> {
+        Some(self.comp.power(x)? * self.m)
+    }
+    fn load_noise(&self, f: f64, opts: &Options) -> Vec<NoiseSource> {
+        // Copies are uncorrelated, so their noise powers add
+        let mut srcs = self.comp.load_noise(f, opts);
+        for src in srcs.iter_mut() {
+            src.psd *= self.m;
+        }
+        srcs
+    }
+    fn op_point(&self) -> Vec<(&'static str, f64)> {
+        self.comp.op_point()
+    }
+    fn linear(&self) -> bool {
+        self.comp.linear()
+    }
+    fn switched(&self) -> bool {
+        self.comp.switched()
+    }
+    fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
+        self.comp.create_matrix_elems(mat)
+    }
 }
 
 /// Helper function to create matrix element at (row,col) if both are non-ground
@@ -352,3 +1107,18 @@ fn make_matrix_elem<T: SpNum>(mat: &mut Matrix<T>, row: Option<VarIndex>, col: O
     }
     return None;
 }
+
+/// Exponent-argument beyond which `safe_exp` is linearized
+pub(crate) const MAX_EXP_ARG: f64 = 80.0;
+
+/// Overflow-safe exponential, for junction currents and the like.
+/// Returns `exp(x)` and its derivative, linearized beyond `MAX_EXP_ARG` (as in SPICE).
+pub(crate) fn safe_exp(x: f64) -> (f64, f64) {
+    if x > MAX_EXP_ARG {
+        let e = MAX_EXP_ARG.exp();
+        (e * (1.0 + x - MAX_EXP_ARG), e)
+    } else {
+        let e = x.exp();
+        (e, e)
+    }
+}
@@ -10,11 +10,11 @@ use std::convert::From;
 use std::ops::{Index, IndexMut};
 
 use super::consts;
-use super::{make_matrix_elem, Component};
-use crate::analysis::{AnalysisInfo, ChargeInteg, Options, Stamps, TranState, VarIndex, Variables};
+use super::{limiting, make_matrix_elem, safe_exp, Component};
+use crate::analysis::{AnalysisInfo, ChargeInteg, NoiseSource, Options, Stamps, TranState, VarIndex, Variables};
 use crate::defs::DefPtr;
 use crate::sparse21::{Eindex, Matrix};
-use crate::{analysis, proto, SpNum};
+use crate::{analysis, proto, sperror, SpNum, SpResult};
 
 /// Mos Terminals, in SPICE order: d, g, s, b
 #[derive(Clone, Copy)]
@@ -24,7 +24,7 @@ pub enum MosTerm {
     S = 2,
     B = 3,
 }
-#[derive(Default)]
+#[derive(Default, Debug)]
 pub struct MosPorts<T> {
     pub d: T,
     pub g: T,
@@ -105,7 +105,7 @@ impl MosType {
 }
 
 /// Mos Level 1 Model Parameters
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Mos1Model {
     pub mos_type: MosType,
     pub vt0: f64,
@@ -137,20 +137,79 @@ pub struct Mos1Model {
     pub rsh: Option<f64>,
 }
 impl Mos1Model {
+    /// Set parameter `param` to `val`.
+    /// Parameters used to derive others at resolution-time (`tox`, `tnom`) cannot be set.
+    pub(crate) fn set(&mut self, param: &str, val: f64) -> SpResult<()> {
+        match param {
+            "vt0" => self.vt0 = val,
+            "kp" => self.kp = val,
+            "gamma" => self.gamma = val,
+            "phi" => self.phi = val,
+            "lambda" => self.lambda = val,
+            "cbd" => self.cbd = val,
+            "cbs" => self.cbs = val,
+            "is" => self.is = val,
+            "pb" => self.pb = val,
+            "cgso" => self.cgso = val,
+            "cgdo" => self.cgdo = val,
+            "cgbo" => self.cgbo = val,
+            "cj" => self.cj = val,
+            "mj" => self.mj = val,
+            "cjsw" => self.cjsw = val,
+            "mjsw" => self.mjsw = val,
+            "js" => self.js = val,
+            "ld" => self.ld = val,
+            "fc" => self.fc = val,
+            "kf" => self.kf = val,
+            "af" => self.af = val,
+            "rd" => self.rd = Some(val),
+            "rs" => self.rs = Some(val),
+            "rsh" => self.rsh = Some(val),
+            _ => return Err(sperror(format!("Invalid or Unsettable Mos1 Model Parameter: {}", param))),
+        }
+        Ok(())
+    }
+    /// Value of parameter `param`, for each of those which can be `set`.
+    /// Unspecified terminal resistances are reported as zero, i.e. none.
+    pub(crate) fn get(&self, param: &str) -> SpResult<f64> {
+        let val = match param {
+            "vt0" => self.vt0,
+            "kp" => self.kp,
+            "gamma" => self.gamma,
+            "phi" => self.phi,
+            "lambda" => self.lambda,
+            "cbd" => self.cbd,
+            "cbs" => self.cbs,
+            "is" => self.is,
+            "pb" => self.pb,
+            "cgso" => self.cgso,
+            "cgdo" => self.cgdo,
+            "cgbo" => self.cgbo,
+            "cj" => self.cj,
+            "mj" => self.mj,
+            "cjsw" => self.cjsw,
+            "mjsw" => self.mjsw,
+            "js" => self.js,
+            "ld" => self.ld,
+            "fc" => self.fc,
+            "kf" => self.kf,
+            "af" => self.af,
+            "rd" => self.rd.unwrap_or(0.0),
+            "rs" => self.rs.unwrap_or(0.0),
+            "rsh" => self.rsh.unwrap_or(0.0),
+            _ => return Err(sperror(format!("Invalid or Unsettable Mos1 Model Parameter: {}", param))),
+        };
+        Ok(val)
+    }
     pub(crate) fn resolve(specs: &proto::Mos1Model) -> Self {
-        use consts::{KB, KB_OVER_Q, KELVIN_TO_C, Q, SIO2_PERMITTIVITY, TEMP_REF};
+        use consts::{KELVIN_TO_C, Q, SIO2_PERMITTIVITY, TEMP_REF};
 
         // Convert from Proto-encoded enum form
         let mos_type = if specs.mos_type == 1 { MosType::PMOS } else { MosType::NMOS };
 
         // Nominal temperature. C to Kelvin conversion happens right here
         let tnom = if let Some(val) = specs.tnom { val + KELVIN_TO_C } else { TEMP_REF };
-        let fact1 = tnom / TEMP_REF;
-        let vtnom = tnom * KB_OVER_Q;
-        let kt1 = KB * tnom;
-        let egfet1 = 1.16 - (7.02e-4 * tnom.powi(2)) / (tnom + 1108.0);
-        let arg1 = -egfet1 / 2.0 / kt1 + 1.1150877 / (KB * 2.0 * TEMP_REF);
-        let pbfact1 = -2.0 * vtnom * (1.5 * fact1.ln() + Q * arg1);
+        let TnomParams { vtnom, egfet1, .. } = TnomParams::new(tnom);
 
         // Parameter defaults take very different tracks depending whether `tox` is specified.
         // First, the no-tox cases:
@@ -246,6 +305,27 @@ impl Default for Mos1Model {
     }
 }
 
+/// Nominal-temperature parameters,
+/// shared by `Mos1Model::resolve` and `Mos1InternalParams::derive`
+struct TnomParams {
+    fact1: f64,
+    vtnom: f64,
+    egfet1: f64,
+    pbfact1: f64,
+}
+impl TnomParams {
+    fn new(tnom: f64) -> Self {
+        use consts::{KB, KB_OVER_Q, Q, TEMP_REF};
+        let fact1 = tnom / TEMP_REF;
+        let vtnom = tnom * KB_OVER_Q;
+        let kt1 = KB * tnom;
+        let egfet1 = 1.16 - (7.02e-4 * tnom.powi(2)) / (tnom + 1108.0);
+        let arg1 = -egfet1 / 2.0 / kt1 + 1.1150877 / (KB * 2.0 * TEMP_REF);
+        let pbfact1 = -2.0 * vtnom * (1.5 * fact1.ln() + Q * arg1);
+        Self { fact1, vtnom, egfet1, pbfact1 }
+    }
+}
+
 /// Mos Level 1 Instance Parameters
 #[derive(Clone, Copy, Debug)]
 pub struct Mos1InstanceParams {
@@ -270,7 +350,7 @@ pub struct Mos1InstanceParams {
 impl Mos1InstanceParams {
     pub(crate) fn resolve(specs: &proto::Mos1InstParams) -> Self {
         Mos1InstanceParams {
-            m: if let Some(val) = specs.m { val } else { 0.0 },
+            m: if let Some(val) = specs.m { val } else { 1.0 },
             l: if let Some(val) = specs.l { val } else { 1e-6 },
             w: if let Some(val) = specs.w { val } else { 1e-6 },
             a_d: if let Some(val) = specs.a_d { val } else { 1e-12 },
@@ -289,6 +369,12 @@ impl Mos1InstanceParams {
         }
     }
 }
+impl Mos1InstanceParams {
+    /// Multiplier, i.e. number of parallel copies
+    pub(crate) fn m(&self) -> f64 {
+        self.m
+    }
+}
 impl Default for Mos1InstanceParams {
     fn default() -> Self {
         Self::resolve(&proto::Mos1InstParams::default())
@@ -318,19 +404,12 @@ pub(crate) struct Mos1InternalParams {
 impl Mos1InternalParams {
     /// Calculate derived parameters from instance and model parameters
     fn derive(model: &Mos1Model, inst: &Mos1InstanceParams, opts: &Options) -> Mos1InternalParams {
-        if let Some(t) = inst.temp {
-            panic!("Mos1 Instance Temperatures Are Not Supported");
-        }
-        let temp = opts.temp; // Note: in Kelvin
+        // Instance temperature, if specified, overrides the circuit's. Note: in Kelvin.
+        let temp = if let Some(t) = inst.temp { t } else { opts.temp };
 
-        // Nominal temperature params (note: repeated calcs from Model::derive)
+        // Nominal temperature params
         use consts::{KB, KB_OVER_Q, Q, TEMP_REF};
-        let fact1 = model.tnom / TEMP_REF;
-        let vtnom = model.tnom * KB_OVER_Q;
-        let kt1 = KB * model.tnom;
-        let egfet1 = 1.16 - (7.02e-4 * model.tnom.powi(2)) / (model.tnom + 1108.0);
-        let arg1 = -egfet1 / 2.0 / kt1 + 1.1150877 / (KB * 2.0 * TEMP_REF);
-        let pbfact1 = -2.0 * vtnom * (1.5 * fact1.ln() + Q * arg1);
+        let TnomParams { fact1, vtnom, egfet1, pbfact1 } = TnomParams::new(model.tnom);
 
         // Instance temperature params
         let kt = temp * KB;
@@ -364,11 +443,12 @@ impl Mos1InternalParams {
         let mut cjsw_t = model.cjsw * capfact;
         let bulkpot_t = fact2 * pbo + pbfact;
         let gmanew = (bulkpot_t - pbo) / pbo;
-        let capfact = 1.0 / (1.0 + model.mj * (4e-4 * (temp - TEMP_REF) - gmanew));
+        // Note these factors multiply, undoing the nominal-temperature divisions above
+        let capfact = 1.0 + model.mj * (4e-4 * (temp - TEMP_REF) - gmanew);
         cbd_t *= capfact;
         cbs_t *= capfact;
         cj_t *= capfact;
-        let capfact = 1.0 / (1.0 + model.mjsw * (4e-4 * (temp - TEMP_REF) - gmanew));
+        let capfact = 1.0 + model.mjsw * (4e-4 * (temp - TEMP_REF) - gmanew);
         cjsw_t *= capfact;
 
         // S/D Junction Params
@@ -421,14 +501,14 @@ impl Mos1InternalParams {
         // Terminal Ohmic Resistances
         let grs = if let Some(r) = model.rs {
             if r <= 0.0 {
-                println!("Warning: Mos1 Model with rs <= 0");
+                log::warn!("Mos1 Model with rs <= 0");
                 0.0
             } else {
                 1.0 / r
             }
         } else if let Some(rsh) = model.rsh {
             if rsh <= 0.0 {
-                println!("Warning: Mos1 Model with rsh <= 0");
+                log::warn!("Mos1 Model with rsh <= 0");
                 0.0
             } else {
                 1.0 / rsh / inst.nrs
@@ -438,14 +518,14 @@ impl Mos1InternalParams {
         };
         let grd = if let Some(r) = model.rd {
             if r <= 0.0 {
-                println!("Warning: Mos1 Model with rd <= 0");
+                log::warn!("Mos1 Model with rd <= 0");
                 0.0
             } else {
                 1.0 / r
             }
         } else if let Some(rsh) = model.rsh {
             if rsh <= 0.0 {
-                println!("Warning: Mos1 Model with rsh <= 0");
+                log::warn!("Mos1 Model with rsh <= 0");
                 0.0
             } else {
                 1.0 / rsh / inst.nrd
@@ -531,6 +611,8 @@ pub(crate) struct Mos1OpPoint {
     vgb: f64,
     vdb: f64,
     vsb: f64,
+    /// Overdrive, `vgs - von`, including body effect
+    vov: f64,
     gm: f64,
     gds: f64,
     gmbs: f64,
@@ -592,18 +674,19 @@ impl<T> Index<Mos1Var> for Mos1Vars<T> {
 impl Mos1Vars<Option<VarIndex>> {
     pub(crate) fn from<P: Clone + Into<Option<VarIndex>>, T: SpNum>(
         path: String,
+        sep: &str,
         terms: &MosPorts<P>,
         model: &Mos1Model,
         vars: &mut Variables<T>,
     ) -> Self {
         let dp = if model.rd.is_some() || model.rsh.is_some() {
-            let name = format!("{}.{}", path, "dp");
+            let name = format!("{}{}{}", path, sep, "dp");
             Some(vars.add(name, analysis::VarKind::V))
         } else {
             terms.d.clone().into()
         };
         let sp = if model.rs.is_some() || model.rsh.is_some() {
-            let name = format!("{}.{}", path, "sp");
+            let name = format!("{}{}{}", path, sep, "sp");
             Some(vars.add(name, analysis::VarKind::V))
         } else {
             terms.s.clone().into()
@@ -656,15 +739,28 @@ impl Mos1 {
         // i.e. the polarities typically expressed for NMOS
         let p = model.mos_type.p();
         let reversed = p * (v.d - v.s) < 0.0;
-        // FIXME: add inter-step limiting
         let (vd, vs) = if reversed { (v.s, v.d) } else { (v.d, v.s) };
-        let vgs = p * (v.g - vs);
-        let vgd = p * (v.g - vd);
-        let vds = p * (vd - vs);
-        let vgb = p * (v.g - v.b);
+        let mut vgs = p * (v.g - vs);
+        let mut vds = p * (vd - vs);
         // Same for bulk junction diodes - polarities such that more `vsb`, `vdb` = more *reverse* bias.
-        let vsb = p * (vs - v.b);
-        let vdb = p * (vd - v.b);
+        let mut vsb = p * (vs - v.b);
+
+        // Inter-iteration limiting, relative to our prior guess, in this iteration's source/drain orientation
+        if opts.limiting {
+            let g = &self.guess;
+            let (vgs_old, vds_old, vsb_old) = if reversed == g.reversed {
+                (g.vgs, g.vds, g.vsb)
+            } else {
+                (g.vgd, -g.vds, g.vdb)
+            };
+            vgs = limiting::fetlim(vgs, vgs_old, intp.vt0_t);
+            vds = limiting::limvds(vds, vds_old);
+            let bs_junc = if !reversed { &intp.source_junc } else { &intp.drain_junc };
+            vsb = -limiting::pnjlim(-vsb, -vsb_old, intp.vtherm, bs_junc.vcrit);
+        }
+        let vgd = vgs - vds;
+        let vgb = vgs + vsb;
+        let vdb = vds + vsb;
 
         // Threshold & body effect calcs
         let von = if vsb > 0.0 {
@@ -713,12 +809,14 @@ impl Mos1 {
             (drain_junc, source_junc)
         };
         // Source-Bulk
-        let ibs = bs_junc.isat * ((-vsb / vtherm).exp() - 1.0);
-        let gbs = (bs_junc.isat / vtherm) * (-vsb / vtherm).exp() + gmin;
+        let (ebs, debs) = safe_exp(-vsb / vtherm);
+        let ibs = bs_junc.isat * (ebs - 1.0);
+        let gbs = (bs_junc.isat / vtherm) * debs + gmin;
         let ibs_rhs = ibs + vsb * gbs;
         // Drain-Bulk
-        let ibd = bd_junc.isat * ((-vdb / vtherm).exp() - 1.0);
-        let gbd = (bd_junc.isat / vtherm) * (-vdb / vtherm).exp() + gmin;
+        let (ebd, debd) = safe_exp(-vdb / vtherm);
+        let ibd = bd_junc.isat * (ebd - 1.0);
+        let gbd = (bd_junc.isat / vtherm) * debd + gmin;
         let ibd_rhs = ibd + vdb * gbd;
 
         // Capacitance Calculations
@@ -780,12 +878,8 @@ impl Mos1 {
                 } else {
                     (vgs - self.op.vgd) * cgs
                 };
-                let ip = if reversed == self.op.reversed {
-                    self.op.tr.gs.i
-                } else {
-                    self.op.tr.gd.i
-                };
-                tr.gs = state.integq(dqgs, cgs, vgs, ip);
+                let prev = if reversed == self.op.reversed { &self.op.tr.gs } else { &self.op.tr.gd };
+                tr.gs = state.integq(dqgs, cgs, vgs, prev);
             }
             {
                 let dqgd = if reversed == self.op.reversed {
@@ -793,17 +887,13 @@ impl Mos1 {
                 } else {
                     (vgd - self.op.vgs) * cgd
                 };
-                let ip = if reversed == self.op.reversed {
-                    self.op.tr.gd.i
-                } else {
-                    self.op.tr.gs.i
-                };
-                tr.gs = state.integq(dqgd, cgd, vgd, ip);
+                let prev = if reversed == self.op.reversed { &self.op.tr.gd } else { &self.op.tr.gs };
+                tr.gd = state.integq(dqgd, cgd, vgd, prev);
             }
             {
                 // Gate-Bulk Cap
                 let dqgb = (vgb - self.op.vgb) * cgb;
-                tr.gb = state.integq(dqgb, cgb, vgb, self.op.tr.gb.i);
+                tr.gb = state.integq(dqgb, cgb, vgb, &self.op.tr.gb);
             }
             {
                 // Bulk Junction Caps
@@ -817,16 +907,16 @@ impl Mos1 {
                 } else {
                     (-vdb + self.op.vsb) * cbd
                 };
-                let (isp, idp) = if reversed == self.op.reversed {
-                    (self.op.tr.gs.i, self.op.tr.gd.i)
+                let (sprev, dprev) = if reversed == self.op.reversed {
+                    (&self.op.tr.bs, &self.op.tr.bd)
                 } else {
-                    (self.op.tr.gd.i, self.op.tr.gs.i)
+                    (&self.op.tr.bd, &self.op.tr.bs)
                 };
-                tr.bs = state.integq(dqbs, cbs, -vsb, isp);
-                tr.bd = state.integq(dqbd, cbd, -vdb, idp);
+                tr.bs = state.integq(dqbs, cbs, -vsb, sprev);
+                tr.bd = state.integq(dqbd, cbd, -vdb, dprev);
             }
         }
-        let irhs = ids - gm * vgs - gds * vds;
+        let irhs = ids - gm * vgs - gds * vds + gmbs * vsb;
 
         // Sort out which are the "reported" drain and source terminals (sr, dr)
         // FIXME: this also needs the "prime" vs "external" source & drains
@@ -876,6 +966,7 @@ impl Mos1 {
             vgb,
             vdb,
             vsb,
+            vov,
             gm,
             gds,
             gmbs,
@@ -893,6 +984,9 @@ impl Mos1 {
     }
 }
 impl Component for Mos1 {
+    fn linear(&self) -> bool {
+        false
+    }
     fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
         use Mos1Var::{B, D, DP, G, S, SP};
         for t1 in [G, D, S, B, DP, SP].iter() {
@@ -911,6 +1005,67 @@ impl Component for Mos1 {
         self.guess = op; // Save the calculated operating point
         stamps // And return our matrix stamps
     }
+    fn op_point(&self) -> Vec<(&'static str, f64)> {
+        let op = &self.op;
+        vec![
+            ("ids", op.ids),
+            ("vgs", op.vgs),
+            ("vds", op.vds),
+            ("vgd", op.vgd),
+            ("vgb", op.vgb),
+            ("vdb", op.vdb),
+            ("vsb", op.vsb),
+            ("vov", op.vov),
+            ("gm", op.gm),
+            ("gds", op.gds),
+            ("gmbs", op.gmbs),
+            ("gbs", op.gbs),
+            ("gbd", op.gbd),
+            ("cgs", op.cgs),
+            ("cgd", op.cgd),
+            ("cgb", op.cgb),
+            ("cbs", op.cbs),
+            ("cbd", op.cbd),
+            ("reversed", if op.reversed { 1.0 } else { 0.0 }),
+        ]
+    }
+    fn power(&self, _x: &Variables<f64>) -> Option<f64> {
+        // Channel power, from our last committed operating point
+        Some((self.op.ids * self.op.vds).abs())
+    }
+    fn load_noise(&self, f: f64, _opts: &Options) -> Vec<NoiseSource> {
+        let model = &*self.model.read();
+        let intp = &*self.intparams.read();
+        use consts::KB;
+        use Mos1Var::{D, DP, S, SP};
+
+        // Channel thermal noise, 8kT*gm/3, plus flicker noise kf*ids^af / (f*cox*leff^2)
+        let mut psd = 8.0 / 3.0 * KB * intp.temp * self.op.gm.abs();
+        if model.kf != 0.0 && model.cox_per_area > 0.0 {
+            psd += model.kf * self.op.ids.abs().powf(model.af) / (f * model.cox_per_area * intp.leff.powi(2));
+        }
+        let mut srcs = vec![NoiseSource {
+            p: self.ports[DP],
+            n: self.ports[SP],
+            psd,
+        }];
+        // Thermal noise of our terminal resistances
+        if intp.grd > 0.0 {
+            srcs.push(NoiseSource {
+                p: self.ports[D],
+                n: self.ports[DP],
+                psd: 4.0 * KB * intp.temp * intp.grd,
+            });
+        }
+        if intp.grs > 0.0 {
+            srcs.push(NoiseSource {
+                p: self.ports[S],
+                n: self.ports[SP],
+                psd: 4.0 * KB * intp.temp * intp.grs,
+            });
+        }
+        srcs
+    }
     fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
         let intp = &*self.intparams.read();
 
@@ -922,10 +1077,11 @@ impl Component for Mos1 {
         // Short-hand the conductances from our op-point.
         // (Rustc should be smart enough not to copy these.)
         let Mos1OpPoint { gm, gds, gmbs, gbs, gbd, .. } = self.op;
-        // Cap admittances
-        let gcgs = omega * self.op.cgs;
-        let gcgd = omega * self.op.cgd;
-        let gcgb = omega * self.op.cgb;
+        // Cap admittances. Meyer gate caps are stored as half-values, as in `op_stamp`,
+        // and are summed with their overlap components.
+        let gcgs = omega * (2.0 * self.op.cgs + intp.cgs_ov);
+        let gcgd = omega * (2.0 * self.op.cgd + intp.cgd_ov);
+        let gcgb = omega * (2.0 * self.op.cgb + intp.cgb_ov);
         let gcbs = omega * self.op.cbs;
         let gcbd = omega * self.op.cbd;
 
@@ -939,23 +1095,22 @@ impl Component for Mos1 {
         // And finally, send back our AC-matrix contributions
         return Stamps {
             g: vec![
-                (self.matps[(dr, dr)], Complex::new(gds + intp.grd + gbd, gcgd)),
-                (self.matps[(sr, sr)], Complex::new(gm + gds + intp.grs + gbs + gmbs, gcgs)),
+                (self.matps[(dr, dr)], Complex::new(gds + intp.grd + gbd, gcgd + gcbd)),
+                (self.matps[(sr, sr)], Complex::new(gm + gds + intp.grs + gbs + gmbs, gcgs + gcbs)),
                 (self.matps[(dr, sr)], Complex::new(-gm - gds - gmbs, 0.0)),
                 (self.matps[(sr, dr)], Complex::new(-gds, 0.0)),
                 (self.matps[(dr, G)], Complex::new(gm, -gcgd)),
                 (self.matps[(sr, G)], Complex::new(-gm, -gcgs)),
                 (self.matps[(G, G)], Complex::new(0.0, gcgd + gcgs + gcgb)),
-                (self.matps[(B, B)], Complex::new(gbd + gbs, gcgb)),
+                (self.matps[(B, B)], Complex::new(gbd + gbs, gcgb + gcbd + gcbs)),
                 (self.matps[(G, B)], Complex::new(0.0, -gcgb)),
                 (self.matps[(G, dr)], Complex::new(0.0, -gcgd)),
                 (self.matps[(G, sr)], Complex::new(0.0, -gcgs)),
                 (self.matps[(B, G)], Complex::new(0.0, -gcgb)),
-                (self.matps[(G, dr)], Complex::new(0.0, -gcgd)),
-                (self.matps[(B, dr)], Complex::new(-gbd, 0.0)),
-                (self.matps[(B, sr)], Complex::new(-gbs, 0.0)),
-                (self.matps[(dr, B)], Complex::new(-gbd + gmbs, 0.0)),
-                (self.matps[(sr, B)], Complex::new(-gbs - gmbs, 0.0)),
+                (self.matps[(B, dr)], Complex::new(-gbd, -gcbd)),
+                (self.matps[(B, sr)], Complex::new(-gbs, -gcbs)),
+                (self.matps[(dr, B)], Complex::new(-gbd + gmbs, -gcbd)),
+                (self.matps[(sr, B)], Complex::new(-gbs - gmbs, -gcbs)),
                 (self.matps[(dx, dr)], Complex::new(-intp.grd, 0.0)),
                 (self.matps[(dr, dx)], Complex::new(-intp.grd, 0.0)),
                 (self.matps[(dx, dx)], Complex::new(intp.grd, 0.0)),
@@ -975,6 +1130,32 @@ use crate::defs::{CacheEntry, ModelInstanceCache};
 ///
 pub(crate) type Mos1Defs = ModelInstanceCache<Mos1Model, Mos1InstanceParams, Mos1CacheEntry>;
 
+impl Mos1Defs {
+    /// Set parameter `param` of model `name` to `val`,
+    /// re-deriving internal parameters for each instance using it.
+    pub(crate) fn alter_model(&mut self, name: &str, param: &str, val: f64, opts: &Options) -> SpResult<()> {
+        let model = match self.models.get(name) {
+            Some(m) => m,
+            None => return Err(sperror(format!("Mos1 Model Not Found: {}", name))),
+        };
+        model.write().set(param, val)?;
+        for ((_inst, modelname), entry) in self.cache.iter() {
+            if modelname == name {
+                let intp = Mos1InternalParams::derive(&entry.model.read(), &entry.inst.read(), opts);
+                *entry.intp.write() = intp;
+            }
+        }
+        Ok(())
+    }
+    /// Re-derive internal parameters of every instance, e.g. after a change in circuit temperature
+    pub(crate) fn rederive(&self, opts: &Options) {
+        for entry in self.cache.values() {
+            let intp = Mos1InternalParams::derive(&entry.model.read(), &entry.inst.read(), opts);
+            *entry.intp.write() = intp;
+        }
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct Mos1CacheEntry {
     pub(crate) model: DefPtr<Mos1Model>,
@@ -1022,11 +1203,21 @@ impl Default for Mos0Params {
     }
 }
 
+/// Mos Level-Zero Operating Point
+#[derive(Default, Clone)]
+pub(crate) struct Mos0OpPoint {
+    gm: f64,
+    gds: f64,
+    reversed: bool,
+}
+
 /// Mos "Level Zero" Simplified Solver
 pub struct Mos0 {
     params: Mos0Params,
     ports: MosPorts<Option<VarIndex>>,
     matps: Mos0MatrixPointers,
+    guess: Mos0OpPoint,
+    op: Mos0OpPoint,
 }
 impl Mos0 {
     pub(crate) fn new(ports: MosPorts<Option<VarIndex>>, mos_type: MosType) -> Self {
@@ -1037,10 +1228,15 @@ impl Mos0 {
             },
             ports,
             matps: Mos0MatrixPointers([[None; 4]; 4]),
+            guess: Mos0OpPoint::default(),
+            op: Mos0OpPoint::default(),
         }
     }
 }
 impl Component for Mos0 {
+    fn linear(&self) -> bool {
+        false
+    }
     fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
         use MosTerm::{D, G, S};
         let matps = [(D, D), (S, S), (D, S), (S, D), (D, G), (S, G)];
@@ -1082,6 +1278,7 @@ impl Component for Mos0 {
                 gds = beta * ((vov - vds) * (1.0 + lam * vds) + lam * ((vov * vds) - vds.powi(2) / 2.0));
             }
         }
+        self.guess = Mos0OpPoint { gm, gds, reversed };
         // Sort out which are the "reported" drain and source terminals (sr, dr)
         let (sr, dr) = if !reversed { (S, D) } else { (D, S) };
         let irhs = ids - gm * vgs - gds * vds;
@@ -1097,6 +1294,28 @@ impl Component for Mos0 {
             b: vec![(self.ports[dr], -p * irhs), (self.ports[sr], p * irhs)],
         };
     }
+    fn commit(&mut self) {
+        self.op = self.guess.clone();
+    }
+    /// AC load, with the purely-real conductances of our committed operating point.
+    /// Mos0 has no capacitances.
+    fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, _an: &AnalysisInfo, opts: &Options) -> Stamps<Complex<f64>> {
+        use MosTerm::{D, G, S};
+        let Mos0OpPoint { gm, gds, reversed } = self.op;
+        let gmin = opts.gmin;
+        let (sr, dr) = if !reversed { (S, D) } else { (D, S) };
+        Stamps {
+            g: vec![
+                (self.matps[(dr, dr)], Complex::new(gds + gmin, 0.0)),
+                (self.matps[(sr, sr)], Complex::new(gm + gds + gmin, 0.0)),
+                (self.matps[(dr, sr)], Complex::new(-(gm + gds + gmin), 0.0)),
+                (self.matps[(sr, dr)], Complex::new(-gds - gmin, 0.0)),
+                (self.matps[(dr, G)], Complex::new(gm, 0.0)),
+                (self.matps[(sr, G)], Complex::new(-gm, 0.0)),
+            ],
+            b: vec![],
+        }
+    }
 }
 #[derive(Default)]
 struct Mos0MatrixPointers([[Option<Eindex>; 4]; 4]);
@@ -1111,3 +1330,392 @@ impl IndexMut<(MosTerm, MosTerm)> for Mos0MatrixPointers {
         &mut self.0[ts.0 as usize][ts.1 as usize]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert::assert;
+    use crate::spresult::TestResult;
+
+    /// Check bulk junctions produce finite stamps, even when wildly (forward or reverse) biased
+    #[test]
+    fn test_mos1_bulk_bias_finite() -> TestResult {
+        let opts = Options::default();
+        for mos_type in [MosType::NMOS, MosType::PMOS].iter() {
+            let model = Mos1Model {
+                mos_type: *mos_type,
+                ..Default::default()
+            };
+            let intp = Mos1InternalParams::derive(&model, &Mos1InstanceParams::default(), &opts);
+            let mos = Mos1 {
+                model: DefPtr::new(model),
+                intparams: DefPtr::new(intp),
+                ..Default::default()
+            };
+            for vb in [-100.0, 100.0].iter() {
+                let v = Mos1Vars {
+                    d: 1.0,
+                    dp: 1.0,
+                    g: 1.0,
+                    s: 0.0,
+                    sp: 0.0,
+                    b: *vb,
+                };
+                let (_op, stamps) = mos.op_stamp(v, &AnalysisInfo::OP, &opts);
+                for (_, val) in stamps.g.iter() {
+                    assert(val.is_finite()).eq(true)?;
+                }
+                for (_, val) in stamps.b.iter() {
+                    assert(val.is_finite()).eq(true)?;
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Check the saturation-region transconductance stamps, against their analytic values
+    #[test]
+    fn test_mos1_sat_gm_stamp() -> TestResult {
+        use crate::comps::harness::StampHarness;
+        let opts = Options::default();
+        let model = Mos1Model::resolve(&proto::Mos1Model {
+            vt0: Some(0.5),
+            kp: Some(1e-4),
+            lambda: Some(0.1),
+            ..Default::default()
+        });
+        let inst = Mos1InstanceParams::default();
+        let intp = Mos1InternalParams::derive(&model, &inst, &opts);
+        let beta = model.kp * inst.w / inst.l;
+
+        let mut h = StampHarness::new(&["d", "g", "s", "b"]);
+        let mut mos = Mos1 {
+            model: DefPtr::new(model),
+            intparams: DefPtr::new(intp),
+            ports: Mos1Vars {
+                d: h.var("d"),
+                g: h.var("g"),
+                s: h.var("s"),
+                b: h.var("b"),
+                dp: h.var("d"),
+                sp: h.var("s"),
+            },
+            ..Default::default()
+        };
+        // Bias in saturation: vov = 0.3 < vds = 1.0
+        h.set("d", 1.0);
+        h.set("g", 0.8);
+        let stamps = h.load(&mut mos, &AnalysisInfo::OP, &opts);
+        let (g, _b) = h.dense(&stamps);
+
+        let (vov, vds) = (0.3, 1.0);
+        let gm = beta * vov * (1.0 + 0.1 * vds);
+        let gds = 0.1 * beta / 2.0 * vov.powi(2);
+        let (d, gi, s) = (0, 1, 2);
+        assert(g[d][gi]).isclose(gm, 1e-15)?;
+        assert(g[s][gi]).isclose(-gm, 1e-15)?;
+        // Drain-side diagonal also includes the (reverse-biased) bulk junction, i.e. `gmin`
+        assert(g[d][d]).isclose(gds + opts.gmin, 1e-15)?;
+        assert(g[d][s]).isclose(-gm - gds, 1e-15)?;
+        Ok(())
+    }
+    /// Check the DC companion current, `G*x - b`, reproduces the drain current of a body-biased device.
+    /// Requires the `gmbs * vsb` term in the companion current source.
+    #[test]
+    fn test_mos1_body_bias_dc_current() -> TestResult {
+        use crate::comps::harness::StampHarness;
+        // Disable limiting, so stamps linearize about the harness voltages themselves
+        let opts = Options {
+            limiting: false,
+            ..Default::default()
+        };
+        let model = Mos1Model::resolve(&proto::Mos1Model {
+            vt0: Some(0.5),
+            kp: Some(1e-4),
+            lambda: Some(0.1),
+            gamma: Some(0.4),
+            ..Default::default()
+        });
+        let inst = Mos1InstanceParams::default();
+        let intp = Mos1InternalParams::derive(&model, &inst, &opts);
+        // Saturated, with the bulk 0.5V below the source
+        let (vd, vg, vb) = (1.0, 1.5, -0.5);
+        let von = intp.vt0_t + model.gamma * ((intp.phi_t - vb).sqrt() - intp.phi_t.sqrt());
+        let ids = intp.beta / 2.0 * (vg - von).powi(2) * (1.0 + model.lambda * vd);
+
+        let mut h = StampHarness::new(&["d", "g", "s", "b"]);
+        let mut mos = Mos1 {
+            model: DefPtr::new(model),
+            intparams: DefPtr::new(intp),
+            ports: Mos1Vars {
+                d: h.var("d"),
+                g: h.var("g"),
+                s: h.var("s"),
+                b: h.var("b"),
+                dp: h.var("d"),
+                sp: h.var("s"),
+            },
+            ..Default::default()
+        };
+        h.set("d", vd);
+        h.set("g", vg);
+        h.set("b", vb);
+        h.load(&mut mos, &AnalysisInfo::OP, &opts);
+        let i = h.currents(&mut mos, &opts);
+        let (d, s) = (0, 2);
+        assert(i[d]).isclose(ids, 1e-12)?;
+        assert(i[s]).isclose(-ids, 1e-12)?;
+        Ok(())
+    }
+    /// Check the real part of the AC stamps against finite-differences of the DC currents,
+    /// in both forward and reversed operation, including terminal resistances
+    #[test]
+    fn test_mos1_ac_stamps_fd() -> TestResult {
+        use crate::comps::harness::StampHarness;
+        // Disable Newton limiting, so that stamps linearize around the harness's (un-iterated) voltages
+        let opts = Options {
+            limiting: false,
+            ..Default::default()
+        };
+        let model = Mos1Model::resolve(&proto::Mos1Model {
+            vt0: Some(0.5),
+            kp: Some(1e-4),
+            lambda: Some(0.1),
+            gamma: Some(0.4),
+            rd: Some(10.0),
+            rs: Some(20.0),
+            ..Default::default()
+        });
+        let inst = Mos1InstanceParams::default();
+
+        for (vd, vs) in [(1.0, 0.1), (0.1, 1.0)].iter() {
+            let intp = Mos1InternalParams::derive(&model, &inst, &opts);
+            let mut h = StampHarness::new(&["d", "g", "s", "b", "dp", "sp"]);
+            let mut mos = Mos1 {
+                model: DefPtr::new(model.clone()),
+                intparams: DefPtr::new(intp),
+                ports: Mos1Vars {
+                    d: h.var("d"),
+                    g: h.var("g"),
+                    s: h.var("s"),
+                    b: h.var("b"),
+                    dp: h.var("dp"),
+                    sp: h.var("sp"),
+                },
+                ..Default::default()
+            };
+            h.set("d", *vd);
+            h.set("dp", *vd);
+            h.set("s", *vs);
+            h.set("sp", *vs);
+            h.set("g", 1.5);
+            h.set("b", -0.2);
+            let mismatches = h.check_ac(&mut mos, &opts, 1e-6, 1e-4, 1e-9);
+            if !mismatches.is_empty() {
+                let each: Vec<String> = mismatches.iter().map(|m| format!("({}, {}): ac={} fd={}", m.row, m.col, m.ac, m.fd)).collect();
+                return Err(sperror(format!("AC Stamp Mismatches at vd={} vs={}: {}", vd, vs, each.join(", "))));
+            }
+        }
+        Ok(())
+    }
+    /// Check transient gate-capacitance companion models land on their own terminals,
+    /// in both forward and reversed operation
+    #[test]
+    fn test_mos1_tran_gate_caps() -> TestResult {
+        use crate::analysis::{IntegMethod, TranOptions, TranState};
+        use crate::comps::harness::StampHarness;
+        let opts = Options::default();
+        let model = Mos1Model::resolve(&proto::Mos1Model {
+            vt0: Some(0.5),
+            kp: Some(1e-4),
+            tox: Some(1e-8),
+            ..Default::default()
+        });
+        let inst = Mos1InstanceParams::default();
+        let topts = TranOptions::default();
+        let state = TranState {
+            t: 1e-9,
+            dt: 1e-9,
+            dt_prev: 0.0,
+            vic: vec![],
+            ric: vec![],
+            ni: IntegMethod::Gear1,
+        };
+        // Biased in triode, where both gate-source and gate-drain capacitances are non-zero
+        for (vd, vs) in [(0.2, 0.0), (0.0, 0.2)].iter() {
+            let intp = Mos1InternalParams::derive(&model, &inst, &opts);
+            let (cgs_ov, cgd_ov) = (intp.cgs_ov, intp.cgd_ov);
+            let mut h = StampHarness::new(&["d", "g", "s", "b", "dp", "sp"]);
+            let mut mos = Mos1 {
+                model: DefPtr::new(model.clone()),
+                intparams: DefPtr::new(intp),
+                ports: Mos1Vars {
+                    d: h.var("d"),
+                    g: h.var("g"),
+                    s: h.var("s"),
+                    b: h.var("b"),
+                    dp: h.var("dp"),
+                    sp: h.var("sp"),
+                },
+                ..Default::default()
+            };
+            h.set("d", *vd);
+            h.set("dp", *vd);
+            h.set("s", *vs);
+            h.set("sp", *vs);
+            h.set("g", 1.5);
+            h.load(&mut mos, &AnalysisInfo::OP, &opts);
+            mos.commit();
+            h.load(&mut mos, &AnalysisInfo::TRAN(&topts, &state), &opts);
+            let (op, guess) = (&mos.op, &mos.guess);
+            assert(guess.reversed).eq(*vd < *vs)?;
+            assert(guess.cgs).gt(0.0)?;
+            assert(guess.cgd).gt(0.0)?;
+            let cgs = guess.cgs + op.cgs + cgs_ov;
+            let cgd = guess.cgd + op.cgd + cgd_ov;
+            assert(guess.tr.gs.g).isclose(cgs / state.dt, 1e-9 * cgs / state.dt)?;
+            assert(guess.tr.gd.g).isclose(cgd / state.dt, 1e-9 * cgd / state.dt)?;
+        }
+        Ok(())
+    }
+    /// Mos1 model with junction capacitances, at nominal temperature `tnom` (Celsius)
+    fn tnom_model(mos_type: i32, tnom: Option<f64>) -> Mos1Model {
+        Mos1Model::resolve(&proto::Mos1Model {
+            mos_type,
+            tnom,
+            vt0: Some(0.5),
+            kp: Some(1e-4),
+            gamma: Some(0.4),
+            phi: Some(0.7),
+            pb: Some(0.9),
+            cj: Some(1e-3),
+            cjsw: Some(1e-10),
+            ..Default::default()
+        })
+    }
+    /// Check derived parameters equal their nominal values when simulating at `tnom`
+    #[test]
+    fn test_mos1_tnom_no_drift() -> TestResult {
+        let inst = Mos1InstanceParams::default();
+        for mos_type in [0, 1].iter() {
+            for tnom in [None, Some(-40.0), Some(27.0), Some(125.0)].iter() {
+                let model = tnom_model(*mos_type, *tnom);
+                let opts = Options {
+                    temp: model.tnom,
+                    ..Default::default()
+                };
+                let intp = Mos1InternalParams::derive(&model, &inst, &opts);
+                assert(intp.vt0_t).isclose(model.vt0, 1e-12)?;
+                assert(intp.kp_t).isclose(model.kp, 1e-18)?;
+                assert(intp.phi_t).isclose(model.phi, 1e-12)?;
+                let junc = &intp.drain_junc;
+                assert(junc.bulkpot_t).isclose(model.pb, 1e-12)?;
+                assert(junc.czb).isclose(model.cj * inst.a_d, 1e-24)?;
+                assert(junc.czbsw).isclose(model.cjsw * inst.pd, 1e-24)?;
+            }
+        }
+        Ok(())
+    }
+    /// Logger capturing every record, for checking emitted diagnostics
+    struct CaptureLogger(std::sync::Mutex<Vec<(log::Level, String, String)>>);
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+        fn log(&self, record: &log::Record) {
+            let entry = (record.level(), record.target().to_string(), record.args().to_string());
+            self.0.lock().unwrap().push(entry);
+        }
+        fn flush(&self) {}
+    }
+    static LOGGER: CaptureLogger = CaptureLogger(std::sync::Mutex::new(vec![]));
+
+    /// Check a non-positive `rs` is reported as a `warn`-level log record
+    #[test]
+    fn test_mos1_bad_rs_warns() -> TestResult {
+        // Other tests may have installed the logger, or may log concurrently
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Trace);
+        let model = Mos1Model {
+            rs: Some(-1.0),
+            ..Default::default()
+        };
+        let intp = Mos1InternalParams::derive(&model, &Mos1InstanceParams::default(), &Options::default());
+        assert(intp.grs).eq(0.0)?;
+        let records = LOGGER.0.lock().unwrap();
+        let warned = records
+            .iter()
+            .any(|(level, target, msg)| *level == log::Level::Warn && target.starts_with("spice21::comps::mos") && msg.contains("rs <= 0"));
+        assert(warned).eq(true)?;
+        Ok(())
+    }
+    /// Check the direction of temperature-scaling, away from `tnom`
+    #[test]
+    fn test_mos1_tnom_scaling() -> TestResult {
+        let inst = Mos1InstanceParams::default();
+        let model = tnom_model(0, Some(50.0));
+        let derive = |temp: f64| {
+            let opts = Options {
+                temp: temp + consts::KELVIN_TO_C,
+                ..Default::default()
+            };
+            Mos1InternalParams::derive(&model, &inst, &opts)
+        };
+        let (cold, hot) = (derive(0.0), derive(100.0));
+        // Mobility, threshold, and built-in potentials all fall with temperature
+        assert(cold.kp_t).gt(model.kp)?;
+        assert(hot.kp_t).lt(model.kp)?;
+        assert(cold.vt0_t).gt(model.vt0)?;
+        assert(hot.vt0_t).lt(model.vt0)?;
+        assert(cold.drain_junc.bulkpot_t).gt(model.pb)?;
+        assert(hot.drain_junc.bulkpot_t).lt(model.pb)?;
+        // While junction capacitances rise
+        assert(cold.drain_junc.czb).lt(model.cj * inst.a_d)?;
+        assert(hot.drain_junc.czb).gt(model.cj * inst.a_d)?;
+        Ok(())
+    }
+    /// Check bulk-junction charge and capacitance are continuous (C1) across the depletion/forward-bias boundary
+    #[test]
+    fn test_mos1_junction_qc_continuity() -> TestResult {
+        let opts = Options::default();
+        let model = Mos1Model {
+            cj: 1e-4,
+            cjsw: 1e-10,
+            mj: 0.5,
+            mjsw: 0.33,
+            fc: 0.5,
+            ..Default::default()
+        };
+        let inst = Mos1InstanceParams {
+            a_d: 1e-12,
+            pd: 4e-6,
+            a_s: 0.0,
+            ps: 0.0,
+            ..Default::default()
+        };
+        let intp = Mos1InternalParams::derive(&model, &inst, &opts);
+        let junc = &intp.drain_junc;
+        assert(junc.czb).gt(0.0)?;
+        assert(junc.czbsw).gt(0.0)?;
+
+        let vdt = junc.depletion_threshold;
+        let dv = 1e-9;
+        let (ql, cl) = junc.qc(vdt - dv, &model);
+        let (qh, ch) = junc.qc(vdt + dv, &model);
+        // Charge and capacitance each match on either side
+        assert(qh - ql).abs().lt(2.0 * dv * cl.max(ch) * (1.0 + 1e-6))?;
+        assert((ch - cl) / cl).abs().lt(1e-6)?;
+        // And capacitance matches the slope of charge, in both regions
+        let h = 1e-4;
+        for v in [vdt - 10.0 * h, vdt + 10.0 * h].iter() {
+            let (q0, _) = junc.qc(v - h, &model);
+            let (q1, _) = junc.qc(v + h, &model);
+            let (_, c) = junc.qc(*v, &model);
+            assert((q1 - q0) / (2.0 * h) / c - 1.0).abs().lt(1e-6)?;
+        }
+        // Junctions without capacitance have neither charge nor capacitance
+        let (q, c) = intp.source_junc.qc(vdt + 0.1, &model);
+        assert(q).eq(0.0)?;
+        assert(c).eq(0.0)?;
+        Ok(())
+    }
+}
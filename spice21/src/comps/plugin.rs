@@ -0,0 +1,118 @@
+//!
+//! # Plugin Devices
+//!
+//! Extension point for device-types defined outside Spice21.
+//!
+//! Each plugin registers a `DeviceDef` under a `kind` string, via `Defs::register_device`.
+//! Circuits instantiate it with the `U` component-type, e.g. in YAML:
+//!
+//! ```yaml
+//! - {type: U, kind: leaker, name: l1, ports: {p: a, n: ""}, params: {g: 1e-3} }
+//! ```
+//!
+//! Elaboration calls the registered `factory` with the instance's `params`,
+//! and connects the resulting `Device`'s terminals in the order of `DeviceDef::ports`.
+//!
+use num::Complex;
+use std::collections::HashMap;
+
+use super::{make_matrix_elem, Component};
+use crate::analysis::{AnalysisInfo, Options, Stamps, VarIndex, Variables};
+use crate::sparse21::{Eindex, Matrix};
+use crate::{SpNum, SpResult};
+
+/// Plugin Device Stamps, indexed by terminal-number.
+/// Follows the sign conventions of the solver's `G * x = b`:
+/// conductances `g` are (row, column, value) entries, and
+/// right-hand-side entries `b` are (row, current into the terminal).
+#[derive(Debug, Default, Clone)]
+pub struct DeviceStamps {
+    pub g: Vec<(usize, usize, f64)>,
+    pub b: Vec<(usize, f64)>,
+}
+
+/// Plugin Device
+/// Loads linearized stamps at terminal-voltages `v`, once per Newton iteration.
+/// AC analysis uses the conductances of the most recent converged load.
+pub trait Device {
+    fn load(&mut self, v: &[f64]) -> DeviceStamps;
+    /// Boolean indication of whether stamps are independent of `v`.
+    /// Defaults to `false`. Linear devices override it, to be allowed in linear-only (`Options::linear`) analyses.
+    fn linear(&self) -> bool {
+        false
+    }
+}
+
+/// Plugin Device Factory, creating a `Device` from instance parameters
+pub type DeviceFactory = fn(&HashMap<String, f64>) -> SpResult<Box<dyn Device>>;
+
+/// Plugin Device Definition
+#[derive(Clone)]
+pub struct DeviceDef {
+    /// Terminal names, in the order of the `Device`'s terminal-numbers
+    pub ports: Vec<String>,
+    pub factory: DeviceFactory,
+}
+
+/// Solver for a `Device`, mapping its terminal-numbers to solver variables
+pub(crate) struct Plugin {
+    dev: Box<dyn Device>,
+    terms: Vec<Option<VarIndex>>,
+    matps: Vec<Vec<Option<Eindex>>>,
+    guess: Vec<(usize, usize, f64)>,
+    op: Vec<(usize, usize, f64)>,
+}
+
+impl Plugin {
+    pub(crate) fn new(dev: Box<dyn Device>, terms: Vec<Option<VarIndex>>) -> Self {
+        Plugin {
+            dev,
+            terms,
+            matps: vec![],
+            guess: vec![],
+            op: vec![],
+        }
+    }
+    fn g_stamp(&self, (r, c, v): (usize, usize, f64)) -> (Option<Eindex>, f64) {
+        (self.matps[r][c], v)
+    }
+}
+
+impl Component for Plugin {
+    fn commit(&mut self) {
+        self.op = self.guess.clone();
+    }
+    fn create_matrix_elems<T: SpNum>(&mut self, mat: &mut Matrix<T>) {
+        self.matps = self
+            .terms
+            .iter()
+            .map(|r| self.terms.iter().map(|c| make_matrix_elem(mat, *r, *c)).collect())
+            .collect();
+    }
+    fn load(&mut self, guess: &Variables<f64>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<f64> {
+        let v: Vec<f64> = self.terms.iter().map(|t| guess.get(*t)).collect();
+        let DeviceStamps { g, b } = self.dev.load(&v);
+        let stamps = Stamps {
+            g: g.iter().map(|e| self.g_stamp(*e)).collect(),
+            b: b.iter().map(|(t, v)| (self.terms[*t], *v)).collect(),
+        };
+        self.guess = g;
+        stamps
+    }
+    fn load_ac(&mut self, _guess: &Variables<Complex<f64>>, _an: &AnalysisInfo, _opts: &Options) -> Stamps<Complex<f64>> {
+        Stamps {
+            g: self
+                .op
+                .iter()
+                .map(|e| {
+                    let (ei, v) = self.g_stamp(*e);
+                    (ei, Complex::new(v, 0.0))
+                })
+                .collect(),
+            b: vec![],
+        }
+    }
+    fn linear(&self) -> bool {
+        self.dev.linear()
+    }
+}
@@ -2,9 +2,12 @@
 /// # Spice21 Circuit-Definitions Depots
 ///
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock, RwLockReadGuard};
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::analysis;
+use crate::proto::Corner;
+use crate::{sperror, SpResult};
 
 ///
 /// # Definition Pointer
@@ -27,15 +30,31 @@ impl<T> DefPtr<T> {
     pub fn read(&self) -> RwLockReadGuard<T> {
         self.0.read().unwrap()
     }
+    /// Write our definition, e.g. to alter parameter values.
+    /// Changes are visible to all sharers of the pointer.
+    /// Panics if write fails.
+    pub fn write(&self) -> RwLockWriteGuard<'_, T> {
+        self.0.write().unwrap()
+    }
     pub fn clone(i: &Self) -> Self {
         Self(Arc::clone(&i.0))
     }
+    /// Boolean indication of whether `a` and `b` point to the same definition
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        Arc::ptr_eq(&a.0, &b.0)
+    }
+}
+
+/// Copy each definition in `ptrs` into a new `DefPtr`
+fn copy_ptrs<T: Clone>(ptrs: &HashMap<String, DefPtr<T>>) -> HashMap<String, DefPtr<T>> {
+    ptrs.iter().map(|(k, v)| (k.clone(), DefPtr::new(v.read().clone()))).collect()
 }
 
 // The Module-Definition depot is defined here.
 // All others (models, instance parameters, etc.)
 // are imported from their comp-specific modules.
 
+use crate::circuit::module_def_key;
 use crate::proto::Module as ModuleDef;
 ///
 /// # Module Definitions Depot
@@ -112,10 +131,33 @@ where
         self.cache.insert((inst.to_string(), model.to_string()), e.clone());
         Some(e)
     }
+    /// Independent copy of our model and instance definitions, sharing no pointers with `self`.
+    /// Derived (cached) parameters are not copied.
+    pub(crate) fn copy(&self) -> Self
+    where
+        Model: Clone,
+        Instance: Clone,
+    {
+        Self {
+            models: copy_ptrs(&self.models),
+            insts: copy_ptrs(&self.insts),
+            cache: HashMap::new(),
+        }
+    }
+    /// Text representations of each model and instance definition, for content-hashing
+    pub(crate) fn content_keys(&self, prefix: &str) -> Vec<String>
+    where
+        Model: Debug,
+        Instance: Debug,
+    {
+        let models = self.models.iter().map(|(k, v)| format!("{}.model:{}:{:?}", prefix, k, &*v.read()));
+        let insts = self.insts.iter().map(|(k, v)| format!("{}.inst:{}:{:?}", prefix, k, &*v.read()));
+        models.chain(insts).collect()
+    }
 }
 
 // Collect up device-type-specific depots/ caches
-use crate::comps::{bsim4, diode, mos};
+use crate::comps::{bsim4, diode, mos, plugin};
 
 ///
 /// # Definitions Struct
@@ -132,4 +174,65 @@ pub struct Defs {
     pub(crate) mos1: mos::Mos1Defs,
     pub(crate) bsim4: bsim4::Bsim4Cache,
     pub(crate) diodes: diode::DiodeDefs,
+    pub(crate) devices: HashMap<String, plugin::DeviceDef>,
+    pub(crate) corners: HashMap<String, Corner>,
+    /// Instance-names generated by `Ckt::add`, mapped to those originally requested
+    pub(crate) aliases: HashMap<String, String>,
+}
+impl Defs {
+    /// Register plugin device-type `kind`, with terminals `ports` and constructor `factory`.
+    /// Instances of type `U` with matching `kind` elaborate into its `Device`s.
+    pub fn register_device(&mut self, kind: &str, ports: &[&str], factory: plugin::DeviceFactory) {
+        let ports = ports.iter().map(|p| p.to_string()).collect();
+        self.devices.insert(kind.to_string(), plugin::DeviceDef { ports, factory });
+    }
+    /// Independent copy of all definitions, sharing no pointers with `self`.
+    /// Derived (cached) parameters are not copied.
+    pub(crate) fn copy(&self) -> Self {
+        Self {
+            modules: ModuleDefs {
+                store: copy_ptrs(&self.modules.store),
+            },
+            mos0: self.mos0.clone(),
+            mos1: self.mos1.copy(),
+            bsim4: self.bsim4.copy(),
+            diodes: self.diodes.copy(),
+            devices: self.devices.clone(),
+            corners: self.corners.clone(),
+            aliases: self.aliases.clone(),
+        }
+    }
+    /// Define process corner `corner`, selectable by name via `Options.corner`
+    pub fn add_corner(&mut self, corner: Corner) {
+        self.corners.insert(corner.name.clone(), corner);
+    }
+    /// Apply the parameter shifts of corner `name` to our models.
+    /// Each shifted parameter becomes `val * mul + add`. Only Mos1 models are supported.
+    pub(crate) fn apply_corner(&mut self, name: &str, opts: &analysis::Options) -> SpResult<()> {
+        let corner = match self.corners.get(name) {
+            Some(c) => c.clone(),
+            None => return Err(sperror(format!("Corner Not Found: {}", name))),
+        };
+        for shift in corner.shifts.iter() {
+            let val = match self.mos1.models.get(&shift.model) {
+                Some(m) => m.read().get(&shift.param)?,
+                None => return Err(sperror(format!("Corner {} References Unknown Mos1 Model: {}", name, shift.model))),
+            };
+            let val = val * shift.mul.unwrap_or(1.0) + shift.add.unwrap_or(0.0);
+            self.mos1.alter_model(&shift.model, &shift.param, val, opts)?;
+        }
+        Ok(())
+    }
+    /// Text representations of each definition, in arbitrary order, for content-hashing
+    pub(crate) fn content_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.modules.store.iter().map(|(k, v)| format!("module:{}:{}", k, module_def_key(&v.read()))).collect();
+        keys.extend(self.mos0.iter().map(|(k, v)| format!("mos0:{}:{:?}", k, v)));
+        keys.extend(self.mos1.content_keys("mos1"));
+        keys.extend(self.diodes.content_keys("diode"));
+        keys.extend(self.bsim4.models.iter().map(|(k, v)| format!("bsim4.model:{}:{:?}", k, v)));
+        keys.extend(self.bsim4.insts.iter().map(|(k, v)| format!("bsim4.inst:{}:{:?}", k, v)));
+        keys.extend(self.devices.iter().map(|(k, v)| format!("device:{}:{:?}", k, v.ports)));
+        keys.extend(self.corners.iter().map(|(k, v)| format!("corner:{}:{:?}", k, v)));
+        keys
+    }
 }
@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 
-use crate::analysis::{Options, VarIndex, Variables};
+use crate::analysis::{Options, VarIndex, VarLayout, Variables};
 use crate::circuit::{Comp, NodeRef};
-use crate::comps::ComponentSolver;
+use crate::comps::{Component, ComponentSolver, Multiplied};
+use crate::defs::DefPtr;
+use crate::sparse21::Matrix;
+use crate::spresult::{sperror, SpResult};
 use crate::SpNum;
 use crate::{circuit, defs};
 
@@ -21,10 +24,29 @@ use crate::{circuit, defs};
 ///
 pub(crate) struct Elaborator<'a, NumT: SpNum> {
     pub(crate) comps: Vec<ComponentSolver<'a>>,
+    /// Hierarchical instance-names, one per entry in `comps`
+    pub(crate) names: Vec<String>,
     pub(crate) vars: Variables<NumT>,
     pub(crate) defs: defs::Defs,
     pub(crate) path: Vec<String>,
     pub(crate) opts: Options,
+    /// Multiplier of the present hierarchy, i.e. the product of each enclosing module-instance's `m` parameter
+    pub(crate) mult: f64,
+    /// Toleranced components, for Monte Carlo analysis
+    pub(crate) variations: Vec<Variation>,
+    /// Current-variable and inductance of each inductor, by hierarchical name. Referenced by mutual inductances.
+    pub(crate) inductors: HashMap<String, (VarIndex, f64)>,
+}
+/// Statistical variation of an elaborated component's value
+#[derive(Debug, Clone)]
+pub(crate) struct Variation {
+    /// Index into `Elaborator.comps`
+    pub(crate) comp: usize,
+    /// Nominal value
+    pub(crate) nom: f64,
+    /// Tolerance, as a fraction of `nom`
+    pub(crate) tol: f64,
+    pub(crate) dist: circuit::Dist,
 }
 impl<'a, NumT: SpNum> Elaborator<'a, NumT> {
     /// Get or create a Variable for Node `node`.
@@ -33,12 +55,13 @@ impl<'a, NumT: SpNum> Elaborator<'a, NumT> {
     /// For `autonode=1`, new nodes are created for any identifier not previously encountered (ala SPICE netlists)
     /// This is (hopefully) a temporary measure.
     fn node_var(&mut self, node: NodeRef, autonode: bool, ns: &mut HashMap<String, Option<VarIndex>>) -> Option<VarIndex> {
+        // Ground is global, and has no variable
+        if let NodeRef::Gnd = node {
+            return None;
+        }
         if autonode {
-            if let NodeRef::Gnd = node {
-                return None;
-            }
             self.path.push(node.to_string());
-            let pathname = self.path.join(".");
+            let pathname = self.pathstr();
             let var = self.vars.find_or_create(NodeRef::Name(pathname)).clone();
             ns.insert(node.to_string(), var.clone());
             self.path.pop();
@@ -52,35 +75,99 @@ impl<'a, NumT: SpNum> Elaborator<'a, NumT> {
     }
     /// Elaborate a Module or Component Instance
     /// Dispatches based on circuit::Comp variants.
-    pub(crate) fn elaborate_instance(&mut self, inst: Comp, ns: &mut HashMap<String, Option<VarIndex>>, autonode: bool) {
+    pub(crate) fn elaborate_instance(&mut self, inst: Comp, ns: &mut HashMap<String, Option<VarIndex>>, autonode: bool) -> SpResult<()> {
         // FIXME: port/signal-name paths
         match inst {
             Comp::R(r) => {
-                let circuit::Ri { g, p, n, .. } = r;
+                let circuit::Ri { name, g, p, n, tol, dist } = r;
                 use crate::comps::Resistor;
                 let pvar = self.node_var(p, autonode, ns);
                 let nvar = self.node_var(n, autonode, ns);
-                self.comps.push(Resistor::new(g, pvar.clone(), nvar.clone()).into());
+                let name = self.instname(name);
+                if tol != 0.0 {
+                    let comp = self.comps.len();
+                    self.variations.push(Variation { comp, nom: g, tol, dist });
+                }
+                self.push_comp(Resistor::new(g, pvar.clone(), nvar.clone()).into(), name, 1.0);
             }
             Comp::C(c) => {
-                let circuit::Ci { c, p, n, .. } = c;
+                let circuit::Ci { name, c, esr, p, n } = c;
                 use crate::comps::Capacitor;
                 let pvar = self.node_var(p, autonode, ns);
                 let nvar = self.node_var(n, autonode, ns);
-                self.comps.push(Capacitor::new(c, pvar.clone(), nvar.clone()).into());
+                let name = self.instname(name);
+                let cap = if esr > 0.0 {
+                    // Series resistance adds an internal node, between it and the capacitance
+                    let ivar = self.vars.addv(format!("{}{}{}", name, self.opts.hier_separator, "esr"));
+                    Capacitor::new(c, Some(ivar), nvar).with_esr(esr, pvar)
+                } else {
+                    Capacitor::new(c, pvar, nvar)
+                };
+                self.push_comp(cap.into(), name, 1.0);
             }
             Comp::I(i) => {
-                let circuit::Ii { dc, p, n, .. } = i;
+                let circuit::Ii { name, dc, acm, p, n } = i;
                 use crate::comps::Isrc;
                 let pvar = self.node_var(p, autonode, ns);
                 let nvar = self.node_var(n, autonode, ns);
-                self.comps.push(Isrc::new(dc, pvar.clone(), nvar.clone()).into());
+                let name = self.instname(name);
+                self.push_comp(Isrc::new(dc, pvar.clone(), nvar.clone()).with_acm(acm).into(), name, 1.0);
+            }
+            Comp::G(g) => {
+                let circuit::Gi { name, poly, p, n, ctrl } = g;
+                use crate::comps::Vccs;
+                let pvar = self.node_var(p, autonode, ns);
+                let nvar = self.node_var(n, autonode, ns);
+                let cvars = ctrl
+                    .into_iter()
+                    .map(|(cp, cn)| (self.node_var(cp, autonode, ns), self.node_var(cn, autonode, ns)))
+                    .collect();
+                let name = self.instname(name);
+                self.push_comp(Vccs::new(poly, pvar, nvar, cvars).into(), name, 1.0);
+            }
+            Comp::E(e) => {
+                let circuit::Ei { name, gain, p, n, cp, cn } = e;
+                use crate::comps::Vcvs;
+                let pvar = self.node_var(p, autonode, ns);
+                let nvar = self.node_var(n, autonode, ns);
+                let cpvar = self.node_var(cp, autonode, ns);
+                let cnvar = self.node_var(cn, autonode, ns);
+                // Create the current variable, named `self.path`, as for voltage sources
+                self.path.push(name);
+                let ivar = self.vars.addi(self.pathstr());
+                self.push_comp(Vcvs::new(gain, pvar, nvar, cpvar, cnvar, ivar).into(), self.pathstr(), 1.0);
+                self.path.pop();
+            }
+            Comp::S(s) => {
+                let circuit::Si { name, p, n, cp, cn, ron, roff, von, voff } = s;
+                use crate::comps::Vswitch;
+                let pvar = self.node_var(p, autonode, ns);
+                let nvar = self.node_var(n, autonode, ns);
+                let cpvar = self.node_var(cp, autonode, ns);
+                let cnvar = self.node_var(cn, autonode, ns);
+                let name = self.instname(name);
+                self.push_comp(Vswitch::new(ron, roff, von, voff, pvar, nvar, cpvar, cnvar).into(), name, 1.0);
+            }
+            Comp::B(b) => {
+                let circuit::Bi { name, p, n, expr } = b;
+                use crate::comps::behavioral::{parse, Bsrc};
+                let expr = parse(&expr)?;
+                let pvar = self.node_var(p, autonode, ns);
+                let nvar = self.node_var(n, autonode, ns);
+                let vars = expr.nodes.iter().map(|k| self.node_var(circuit::n(k.clone()), autonode, ns)).collect();
+                let name = self.instname(name);
+                self.push_comp(Bsrc::new(expr, pvar, nvar, vars).into(), name, 1.0);
             }
+            Comp::L(x) => self.elaborate_inductor(x, ns),
+            Comp::K(x) => self.elaborate_mutual(x)?,
             Comp::V(x) => self.elaborate_vsrc(x, ns),
             Comp::D(x) => self.elaborate_diode(x, ns),
+            Comp::U(x) => self.elaborate_device(x, autonode, ns)?,
             Comp::Mos(x) => self.elaborate_mos(x, ns),
-            Comp::Module(x) => self.elaborate_module_inst(x, ns),
+            Comp::Module(x) => self.elaborate_module_inst(x, ns)?,
+            Comp::ModuleArray(x) => self.elaborate_module_array(x, ns)?,
         }
+        Ok(())
     }
     pub(crate) fn elaborate_diode(&mut self, d: circuit::DiodeI, ns: &mut HashMap<String, Option<VarIndex>>) {
         use crate::comps::diode;
@@ -98,7 +185,7 @@ impl<'a, NumT: SpNum> Elaborator<'a, NumT> {
         };
         let diode::DiodeCacheEntry { model, intp, .. } = ddef;
         // Derive internal params
-        let ports = diode::DiodePorts::from(self.pathstr(), &*model.read(), pvar, nvar, &mut self.vars);
+        let ports = diode::DiodePorts::from(self.pathstr(), &self.opts.hier_separator, &*model.read(), pvar, nvar, &mut self.vars);
         // And create our solver
         let d = diode::Diode {
             ports,
@@ -106,8 +193,33 @@ impl<'a, NumT: SpNum> Elaborator<'a, NumT> {
             intp,
             ..Default::default()
         };
+        self.push_comp(d.into(), self.pathstr(), 1.0);
         self.path.pop();
-        self.comps.push(d.into());
+    }
+    /// Elaborate a plugin device, via the factory registered for its `kind`.
+    /// Fails for unregistered kinds, missing port-connections, and errors from the factory.
+    pub(crate) fn elaborate_device(&mut self, d: circuit::DeviceI, autonode: bool, ns: &mut HashMap<String, Option<VarIndex>>) -> SpResult<()> {
+        use crate::comps::plugin::Plugin;
+        let circuit::DeviceI { name, kind, ports, params } = d;
+        let def = match self.defs.devices.get(&kind) {
+            Some(def) => def.clone(),
+            None => return Err(sperror(format!("Device Kind Not Registered: {}", kind))),
+        };
+        let mut terms = vec![];
+        for port in def.ports.iter() {
+            let node = match ports.get(port) {
+                Some(node) => node.clone(),
+                None => return Err(sperror(format!("Device {} Missing Connection to Port {}", name, port))),
+            };
+            terms.push(self.node_var(circuit::n(node), autonode, ns));
+        }
+        let dev = match (def.factory)(&params) {
+            Ok(dev) => dev,
+            Err(e) => return Err(sperror(format!("Device {} Creation Failed: {}", name, e.desc))),
+        };
+        let name = self.instname(name);
+        self.push_comp(Plugin::new(dev, terms).into(), name, 1.0);
+        Ok(())
     }
     pub(crate) fn elaborate_vsrc(&mut self, vi: circuit::Vi, ns: &mut HashMap<String, Option<VarIndex>>) {
         use crate::comps::Vsrc;
@@ -121,9 +233,46 @@ impl<'a, NumT: SpNum> Elaborator<'a, NumT> {
         // Create the current variable, named `self.path`
         self.path.push(name);
         let ivar = self.vars.addi(self.pathstr());
-        self.path.pop();
         // And create our solver
-        self.comps.push(Vsrc::new(vdc, acm, pvar, nvar, ivar).into());
+        self.push_comp(Vsrc::new(vdc, acm, pvar, nvar, ivar).into(), self.pathstr(), 1.0);
+        self.path.pop();
+    }
+    pub(crate) fn elaborate_inductor(&mut self, li: circuit::Li, ns: &mut HashMap<String, Option<VarIndex>>) {
+        use crate::comps::Inductor;
+        let circuit::Li { name, l, rser, p, n } = li;
+        let pvar = self.node_var(p, self.on_top(), ns);
+        let nvar = self.node_var(n, self.on_top(), ns);
+        // Create the current variable, named `self.path`, as for voltage sources
+        self.path.push(name);
+        let ivar = self.vars.addi(self.pathstr());
+        self.inductors.insert(self.pathstr(), (ivar, l));
+        let ind = if rser > 0.0 {
+            // Series resistance adds an internal node, between it and the inductance
+            let rvar = self.vars.addv(format!("{}{}{}", self.pathstr(), self.opts.hier_separator, "rser"));
+            Inductor::new(l, Some(rvar), nvar, ivar).with_rser(rser, pvar)
+        } else {
+            Inductor::new(l, pvar, nvar, ivar)
+        };
+        self.push_comp(ind.into(), self.pathstr(), 1.0);
+        self.path.pop();
+    }
+    /// Elaborate a mutual inductance, between two inductors in the current scope.
+    /// Requires both be elaborated first; see `mutuals_last`.
+    pub(crate) fn elaborate_mutual(&mut self, ki: circuit::Ki) -> SpResult<()> {
+        use crate::comps::Mutual;
+        let circuit::Ki { name, l1, l2, k } = ki;
+        let name = self.instname(name);
+        if !(-1.0..=1.0).contains(&k) {
+            return Err(sperror(format!("Mutual Inductance {} Coupling Out of Range: {}", name, k)));
+        }
+        let inductor = |l: String| match self.inductors.get(&self.instname(l.clone())) {
+            Some(e) => Ok(*e),
+            None => Err(sperror(format!("Mutual Inductance {} References Unknown Inductor: {}", name, l))),
+        };
+        let ((i1, l1), (i2, l2)) = (inductor(l1)?, inductor(l2)?);
+        let m = k * (l1 * l2).sqrt();
+        self.push_comp(Mutual::new(m, i1, i2).into(), name, 1.0);
+        Ok(())
     }
     pub(crate) fn elaborate_mos(&mut self, m: circuit::Mosi, ns: &mut HashMap<String, Option<VarIndex>>) {
         use crate::comps::{bsim4, mos};
@@ -140,9 +289,10 @@ impl<'a, NumT: SpNum> Elaborator<'a, NumT> {
         self.path.push(name);
 
         // Determine solver-type from our `Defs` models
+        let mut m = 1.0;
         let c: ComponentSolver = if let Some(_m) = self.defs.bsim4.models.get(&model) {
             let (model, inst) = self.defs.bsim4.get(&model, &params).unwrap();
-            let ports = bsim4::Bsim4Ports::from(self.pathstr(), &ports, &model.vals, &inst.intp, &mut self.vars);
+            let ports = bsim4::Bsim4Ports::from(self.pathstr(), &self.opts.hier_separator, &ports, &model.vals, &inst.intp, &mut self.vars);
             bsim4::Bsim4::new(ports, model, inst).into()
         } else if let Some(m_) = self.defs.mos1.models.get(&model) { 
             // Get our model and params from definitions
@@ -151,7 +301,8 @@ impl<'a, NumT: SpNum> Elaborator<'a, NumT> {
                 None => panic!(format!("Parameters not defined: {}", params)),
             };
             let mos::Mos1CacheEntry { model, intp, inst } = e;
-            let ports = mos::Mos1Vars::from(self.pathstr(), &ports, &*model.read(), &mut self.vars);
+            m = inst.read().m();
+            let ports = mos::Mos1Vars::from(self.pathstr(), &self.opts.hier_separator, &ports, &*model.read(), &mut self.vars);
             mos::Mos1 {
                 ports,
                 model,
@@ -167,55 +318,151 @@ impl<'a, NumT: SpNum> Elaborator<'a, NumT> {
             panic!(format!("Model not defined: {}", model));
         };
         // Add the ComponentSolver
-        self.comps.push(c);
+        self.push_comp(c, self.pathstr(), m);
         // And pop its instance-name
         self.path.pop();
     }
-    /// Concatenate our path into a period-separated string
+    /// Add solver `comp`, named `name`, with (instance-level) multiplier `m`.
+    /// Wraps `comp` in a `Multiplied` when its combination with the hierarchy's multiplier is other than one.
+    fn push_comp(&mut self, comp: ComponentSolver<'a>, name: String, m: f64) {
+        let m = m * self.mult;
+        if m == 1.0 {
+            self.comps.push(comp);
+        } else {
+            let comp = Box::new(comp);
+            self.comps.push(Multiplied { m, comp }.into());
+        }
+        self.names.push(name);
+    }
+    /// Concatenate our path into a string, separated by `Options.hier_separator`
     fn pathstr(&self) -> String {
-        self.path.join(".")
+        self.path.join(&self.opts.hier_separator)
+    }
+    /// Hierarchical path-name of instance `name` in our current scope
+    fn instname(&self, name: String) -> String {
+        let mut path = self.path.clone();
+        path.push(name);
+        path.join(&self.opts.hier_separator)
     }
     /// Boolean helper function, indicating whether we are currently at top-level
     fn on_top(&self) -> bool {
         self.path.len() == 0
     }
-    pub(crate) fn elaborate_module_inst(&mut self, m: circuit::ModuleI, ns: &mut HashMap<String, Option<VarIndex>>) {
-        let circuit::ModuleI { name, module, ports, .. } = m;
-        // FIXME: parameter handling
-
-        let mdef = match self.defs.modules.get(&module) {
-            Some(md) => md,
-            None => panic!("ModuleDef not found: {}", module),
-        };
+    pub(crate) fn elaborate_module_inst(&mut self, m: circuit::ModuleI, ns: &mut HashMap<String, Option<VarIndex>>) -> SpResult<()> {
+        let circuit::ModuleI { name, module, ports, params } = m;
+        let mdef = self.module_def(&module)?;
+        let m = module_mult(&mdef.read(), &name, params.iter().map(|(k, v)| (k, *v)))?;
+        // Ports connect to signals of the enclosing scope
+        let mut port_vars: HashMap<String, Option<VarIndex>> = HashMap::new();
+        for (k, v) in ports.iter() {
+            let var = match ns.get(v) {
+                Some(var) => *var,
+                None => return Err(sperror(format!("Port {} of Instance {} Connected to Unknown Signal {}", k, name, v))),
+            };
+            port_vars.insert(k.clone(), var);
+        }
+        self.elaborate_module_ports(&mdef, name, port_vars, m)
+    }
+    /// Elaborate a Module Instance Array.
+    /// Expands into `width` instances named `name[i]`, all sharing a single `ModuleDef` pointer.
+    /// Port connections including the placeholder `{i}` are replaced by each instance's index,
+    /// creating a signal for each distinct connection.
+    /// Instance `i` takes entry `i` of each parameter column, as would an ordinary module instance.
+    pub(crate) fn elaborate_module_array(&mut self, a: circuit::ModuleArrayI, ns: &mut HashMap<String, Option<VarIndex>>) -> SpResult<()> {
+        let circuit::ModuleArrayI {
+            name,
+            module,
+            width,
+            ports,
+            params,
+        } = a;
+        // Check our parameter-table has a value for each instance
+        for (pname, col) in params.iter() {
+            if col.vals.len() != width as usize {
+                let msg = format!("Parameter {} of Array {} has {} Values, Expected {}", pname, name, col.vals.len(), width);
+                return Err(sperror(msg));
+            }
+        }
+        // Look up the module definition once, for all instances
+        let mdef = self.module_def(&module)?;
+        for i in 0..width as usize {
+            let inst = format!("{}[{}]", name, i);
+            let m = module_mult(&mdef.read(), &inst, params.iter().map(|(k, col)| (k, col.vals[i])))?;
+            let idx = i.to_string();
+            let mut port_vars: HashMap<String, Option<VarIndex>> = HashMap::new();
+            for (k, v) in ports.iter() {
+                let var = self.node_var(circuit::n(v.replace("{i}", &idx)), self.on_top(), ns);
+                port_vars.insert(k.clone(), var);
+            }
+            self.elaborate_module_ports(&mdef, inst, port_vars, m)?;
+        }
+        Ok(())
+    }
+    /// Retrieve the definition of Module `module`
+    fn module_def(&mut self, module: &str) -> SpResult<DefPtr<circuit::ModuleDef>> {
+        match self.defs.modules.get(module) {
+            Some(md) => Ok(md),
+            None => Err(sperror(format!("ModuleDef Not Found: {}", module))),
+        }
+    }
+    /// Elaborate an instance of `mdef` named `name`, with port-variables `port_vars` and multiplier `m`.
+    /// Requires a connection to each of the module's ports, and no others.
+    /// The multiplier scales every device inside the instance, composing with those of enclosing instances and of the devices themselves.
+    fn elaborate_module_ports(
+        &mut self,
+        mdef: &DefPtr<circuit::ModuleDef>,
+        name: String,
+        port_vars: HashMap<String, Option<VarIndex>>,
+        m: f64,
+    ) -> SpResult<()> {
+        {
+            let mdef = mdef.read();
+            if port_vars.len() != mdef.ports.len() || mdef.ports.iter().any(|p| !port_vars.contains_key(p)) {
+                let mut conns: Vec<&String> = port_vars.keys().collect();
+                conns.sort();
+                let msg = format!(
+                    "Instance {} of Module {} Connects Ports {:?}, Expected {:?}",
+                    name, mdef.name, conns, mdef.ports
+                );
+                return Err(sperror(msg));
+            }
+        }
         // Each Module instance generates a new namespace.
-        // Initialize it by grabbing the variables corresponding to each port.
-        // By the time we get here, each value in the `m.ports` map
-        // must correspond to an existing variable, or elaboration fails.
+        // Initialize it with the variables corresponding to each port, as resolved by our caller.
         // (This is essentially where connections are made.)
         // This variable-map `inst_ns` seeds the module-innards namespace.
-        let mut inst_ns: HashMap<String, Option<VarIndex>> = HashMap::new();
-        for (k, v) in &ports {
-            let var = ns.get(v).unwrap().clone();
-            inst_ns.insert(k.clone(), var);
-        }
+        let mut inst_ns = port_vars;
         self.path.push(name);
         if self.path.len() > 1024 {
-            panic!("Elaboration Error: Too deep a hierarchy (for now)!");
+            return Err(sperror("Elaboration Error: Too deep a hierarchy (for now)!"));
         }
-        self.elaborate_module(&*mdef.read(), &mut inst_ns);
+        // Alias each port's hierarchical path, e.g. `x1.out`, to its connected variable
+        for (k, var) in inst_ns.iter() {
+            if let Some(var) = var {
+                self.path.push(k.clone());
+                let path = self.pathstr();
+                self.vars.alias(path, *var);
+                self.path.pop();
+            }
+        }
+        let mult = self.mult;
+        self.mult *= m;
+        self.elaborate_module(&*mdef.read(), &mut inst_ns)?;
+        self.mult = mult;
         self.path.pop();
+        Ok(())
     }
     /// Create a new Signal at `self.path.signame`, and append it to `ns`.
     pub(crate) fn elaborate_signal(&mut self, signame: &str, ns: &mut HashMap<String, Option<VarIndex>>) {
         // FIXME: add checks for name collisions
         self.path.push(signame.to_string());
-        let pathname = self.path.join(".");
+        let pathname = self.pathstr();
         let var = self.vars.addv(pathname);
         ns.insert(signame.to_string(), Some(var));
         self.path.pop();
     }
     /// Elaborate the content of `ModuleDef` `m`.
-    pub(crate) fn elaborate_module(&mut self, m: &circuit::ModuleDef, ns: &mut HashMap<String, Option<VarIndex>>) {
+    pub(crate) fn elaborate_module(&mut self, m: &circuit::ModuleDef, ns: &mut HashMap<String, Option<VarIndex>>) -> SpResult<()> {
         let circuit::ModuleDef { signals, comps, .. } = m;
         // FIXME: parameter handling
 
@@ -228,38 +475,149 @@ impl<'a, NumT: SpNum> Elaborator<'a, NumT> {
         // Top-levels have a Vec<circuit::Comp> (already converted)
         // Modules have a Vec<proto::Comp> (i.e. the interface objects )
         // FIXME: check port/ param compatibility
-        for inst in comps.iter() {
-            let comp = if let Some(i) = inst.comp.clone() {
-                circuit::Comp::from(i)
-            } else {
-                panic!("Invalid Comp!!!")
-            };
-            self.elaborate_instance(comp, ns, false);
+        let comps = comps
+            .iter()
+            .map(|inst| match inst.comp.clone() {
+                Some(i) => circuit::Comp::from_proto(i),
+                None => panic!("Invalid Comp!!!"),
+            })
+            .collect::<SpResult<_>>()?;
+        for comp in mutuals_last(comps) {
+            self.elaborate_instance(comp, ns, false)?;
+        }
+        Ok(())
+    }
+}
+/// Multiplier of an instance `name` of Module `mdef`, with parameter values `params`.
+/// Other than the multiplier `m`, parameters must be declared by the module.
+fn module_mult<'p>(mdef: &circuit::ModuleDef, name: &str, params: impl Iterator<Item = (&'p String, f64)>) -> SpResult<f64> {
+    let mut m = 1.0;
+    for (k, v) in params {
+        if k == "m" {
+            m = v;
+        } else if !mdef.params.contains_key(k) {
+            return Err(sperror(format!("Unknown Parameter {} of Instance {} of Module {}", k, name, mdef.name)));
         }
     }
+    Ok(m)
+}
+impl<'a, NumT: SpNum> Elaborator<'a, NumT> {
+    /// Create an Elaborator of circuit-definitions `defs`, with no components.
+    /// Variables reuse the indices of any prior `layout`.
+    pub(crate) fn new(defs: defs::Defs, opts: Options, layout: Option<&VarLayout>) -> Self {
+        let vars = match layout {
+            Some(l) => Variables::with_layout(l),
+            None => Variables::new(),
+        };
+        Elaborator {
+            comps: Vec::new(),
+            names: Vec::new(),
+            vars,
+            defs,
+            path: Vec::new(),
+            opts,
+            mult: 1.0,
+            variations: Vec::new(),
+            inductors: HashMap::new(),
+        }
+    }
+    /// Elaborate top-level components `comps` and signals `signals`.
+    /// Variables of any prior layout not claimed by them remain as placeholders, held at zero by a unit conductance,
+    /// so that no surviving index shifts.
+    pub(crate) fn elaborate_top(&mut self, comps: Vec<Comp>, signals: Vec<String>) -> SpResult<()> {
+        // Initialize the top-level namespace with Gnd
+        let mut ns: HashMap<String, Option<VarIndex>> = HashMap::new();
+        ns.insert("".into(), None);
+        // Add Variables for each top-level Signal
+        for signame in signals.iter() {
+            self.elaborate_signal(signame, &mut ns);
+        }
+        // Visit all of our components
+        for inst in mutuals_last(comps) {
+            self.elaborate_instance(inst, &mut ns, true)?; // FIXME: autonode'ing top-level instances
+        }
+        // Hold any unclaimed prior variables at zero
+        for var in self.vars.take_pending().into_iter() {
+            use crate::comps::Resistor;
+            let name = self.vars.name(var).to_string();
+            self.push_comp(Resistor::new(1.0, Some(var), None).into(), name, 1.0);
+        }
+        Ok(())
+    }
 }
 /// Elaborate a top-level circuit
 /// Returns the generated `Elaborator`, including its flattened `ComponentSolvers`
 /// and all definitions carried over from `ckt`.
-pub(crate) fn elaborate<'a, T: SpNum>(ckt: circuit::Ckt, opts: Options) -> Elaborator<'a, T> {
+#[cfg(test)]
+pub(crate) fn elaborate<'a, T: SpNum>(ckt: circuit::Ckt, opts: Options) -> SpResult<Elaborator<'a, T>> {
+    elaborate_with(ckt, opts, None)
+}
+/// Elaborate a top-level circuit, reusing the variable-indices of a prior `layout`.
+/// Variables present in `layout` keep their indices; new ones are appended after them.
+/// Any no longer present remain as placeholders, held at zero by a unit conductance,
+/// so that no surviving index shifts.
+pub(crate) fn elaborate_with<'a, T: SpNum>(ckt: circuit::Ckt, opts: Options, layout: Option<&VarLayout>) -> SpResult<Elaborator<'a, T>> {
     let circuit::Ckt { comps, defs, signals, .. } = ckt;
-    let mut e = Elaborator {
-        comps: Vec::new(),
-        vars: Variables::new(),
-        defs,
-        path: Vec::new(),
-        opts,
-    };
-    // Initialize the top-level namespace with Gnd
-    let mut ns: HashMap<String, Option<VarIndex>> = HashMap::new();
-    ns.insert("".into(), None);
-    // Add Variables for each top-level Signal
-    for signame in signals.iter() {
-        e.elaborate_signal(signame, &mut ns);
+    let mut e = Elaborator::new(defs, opts, layout);
+    e.elaborate_top(comps, signals)?;
+    Ok(e)
+}
+
+/// Re-order `comps` to place mutual inductances after everything else, i.e. after the inductors they reference
+fn mutuals_last(comps: Vec<Comp>) -> impl Iterator<Item = Comp> {
+    let (mutuals, others): (Vec<Comp>, Vec<Comp>) = comps.into_iter().partition(|c| matches!(c, Comp::K(_)));
+    others.into_iter().chain(mutuals)
+}
+
+///
+/// # Flattened Circuit
+///
+/// An elaborated circuit, with its matrix structure created but not yet factored.
+/// Primarily useful for inspecting problem size prior to simulation.
+///
+pub struct FlatCkt<'a> {
+    pub(crate) comps: Vec<ComponentSolver<'a>>,
+    pub(crate) names: Vec<String>,
+    pub(crate) vars: Variables<f64>,
+    pub(crate) mat: Matrix<f64>,
+}
+impl<'a> FlatCkt<'a> {
+    /// Elaborate `ckt` and create its matrix elements
+    pub fn new(ckt: circuit::Ckt, opts: Option<Options>) -> SpResult<Self> {
+        Self::elab(ckt, opts, None)
+    }
+    /// Re-elaborate `ckt`, typically an edited version of a prior circuit, keeping the variable-indices of `layout`.
+    /// See `elaborate_with` for handling of added and removed variables.
+    pub fn with_layout(ckt: circuit::Ckt, opts: Option<Options>, layout: &VarLayout) -> SpResult<Self> {
+        Self::elab(ckt, opts, Some(layout))
+    }
+    fn elab(ckt: circuit::Ckt, opts: Option<Options>, layout: Option<&VarLayout>) -> SpResult<Self> {
+        let opts = if let Some(o) = opts { o } else { Options::default() };
+        let Elaborator { mut comps, names, vars, .. } = elaborate_with(ckt, opts, layout)?;
+        let mut mat = Matrix::new();
+        for comp in comps.iter_mut() {
+            comp.create_matrix_elems(&mut mat);
+        }
+        Ok(FlatCkt { comps, names, vars, mat })
+    }
+    /// Matrix dimensions, as a tuple of (number of unknowns, number of non-zero elements)
+    pub fn matrix_dims(&self) -> (usize, usize) {
+        (self.vars.len(), self.mat.nnz())
+    }
+    /// Number of flattened Component instances
+    pub fn num_comps(&self) -> usize {
+        self.comps.len()
+    }
+    /// Hierarchical names of each flattened Component instance
+    pub fn comp_names(&self) -> &[String] {
+        &self.names
+    }
+    /// Names and kinds of each variable, in index order
+    pub fn layout(&self) -> VarLayout {
+        self.vars.layout()
     }
-    // Visit all of our components
-    for inst in comps.into_iter() {
-        e.elaborate_instance(inst, &mut ns, true); // FIXME: autonode'ing top-level instances
+    /// Index of the variable named `name`, preferring node-voltages over currents
+    pub fn var_index(&self, name: &str) -> Option<VarIndex> {
+        self.vars.find(name)
     }
-    e
 }
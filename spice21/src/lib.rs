@@ -26,7 +26,7 @@ pub(crate) mod macros {
     ]) => {
         #[allow(dead_code)]
         #[doc=$struct_desc]
-        #[derive(Clone)]
+        #[derive(Clone, Debug)]
         pub struct $src_name {
             $( #[doc=$desc]
                 pub $attr_name : $attr_type ),*
@@ -158,21 +158,21 @@ pub mod circuit;
 pub mod comps;
 pub mod defs;
 pub mod elab;
+pub mod netlist;
+pub mod noise;
 pub mod proto;
 pub mod sparse21;
+pub mod spnum;
 pub mod spresult;
 
 // Re-exports
 pub use analysis::*;
 pub use proto::*;
+pub use spnum::*;
 pub use spresult::*;
 
-// Crate-wide public
-pub(crate) use spnum::*;
-
 // Private modules
 mod assert;
-mod spnum;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,793 @@
+//!
+//! # SPICE Netlist Parser
+//!
+//! Converts SPICE-format netlists into `proto::Circuit`s, for the same elaboration as the YAML, JSON, and TOML front ends.
+//! Supports the DC-relevant subset of cards:
+//!
+//! * Elements `R`, `C`, `L`, `V`, `I`, `D`, `M`, and subcircuit calls `X`
+//! * `.model` definitions of level-one MOSFETs (`nmos`, `pmos`) and diodes (`d`)
+//! * `.subckt` / `.ends` definitions, which become `Module`s, one per distinct set of subcircuit-parameter values
+//! * `.param` definitions, referenced by name or in `{expression}`s
+//!
+//! Analysis and output cards (`.op`, `.tran`, `.print`, and the like) are ignored.
+//! As in SPICE, the first line is the circuit's title, names are case-insensitive, and node `0` is ground.
+//!
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::proto;
+use crate::proto::def::Defines;
+use crate::proto::instance::Comp as CompProto;
+use crate::{SpError, SpResult};
+
+/// Parse SPICE netlist `src`
+pub fn parse(src: &str) -> SpResult<proto::Circuit> {
+    let mut lines = src.lines();
+    let name = lines.next().unwrap_or("").trim().trim_start_matches('*').trim().to_string();
+    let cards = cards(lines.enumerate().map(|(k, l)| (k + 2, l)))?;
+    let mut p = Parser {
+        params: HashMap::new(),
+        locals: HashMap::new(),
+        subckts: HashMap::new(),
+        modules: HashSet::new(),
+        stack: vec![],
+        defs: vec![],
+    };
+    p.collect(&cards)?;
+    let (comps, signals) = p.scope(&cards, &[], None)?;
+    Ok(proto::Circuit {
+        name,
+        signals,
+        defs: p.defs,
+        comps,
+    })
+}
+
+/// Netlist Card, i.e. a line plus any continuations, split into tokens.
+/// Always has at least one token.
+#[derive(Clone)]
+struct Card {
+    /// Line number of its first line
+    line: usize,
+    tokens: Vec<String>,
+}
+impl Card {
+    fn err<S: Into<String>>(&self, msg: S) -> SpError {
+        SpError::new(format!("SPICE Parse Error, line {}: {}", self.line, msg.into()))
+    }
+}
+
+/// Split numbered `lines` into `Card`s, removing comments and joining `+` continuations
+fn cards<'a>(lines: impl Iterator<Item = (usize, &'a str)>) -> SpResult<Vec<Card>> {
+    let mut rv: Vec<Card> = vec![];
+    let mut control = false;
+    for (line, text) in lines {
+        let text = text.split(';').next().unwrap_or("");
+        let text = text.split(" $").next().unwrap_or("").trim().to_lowercase();
+        if text.is_empty() || text.starts_with('*') {
+            continue;
+        }
+        // Skip the contents of `.control` blocks, which are scripts rather than cards
+        if text.starts_with(".control") {
+            control = true;
+        }
+        if control {
+            control = !text.starts_with(".endc");
+            continue;
+        }
+        if text.starts_with(".end") && !text.starts_with(".ends") {
+            break;
+        }
+        let tokens = tokenize(text.trim_start_matches('+'));
+        match (text.starts_with('+'), rv.last_mut()) {
+            (true, Some(card)) => card.tokens.extend(tokens),
+            (true, None) => return Err(SpError::new(format!("SPICE Parse Error, line {}: Continuation Without Card", line))),
+            (false, _) if tokens.is_empty() => return Err(SpError::new(format!("SPICE Parse Error, line {}: Empty Card", line))),
+            (false, _) => rv.push(Card { line, tokens }),
+        }
+    }
+    Ok(rv)
+}
+
+/// Split a card into tokens.
+/// Whitespace, commas, and parentheses separate tokens, and `=` is its own token.
+/// Brace-enclosed expressions are kept whole.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut rv = vec![];
+    let mut tok = String::new();
+    let mut depth = 0;
+    for c in text.chars() {
+        match c {
+            '{' => {
+                depth += 1;
+                tok.push(c);
+            }
+            '}' => {
+                depth -= 1;
+                tok.push(c);
+            }
+            _ if depth > 0 => tok.push(c),
+            ' ' | '\t' | ',' | '(' | ')' | '=' => {
+                if !tok.is_empty() {
+                    rv.push(std::mem::take(&mut tok));
+                }
+                if c == '=' {
+                    rv.push("=".into());
+                }
+            }
+            _ => tok.push(c),
+        }
+    }
+    if !tok.is_empty() {
+        rv.push(tok);
+    }
+    rv
+}
+
+/// Parse a SPICE number, e.g. `1.5`, `10k`, `2.2uF`, or `1meg`.
+/// Scale-factor suffixes are case-insensitive, and any trailing unit-letters ignored.
+fn parse_number(s: &str) -> Option<f64> {
+    let s = s.to_lowercase();
+    // Find the longest numeric prefix
+    let end = (1..=s.len()).rev().find(|k| s.is_char_boundary(*k) && s[..*k].parse::<f64>().is_ok())?;
+    let num: f64 = s[..end].parse().ok()?;
+    if !num.is_finite() {
+        return None; // e.g. `inf` or `nan`
+    }
+    let suffix = &s[end..];
+    if !suffix.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let scale = if suffix.starts_with("meg") {
+        1e6
+    } else if suffix.starts_with("mil") {
+        25.4e-6
+    } else {
+        match suffix.chars().next() {
+            None => 1.0,
+            Some('t') => 1e12,
+            Some('g') => 1e9,
+            Some('k') => 1e3,
+            Some('m') => 1e-3,
+            Some('u') => 1e-6,
+            Some('n') => 1e-9,
+            Some('p') => 1e-12,
+            Some('f') => 1e-15,
+            Some('a') => 1e-18,
+            Some(_) => 1.0, // Units, e.g. the `v` of `1v`
+        }
+    };
+    Some(num * scale).filter(|x| x.is_finite())
+}
+
+/// A `.subckt` definition, gathered ahead of its instances
+#[derive(Clone)]
+struct Subckt {
+    /// Its `.subckt` card
+    card: Card,
+    ports: Vec<String>,
+    /// Default-parameter tokens, as `name = value` triplets
+    defaults: Vec<String>,
+    body: Vec<Card>,
+}
+
+/// Netlist Parser State
+struct Parser {
+    /// `.param` values
+    params: HashMap<String, f64>,
+    /// Parameter values of the `.subckt` being converted, if any. These take precedence over `params`.
+    locals: HashMap<String, f64>,
+    /// Subcircuit definitions, by name
+    subckts: HashMap<String, Subckt>,
+    /// Names of the modules generated so far
+    modules: HashSet<String>,
+    /// Subcircuits currently being converted, innermost last
+    stack: Vec<String>,
+    /// Generated definitions: models, instance-parameter sets, and modules
+    defs: Vec<proto::Def>,
+}
+impl Parser {
+    /// First pass: collect `.param` values and `.subckt` definitions, which may be referenced before their definitions
+    fn collect(&mut self, cards: &[Card]) -> SpResult<()> {
+        for (k, card) in cards.iter().enumerate() {
+            match card.tokens[0].as_str() {
+                ".param" => {
+                    // Each may reference those before it
+                    for pair in card.tokens[1..].chunks(3) {
+                        for (k, v) in self.pairs(card, pair)? {
+                            self.params.insert(k, v);
+                        }
+                    }
+                }
+                ".subckt" => {
+                    if card.tokens.len() < 2 {
+                        return Err(card.err("Missing Subcircuit Name"));
+                    }
+                    // Ports are followed by any default parameters, either after `params:` or as `name = value` pairs
+                    let rest = &card.tokens[2..];
+                    let nports = match rest.iter().position(|t| t == "params:" || t == "=") {
+                        Some(0) if rest[0] == "=" => return Err(card.err("Invalid Subcircuit Parameters")),
+                        Some(k) if rest[k] == "=" => k - 1,
+                        Some(k) => k,
+                        None => rest.len(),
+                    };
+                    let body = match cards[k + 1..].iter().position(|c| c.tokens[0] == ".ends") {
+                        Some(e) => cards[k + 1..k + 1 + e].to_vec(),
+                        None => return Err(card.err("Subcircuit Missing .ends")),
+                    };
+                    let subckt = Subckt {
+                        card: card.clone(),
+                        ports: rest[..nports].to_vec(),
+                        defaults: rest[nports..].iter().filter(|t| *t != "params:").cloned().collect(),
+                        body,
+                    };
+                    self.subckts.insert(card.tokens[1].clone(), subckt);
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+    /// Second pass: convert the cards of a scope, either the top-level or that of module `module`, with ports `ports`.
+    /// Returns its instances and signals, in order of first reference.
+    fn scope(&mut self, cards: &[Card], ports: &[String], module: Option<&str>) -> SpResult<(Vec<proto::Instance>, Vec<String>)> {
+        let mut comps = vec![];
+        let mut signals: Vec<String> = vec![];
+        let mut seen: HashSet<String> = ports.iter().cloned().collect();
+        let mut k = 0;
+        while k < cards.len() {
+            let card = &cards[k];
+            let first = card.tokens[0].as_str();
+            k += 1;
+            if first == ".subckt" {
+                if module.is_some() {
+                    return Err(card.err("Nested Subcircuit Definitions Not Supported"));
+                }
+                // Generate the module for its default parameters. Those with other parameters are generated by their instances.
+                let name = card.tokens[1].clone();
+                self.module(card, &name, &[])?;
+                k += self.subckts[&name].body.len() + 1;
+                continue;
+            }
+            if first == ".ends" {
+                return Err(card.err(".ends Without .subckt"));
+            }
+            if first.starts_with('.') {
+                self.control(card)?;
+                continue;
+            }
+            let comp = self.element(card, module)?;
+            for node in nodes(&comp) {
+                if !node.is_empty() && seen.insert(node.clone()) {
+                    signals.push(node);
+                }
+            }
+            comps.push(proto::Instance { comp: Some(comp) });
+        }
+        Ok((comps, signals))
+    }
+    /// Convert subcircuit `name` into a `Module`, with its default parameters replaced by `overrides`.
+    /// Parameter values are substituted during conversion, so each distinct set generates its own module.
+    /// Those with non-default values are named after them, e.g. `inv(w=2e-6)`.
+    /// Returns the module's name. Errors, referring to `card`, for overrides of undeclared parameters.
+    fn module(&mut self, card: &Card, name: &str, overrides: &[(String, f64)]) -> SpResult<String> {
+        let subckt = match self.subckts.get(name) {
+            Some(s) => s.clone(),
+            None => return Err(card.err(format!("Subcircuit Not Found: {}", name))),
+        };
+        // Default values may only refer to global `.param`s
+        let outer = std::mem::take(&mut self.locals);
+        let defaults = self.pairs(&subckt.card, &subckt.defaults);
+        self.locals = outer;
+        let defaults = defaults?;
+        let mut params = defaults.clone();
+        for (k, v) in overrides.iter() {
+            match params.iter_mut().find(|(p, _)| p == k) {
+                Some(p) => p.1 = *v,
+                None => return Err(card.err(format!("Unknown Parameter {} of Subcircuit {}", k, name))),
+            }
+        }
+        let mname = if params == defaults {
+            name.to_string()
+        } else {
+            let vals: Vec<String> = params.iter().map(|(k, v)| format!("{}={:e}", k, v)).collect();
+            format!("{}({})", name, vals.join(","))
+        };
+        if self.modules.contains(&mname) {
+            return Ok(mname);
+        }
+        if self.stack.iter().any(|s| s == name) {
+            return Err(card.err(format!("Recursive Subcircuit: {}", name)));
+        }
+        self.modules.insert(mname.clone());
+
+        let outer = std::mem::replace(&mut self.locals, params.iter().cloned().collect());
+        self.stack.push(name.to_string());
+        let scope = self.scope(&subckt.body, &subckt.ports, Some(&mname));
+        self.stack.pop();
+        self.locals = outer;
+        let (comps, signals) = scope?;
+
+        let module = proto::Module {
+            name: mname.clone(),
+            ports: subckt.ports,
+            signals,
+            comps,
+            params: params.into_iter().collect(),
+        };
+        self.defs.push(proto::Def {
+            defines: Some(Defines::Module(module)),
+        });
+        Ok(mname)
+    }
+    /// Handle a dot-card other than `.subckt`
+    fn control(&mut self, card: &Card) -> SpResult<()> {
+        match card.tokens[0].as_str() {
+            ".model" => self.model(card),
+            ".param" => Ok(()), // Collected in the first pass
+            // Analysis and output cards
+            ".op" | ".dc" | ".ac" | ".tran" | ".noise" | ".print" | ".plot" | ".probe" | ".save" | ".option" | ".options" | ".temp" | ".ic"
+            | ".nodeset" | ".meas" | ".measure" | ".global" => Ok(()),
+            other => Err(card.err(format!("Unsupported Card: {}", other))),
+        }
+    }
+    /// Convert a `.model` card
+    fn model(&mut self, card: &Card) -> SpResult<()> {
+        if card.tokens.len() < 3 {
+            return Err(card.err("Invalid .model"));
+        }
+        let name = card.tokens[1].clone();
+        let mut params = self.pairs(card, &card.tokens[3..])?;
+        let defines = match card.tokens[2].as_str() {
+            t @ "nmos" | t @ "pmos" => {
+                match params.iter().position(|(k, _)| k == "level") {
+                    Some(k) if params[k].1 != 1.0 => return Err(card.err(format!("Unsupported MOS Level: {}", params[k].1))),
+                    Some(k) => {
+                        params.remove(k);
+                    }
+                    None => (),
+                }
+                let mut model: proto::Mos1Model = from_params(card, &name, params, &[("vto", "vt0")])?;
+                model.mos_type = if t == "pmos" { proto::MosType::Pmos } else { proto::MosType::Nmos } as i32;
+                Defines::Mos1model(model)
+            }
+            "d" => Defines::Diodemodel(from_params(card, &name, params, &[("cjo", "cj0")])?),
+            other => return Err(card.err(format!("Unsupported Model Type: {}", other))),
+        };
+        self.defs.push(proto::Def { defines: Some(defines) });
+        Ok(())
+    }
+    /// Convert an element card, in `.subckt` `module` if any
+    fn element(&mut self, card: &Card, module: Option<&str>) -> SpResult<CompProto> {
+        let t = &card.tokens;
+        let name = t[0].clone();
+        let node = |k: usize| match t.get(k) {
+            Some(n) if n == "0" => Ok(String::new()),
+            Some(n) => Ok(n.clone()),
+            None => Err(card.err(format!("Missing Node on {}", t[0]))),
+        };
+        // Two-terminal elements' value, either positional or as `<key>=`
+        let value = |key: &str| -> SpResult<f64> {
+            match (t.get(3), t.get(4), t.get(5)) {
+                (Some(k), Some(eq), Some(v)) if k == key && eq == "=" => self.value(card, v),
+                (Some(v), None, None) => self.value(card, v),
+                _ => Err(card.err(format!("Invalid Value for {}", t[0]))),
+            }
+        };
+        // Instance-parameter sets are named by instance, prefixed by any enclosing subcircuit
+        let pname = match module {
+            Some(m) => format!("{}.{}", m, name),
+            None => name.clone(),
+        };
+        let comp = match name.chars().next() {
+            Some('r') => {
+                let r = value("r")?;
+                if r == 0.0 {
+                    return Err(card.err(format!("Zero Resistance: {}", name)));
+                }
+                CompProto::R(proto::Resistor {
+                    name,
+                    p: node(1)?,
+                    n: node(2)?,
+                    g: 1.0 / r,
+                    ..Default::default()
+                })
+            }
+            Some('c') => CompProto::C(proto::Capacitor {
+                c: value("c")?,
+                name,
+                p: node(1)?,
+                n: node(2)?,
+                ..Default::default()
+            }),
+            Some('l') => CompProto::L(proto::Inductor {
+                l: value("l")?,
+                name,
+                p: node(1)?,
+                n: node(2)?,
+                ..Default::default()
+            }),
+            Some('v') => {
+                let (dc, acm) = self.source(card)?;
+                CompProto::V(proto::Vsrc {
+                    name,
+                    p: node(1)?,
+                    n: node(2)?,
+                    dc,
+                    acm,
+                })
+            }
+            Some('i') => {
+                let (dc, acm) = self.source(card)?;
+                if acm != 0.0 {
+                    return Err(card.err("AC Current Sources Not Supported"));
+                }
+                CompProto::I(proto::Isrc {
+                    name,
+                    p: node(1)?,
+                    n: node(2)?,
+                    dc,
+                })
+            }
+            Some('d') => {
+                let model = match t.get(3) {
+                    Some(m) => m.clone(),
+                    None => return Err(card.err(format!("Missing Model for {}", name))),
+                };
+                let mut params = self.pairs(card, &t[4..]).or_else(|_| match t.get(4) {
+                    // Positional area
+                    Some(a) if t.len() == 5 => Ok(vec![("area".to_string(), self.value(card, a)?)]),
+                    _ => Err(card.err(format!("Invalid Parameters for {}", name))),
+                })?;
+                params.retain(|(k, _)| k != "model");
+                let mut inst: proto::DiodeInstParams = from_params(card, &pname, params, &[])?;
+                inst.model = model.clone();
+                self.defs.push(proto::Def {
+                    defines: Some(Defines::Diodeinst(inst)),
+                });
+                CompProto::D(proto::Diode {
+                    name,
+                    p: node(1)?,
+                    n: node(2)?,
+                    model,
+                    params: pname,
+                })
+            }
+            Some('m') => {
+                let model = match t.get(5) {
+                    Some(m) => m.clone(),
+                    None => return Err(card.err(format!("Missing Model for {}", name))),
+                };
+                let params = self.pairs(card, &t[6..])?;
+                let inst: proto::Mos1InstParams = from_params(card, &pname, params, &[("ad", "a_d"), ("as", "a_s")])?;
+                self.defs.push(proto::Def {
+                    defines: Some(Defines::Mos1inst(inst)),
+                });
+                CompProto::M(proto::Mos {
+                    name,
+                    model,
+                    params: pname,
+                    ports: Some(proto::MosPorts {
+                        d: node(1)?,
+                        g: node(2)?,
+                        s: node(3)?,
+                        b: node(4)?,
+                    }),
+                })
+            }
+            Some('x') => {
+                // Nodes, then subcircuit name, then any `name = value` parameters, optionally after `params:`
+                let t: Vec<String> = t.iter().filter(|s| *s != "params:").cloned().collect();
+                let npos = t.iter().position(|s| s == "=").map_or(t.len(), |k| k - 1);
+                if npos < 2 {
+                    return Err(card.err(format!("Missing Subcircuit for {}", name)));
+                }
+                let module = t[npos - 1].clone();
+                let ports = match self.subckts.get(&module) {
+                    Some(s) => s.ports.clone(),
+                    None => return Err(card.err(format!("Subcircuit Not Found: {}", module))),
+                };
+                if ports.len() != npos - 2 {
+                    return Err(card.err(format!(
+                        "{} Has {} Connections, Subcircuit {} Has {} Ports",
+                        name,
+                        npos - 2,
+                        module,
+                        ports.len()
+                    )));
+                }
+                let rest = t[npos..].to_vec();
+                // Multiplier `m` applies to the instance, unless declared as a subcircuit parameter.
+                // Others generate a module with their values.
+                let declared = |k: &str| self.subckts[&module].defaults.chunks(3).any(|c| c[0] == k);
+                let mut overrides = self.pairs(card, &rest)?;
+                let params: HashMap<String, f64> = overrides.iter().filter(|(k, _)| k == "m" && !declared(k)).cloned().collect();
+                overrides.retain(|(k, _)| !params.contains_key(k));
+                let conns = (1..npos - 1).map(node).collect::<SpResult<Vec<String>>>()?;
+                let module = self.module(card, &module, &overrides)?;
+                CompProto::X(proto::ModuleInstance {
+                    name,
+                    module,
+                    ports: ports.into_iter().zip(conns).collect(),
+                    params,
+                })
+            }
+            _ => return Err(card.err(format!("Unsupported Element: {}", name))),
+        };
+        Ok(comp)
+    }
+    /// Parse the DC and AC values of an independent source, e.g. `v1 p n dc 1 ac 1`.
+    fn source(&self, card: &Card) -> SpResult<(f64, f64)> {
+        if card.tokens.len() < 3 {
+            return Err(card.err(format!("Missing Node on {}", card.tokens[0])));
+        }
+        let (mut dc, mut acm) = (0.0, 0.0);
+        let mut toks = card.tokens[3..].iter();
+        while let Some(tok) = toks.next() {
+            match tok.as_str() {
+                "dc" => dc = self.value(card, toks.next().map_or("", |s| s))?,
+                "ac" => {
+                    acm = self.value(card, toks.next().map_or("", |s| s))?;
+                    // Phase is not supported, other than zero
+                    if let Some(ph) = toks.next() {
+                        if self.value(card, ph)? != 0.0 {
+                            return Err(card.err("AC Source Phase Not Supported"));
+                        }
+                    }
+                }
+                other => match self.value(card, other) {
+                    Ok(v) => dc = v,
+                    Err(_) => return Err(card.err(format!("Unsupported Source Specification: {}", other))),
+                },
+            }
+        }
+        Ok((dc, acm))
+    }
+    /// Parse `name = value` pairs from `toks`
+    fn pairs(&self, card: &Card, toks: &[String]) -> SpResult<Vec<(String, f64)>> {
+        let mut rv = vec![];
+        for c in toks.chunks(3) {
+            if c.len() != 3 || c[1] != "=" {
+                return Err(card.err("Expected Parameters of the Form `name=value`"));
+            }
+            rv.push((c[0].clone(), self.value(card, &c[2])?));
+        }
+        Ok(rv)
+    }
+    /// Evaluate value-token `tok`: a number, a parameter name, or a brace-enclosed expression of either
+    fn value(&self, card: &Card, tok: &str) -> SpResult<f64> {
+        let tok = tok.trim_matches('\'');
+        if let Some(v) = parse_number(tok) {
+            return Ok(v);
+        }
+        if let Some(v) = self.locals.get(tok).or_else(|| self.params.get(tok)) {
+            return Ok(*v);
+        }
+        if tok.starts_with('{') && tok.ends_with('}') {
+            return self.expr(card, &tok[1..tok.len() - 1]);
+        }
+        Err(card.err(format!("Invalid Value: {}", tok)))
+    }
+    /// Evaluate expression `s`, replacing parameter names and SPICE-suffixed numbers with their values.
+    /// Evaluation uses the behavioral-source expression parser, with no node references.
+    fn expr(&self, card: &Card, s: &str) -> SpResult<f64> {
+        let chars: Vec<char> = s.chars().collect();
+        let mut out = String::new();
+        let mut k = 0;
+        while k < chars.len() {
+            let c = chars[k];
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+                let start = k;
+                while k < chars.len() && (chars[k].is_ascii_alphanumeric() || chars[k] == '.' || chars[k] == '_') {
+                    // Include exponent signs, as in `1e-3`
+                    if (chars[k] == 'e') && k + 1 < chars.len() && (chars[k + 1] == '-' || chars[k + 1] == '+') && chars[start].is_ascii_digit() {
+                        k += 1;
+                    }
+                    k += 1;
+                }
+                let word: String = chars[start..k].iter().collect();
+                if let Some(v) = parse_number(&word) {
+                    out.push_str(&format!("({:e})", v));
+                } else if let Some(v) = self.locals.get(&word).or_else(|| self.params.get(&word)) {
+                    out.push_str(&format!("({:e})", v));
+                } else if word == "pow" {
+                    out.push_str(&word);
+                } else {
+                    return Err(card.err(format!("Unknown Parameter: {}", word)));
+                }
+            } else {
+                out.push(c);
+                k += 1;
+            }
+        }
+        let e = match crate::comps::behavioral::parse(&out) {
+            Ok(e) => e,
+            Err(e) => return Err(card.err(format!("Invalid Expression {}: {}", s, e))),
+        };
+        Ok(e.eval(&[]))
+    }
+}
+
+/// Create proto-struct `T` named `name` from SPICE parameters `params`.
+/// Parameters are matched to fields by name, after applying any `renames` from SPICE to proto names.
+fn from_params<T: Default + Serialize + DeserializeOwned>(
+    card: &Card,
+    name: &str,
+    params: Vec<(String, f64)>,
+    renames: &[(&str, &str)],
+) -> SpResult<T> {
+    let mut fields = match serde_json::to_value(T::default()) {
+        Ok(serde_json::Value::Object(m)) => m,
+        _ => return Err(card.err("Internal Error: Invalid Parameter Struct")),
+    };
+    for (key, val) in params.into_iter() {
+        let key = renames
+            .iter()
+            .find(|(from, _)| *from == key)
+            .map_or(key.clone(), |(_, to)| to.to_string());
+        match fields.get_mut(&key) {
+            Some(f) if key != "name" && key != "model" && key != "mos_type" => *f = val.into(),
+            _ => return Err(card.err(format!("Unknown Parameter: {}", key))),
+        }
+    }
+    fields.insert("name".into(), name.into());
+    match serde_json::from_value(serde_json::Value::Object(fields)) {
+        Ok(t) => Ok(t),
+        Err(e) => Err(card.err(format!("Invalid Parameters: {}", e))),
+    }
+}
+
+/// Node connections of element `comp`
+fn nodes(comp: &CompProto) -> Vec<String> {
+    match comp {
+        CompProto::R(x) => vec![x.p.clone(), x.n.clone()],
+        CompProto::C(x) => vec![x.p.clone(), x.n.clone()],
+        CompProto::L(x) => vec![x.p.clone(), x.n.clone()],
+        CompProto::V(x) => vec![x.p.clone(), x.n.clone()],
+        CompProto::I(x) => vec![x.p.clone(), x.n.clone()],
+        CompProto::D(x) => vec![x.p.clone(), x.n.clone()],
+        CompProto::M(x) => match &x.ports {
+            Some(p) => vec![p.d.clone(), p.g.clone(), p.s.clone(), p.b.clone()],
+            None => vec![],
+        },
+        CompProto::X(x) => {
+            let mut conns: Vec<(&String, &String)> = x.ports.iter().collect();
+            conns.sort();
+            conns.into_iter().map(|(_, v)| v.clone()).collect()
+        }
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert::assert;
+    use crate::spresult::TestResult;
+
+    #[test]
+    fn test_parse_number() -> TestResult {
+        assert(parse_number("1.5")).eq(Some(1.5))?;
+        assert(parse_number("10k")).eq(Some(10e3))?;
+        assert(parse_number("2.2uF").unwrap()).isclose(2.2e-6, 1e-18)?;
+        assert(parse_number("1MEG")).eq(Some(1e6))?;
+        assert(parse_number("3m").unwrap()).isclose(3e-3, 1e-15)?;
+        assert(parse_number("1e-3")).eq(Some(1e-3))?;
+        assert(parse_number("5v")).eq(Some(5.0))?;
+        assert(parse_number("vdd")).eq(None)?;
+        assert(parse_number("1.2.3")).eq(None)?;
+        // Non-finite values
+        for s in ["nan", "inf", "-inf", "infinity", "1e400", "1e308k"].iter() {
+            assert(parse_number(s)).eq(None)?;
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_parse_netlist() -> TestResult {
+        let deck = "divider deck
+            .param rval=2k half={rval/2}
+            * A comment
+            .subckt div top bot mid
+            r1 top mid {half}
+            r2 mid bot
+            + 1k ; trailing comment
+            .ends
+            v1 vdd 0 dc 1 ac 1
+            x1 vdd 0 out div
+            .op
+            .end
+            r3 these lines are ignored";
+        let c = parse(deck)?;
+        assert(c.name).eq(s("divider deck"))?;
+        assert(c.signals).eq(vec![s("vdd"), s("out")])?;
+        assert(c.comps.len()).eq(2)?;
+        match &c.comps[1].comp {
+            Some(CompProto::X(x)) => {
+                assert(x.module.clone()).eq(s("div"))?;
+                assert(x.ports["top"].clone()).eq(s("vdd"))?;
+                assert(x.ports["bot"].clone()).eq(s(""))?;
+                assert(x.ports["mid"].clone()).eq(s("out"))?;
+            }
+            _ => panic!("Expected a subcircuit instance"),
+        }
+        let m = match &c.defs[0].defines {
+            Some(Defines::Module(m)) => m.clone(),
+            _ => panic!("Expected a module"),
+        };
+        assert(m.ports).eq(vec![s("top"), s("bot"), s("mid")])?;
+        assert(m.signals.len()).eq(0)?;
+        match &m.comps[0].comp {
+            Some(CompProto::R(r)) => assert(r.g).isclose(1e-3, 1e-12)?,
+            _ => panic!("Expected a resistor"),
+        }
+        // And elaborates, through its module definition
+        let op = crate::dcop(crate::circuit::Ckt::from_spice(deck)?, None)?;
+        assert(op.get("out")?).isclose(0.5, 1e-9)?;
+
+        // Errors name their line
+        let e = parse("title\nr1 a 0 1k\nq1 a b c npn").unwrap_err();
+        assert(e.desc).eq(s("SPICE Parse Error, line 3: Unsupported Element: q1"))?;
+        let e = parse("title\n.model m1 nmos level=1 bogus=1").unwrap_err();
+        assert(e.desc).eq(s("SPICE Parse Error, line 2: Unknown Parameter: bogus"))?;
+        Ok(())
+    }
+    #[test]
+    fn test_parse_subckt_params() -> TestResult {
+        // Dividers whose lower resistance is a subcircuit parameter, with default `rbot = 1k`
+        let deck = "param deck
+            .param rtop=1k
+            .subckt div top mid params: rbot={rtop}
+            r1 top mid {rtop}
+            r2 mid 0 {rbot}
+            .ends
+            v1 vdd 0 dc 1
+            x1 vdd out1 div
+            x2 vdd out2 div rbot=3k
+            x3 vdd out3 div rbot={3*rtop} m=2
+            x4 vdd out4 div params: rbot=3k";
+        let c = parse(deck)?;
+        // Overrides generate a module with their values, shared between instances with the same values
+        let modules: Vec<proto::Module> = c
+            .defs
+            .iter()
+            .filter_map(|d| match &d.defines {
+                Some(Defines::Module(m)) => Some(m.clone()),
+                _ => None,
+            })
+            .collect();
+        assert(modules.len()).eq(2)?;
+        match &c.comps[3].comp {
+            Some(CompProto::X(x)) => {
+                assert(x.module.clone()).eq(s("div(rbot=3e3)"))?;
+                assert(x.params["m"]).eq(2.0)?;
+            }
+            _ => panic!("Expected a subcircuit instance"),
+        }
+        let op = crate::dcop(crate::circuit::Ckt::from_spice(deck)?, None)?;
+        assert(op.get("out1")?).isclose(0.5, 1e-9)?;
+        assert(op.get("out2")?).isclose(0.75, 1e-9)?;
+        assert(op.get("out3")?).isclose(0.75, 1e-9)?;
+        assert(op.get("out4")?).isclose(0.75, 1e-9)?;
+
+        // Overrides must be declared by the subcircuit
+        let e = parse("title\n.subckt div a b\nr1 a b 1k\n.ends\nx1 a 0 div rbot=1").unwrap_err();
+        assert(e.desc).eq(s("SPICE Parse Error, line 5: Unknown Parameter rbot of Subcircuit div"))?;
+        Ok(())
+    }
+    #[test]
+    fn test_parse_malformed() -> TestResult {
+        // Malformed cards produce errors, rather than panics
+        let err = |deck: &str| parse(deck).unwrap_err().desc;
+        assert(err("title\nv1 a")).eq(s("SPICE Parse Error, line 2: Missing Node on v1"))?;
+        assert(err("title\ni1")).eq(s("SPICE Parse Error, line 2: Missing Node on i1"))?;
+        assert(err("title\nr1 a 0 1k\n()")).eq(s("SPICE Parse Error, line 3: Empty Card"))?;
+        assert(err("title\n.subckt foo = 1\n.ends")).eq(s("SPICE Parse Error, line 2: Invalid Subcircuit Parameters"))?;
+        assert(err("title\n.subckt")).eq(s("SPICE Parse Error, line 2: Missing Subcircuit Name"))?;
+        assert(err("title\nx1 = 1")).eq(s("SPICE Parse Error, line 2: Missing Subcircuit for x1"))?;
+        Ok(())
+    }
+    fn s(x: &str) -> String {
+        x.to_string()
+    }
+}
@@ -0,0 +1,125 @@
+//!
+//! # Spice21 Noise Metrics
+//!
+//! Post-processing of noise spectra into the figures designers typically report:
+//! input-referred noise, band-integrated noise, and noise figure.
+//!
+//! Noise densities are in V/sqrt(Hz), and gains are the complex-valued
+//! AC transfer from the chosen input to the noise output, at the same frequencies.
+//!
+use num::Complex;
+
+use crate::comps::consts::KB;
+use crate::{sperror, SpResult};
+
+/// Input-referred noise density.
+/// Divides each output noise density in `onoise` by the magnitude of the corresponding AC `gain`.
+pub fn input_referred(onoise: &[f64], gain: &[Complex<f64>]) -> SpResult<Vec<f64>> {
+    if onoise.len() != gain.len() {
+        return Err(sperror("Mismatched noise and gain lengths"));
+    }
+    let mut rv = vec![];
+    for (n, g) in onoise.iter().zip(gain.iter()) {
+        if g.norm() == 0.0 {
+            return Err(sperror("Cannot input-refer noise with zero gain"));
+        }
+        rv.push(n / g.norm());
+    }
+    Ok(rv)
+}
+
+/// Integrated (RMS) noise over the band `[fstart, fstop]`.
+/// Integrates the power spectral density, i.e. the squared densities in `density`,
+/// via the trapezoidal rule, and returns its square root.
+pub fn integrate(freq: &[f64], density: &[f64], fstart: f64, fstop: f64) -> SpResult<f64> {
+    if freq.len() != density.len() {
+        return Err(sperror("Mismatched frequency and noise lengths"));
+    }
+    if fstop < fstart {
+        return Err(sperror("Invalid noise integration band"));
+    }
+    let mut total = 0.0;
+    for k in 1..freq.len() {
+        // Clip each interval to the band
+        let (f0, f1) = (freq[k - 1].max(fstart), freq[k].min(fstop));
+        if f1 <= f0 {
+            continue;
+        }
+        // Linearly interpolate the PSD at the clipped interval edges
+        let psd = |f: f64| {
+            let frac = (f - freq[k - 1]) / (freq[k] - freq[k - 1]);
+            let (p0, p1) = (density[k - 1].powi(2), density[k].powi(2));
+            p0 + frac * (p1 - p0)
+        };
+        total += (f1 - f0) * (psd(f0) + psd(f1)) / 2.0;
+    }
+    Ok(total.sqrt())
+}
+
+/// Noise Figure, in dB, of a two-port driven from source resistance `rs` at temperature `temp` (Kelvin).
+/// Output noise densities `onoise` must include the contribution of the source resistance itself.
+/// The noise factor is the ratio of total input-referred noise power to that of the source resistance.
+pub fn noise_figure(onoise: &[f64], gain: &[Complex<f64>], rs: f64, temp: f64) -> SpResult<Vec<f64>> {
+    if rs <= 0.0 {
+        return Err(sperror("Noise figure requires a positive source resistance"));
+    }
+    let source_psd = 4.0 * KB * temp * rs;
+    let inoise = input_referred(onoise, gain)?;
+    Ok(inoise.iter().map(|n| 10.0 * (n.powi(2) / source_psd).log10()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{ac, AcOptions};
+    use crate::assert::assert;
+    use crate::circuit::{Ckt, Comp, NodeRef::Gnd, Vi};
+    use crate::spresult::TestResult;
+
+    /// Input-referred noise and noise figure of a resistively-loaded "amplifier",
+    /// here a (gain < 1) divider of source resistance `r1` and load `r2`.
+    #[test]
+    fn test_divider_noise() -> TestResult {
+        let (r1, r2, temp) = (1e3, 3e3, 300.15);
+        let ckt = Ckt::from_comps(vec![
+            Comp::V(Vi {
+                name: "vi".into(),
+                vdc: 0.0,
+                acm: 1.0,
+                p: "inp".into(),
+                n: Gnd,
+            }),
+            Comp::r("r1", 1.0 / r1, "inp", "out"),
+            Comp::r("r2", 1.0 / r2, "out", Gnd),
+        ]);
+        let args = AcOptions {
+            fstart: 1,
+            fstop: 1_000_000,
+            npts: 6,
+        };
+        let soln = ac(ckt, None, Some(args))?;
+        let gain = soln.map.get("out").unwrap();
+        assert(gain[0].norm()).isclose(r2 / (r1 + r2), 1e-9)?;
+
+        // Hand-calculated output noise: the thermal noise of the parallel combination
+        let rpar = r1 * r2 / (r1 + r2);
+        let onoise = vec![(4.0 * KB * temp * rpar).sqrt(); gain.len()];
+
+        // Input-referred noise, by hand: sqrt(4kT * r1 * (r1 + r2) / r2)
+        let inoise = input_referred(&onoise, gain)?;
+        let expected = (4.0 * KB * temp * r1 * (r1 + r2) / r2).sqrt();
+        for n in inoise.iter() {
+            assert(*n).isclose(expected, 1e-6 * expected)?;
+        }
+        // Integrated over a 1 kHz - 101 kHz band of this white spectrum
+        let total = integrate(&soln.freq, &inoise, 1e3, 101e3)?;
+        assert(total).isclose(expected * 1e5f64.sqrt(), 1e-6 * total)?;
+
+        // Noise Figure of a resistive attenuator: 10 * log10(1 + r1 / r2)
+        let nf = noise_figure(&onoise, gain, r1, temp)?;
+        for f in nf.iter() {
+            assert(*f).isclose(10.0 * (1.0 + r1 / r2).log10(), 1e-6)?;
+        }
+        Ok(())
+    }
+}
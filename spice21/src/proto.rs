@@ -192,6 +192,7 @@ mod tests {
                         p: s("ac"),
                         n: s("bc"),
                         c: 1e-12,
+                        esr: 0.0,
                     })),
                 },
                 Instance {
@@ -200,6 +201,7 @@ mod tests {
                         p: s("a"),
                         n: s("b"),
                         g: 1e-3,
+                        ..Default::default()
                     })),
                 },
                 Instance {
@@ -267,6 +269,7 @@ mod tests {
                                     p: s("ac"),
                                     n: s("bc"),
                                     c: 1e-12,
+                                    esr: 0.0,
                                 })),
                             },
                             Instance {
@@ -275,6 +278,7 @@ mod tests {
                                     p: s("a"),
                                     n: s("b"),
                                     g: 1e-3,
+                                    ..Default::default()
                                 })),
                             },
                             Instance {
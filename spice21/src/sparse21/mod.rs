@@ -268,6 +268,10 @@ impl<T: SpNum> Matrix<T> {
             None => self._add_element(row, col, T::zero(), false),
         };
     }
+    /// Number of (structurally) non-zero Elements, including any fill-ins
+    pub fn nnz(&self) -> usize {
+        self.elements.len()
+    }
     /// Reset all Elements to zero value.
     pub fn reset(&mut self) {
         for e in self.elements.iter_mut() {
@@ -325,6 +329,54 @@ impl<T: SpNum> Matrix<T> {
         // println!("RES: {:?}", res);
         return Ok(res);
     }
+    /// Magnitude of each row of the residual `rhs - Ax`, i.e. the sum of the absolute values of its terms.
+    /// Primarily useful as a scale for relative residual tolerances.
+    pub fn res_scale(&self, x: &[T], rhs: &[T]) -> SpResult<Vec<f64>> {
+        if x.len() != self.num_cols() {
+            return Err(sperror("Invalid Dimensions"));
+        }
+        // Sum the term-magnitudes of each (internal) row
+        let mut ri = vec![0.0; self.num_rows()];
+        for (row, r) in ri.iter_mut().enumerate() {
+            let mut ep = self.hdr(ROWS, row);
+            while let Some(ei) = ep {
+                let col = match self.axes[COLS].mapping.as_ref() {
+                    Some(m) => m.i2e[self[ei].col],
+                    None => self[ei].col,
+                };
+                *r += (self[ei].val * x[col]).absv();
+                ep = self[ei].next_in_row;
+            }
+        }
+        // Add in the RHS, unwinding row-swaps along the way
+        let mut scale = vec![0.0; ri.len()];
+        for k in 0..ri.len() {
+            let row = match self.axes[ROWS].mapping.as_ref() {
+                Some(m) => m.e2i[k],
+                None => k,
+            };
+            scale[k] = rhs[k].absv() + ri[row];
+        }
+        Ok(scale)
+    }
+    /// Sums of the absolute values of each row and each column, in external (un-swapped) order.
+    /// All-zero rows or columns indicate a (structurally) singular matrix.
+    pub fn norms(&self) -> (Vec<f64>, Vec<f64>) {
+        let (mut rows, mut cols) = (vec![0.0; self.num_rows()], vec![0.0; self.num_cols()]);
+        for e in self.elements.iter() {
+            let row = match self.axes[ROWS].mapping.as_ref() {
+                Some(m) => m.i2e[e.row],
+                None => e.row,
+            };
+            let col = match self.axes[COLS].mapping.as_ref() {
+                Some(m) => m.i2e[e.col],
+                None => e.col,
+            };
+            rows[row] += e.val.absv();
+            cols[col] += e.val.absv();
+        }
+        (rows, cols)
+    }
     fn insert(&mut self, e: &mut Element<T>) {
         let mut expanded = false;
         if e.row + 1 > self.num_rows() {
@@ -930,6 +982,17 @@ impl<T: SpNum> Matrix<T> {
         if self.state != MatrixState::FACTORED {
             self.lu_factorize()?;
         }
+        self.substitute(&rhs)
+    }
+    /// LU-factorize, returning a `Factorization` for solving any number of right-hand sides
+    pub fn factorize(mut self) -> SpResult<Factorization<T>> {
+        if self.state != MatrixState::FACTORED {
+            self.lu_factorize()?;
+        }
+        Ok(Factorization { mat: self })
+    }
+    /// Forward and backward substitution of `rhs`, through our existing LU factors
+    fn substitute(&self, rhs: &[T]) -> SpResult<Vec<T>> {
         assert(self.state).eq(MatrixState::FACTORED)?;
 
         // Unwind any row-swaps
@@ -997,6 +1060,10 @@ impl<T: SpNum> Matrix<T> {
         }
         return res;
     }
+    /// Create a (row, col, val) triplet for each Element, in creation order
+    pub fn entries(&self) -> Vec<Entry<T>> {
+        self.elements.iter().map(|e| (e.row, e.col, e.val)).collect()
+    }
     fn hdr(&self, ax: Axis, loc: usize) -> Option<Eindex> {
         self.axes[ax].hdrs[loc]
     }
@@ -1011,6 +1078,27 @@ impl<T: SpNum> Matrix<T> {
     }
 }
 
+/// # LU Factorization
+///
+/// Factored `Matrix` `A`, created by `Matrix::factorize`.
+/// Solves `Ax=b` for any number of right-hand sides `b`, without re-factoring.
+pub struct Factorization<T: SpNum> {
+    mat: Matrix<T>,
+}
+impl<T: SpNum> Factorization<T> {
+    /// Solve `Ax=b`, where `b` is argument `rhs`
+    pub fn solve(&self, rhs: &[T]) -> SpResult<Vec<T>> {
+        if rhs.len() != self.size() {
+            return Err(sperror("Invalid Dimensions"));
+        }
+        self.mat.substitute(rhs)
+    }
+    /// Number of rows (and columns)
+    pub fn size(&self) -> usize {
+        self.mat.num_rows()
+    }
+}
+
 impl<T: SpNum + One> Matrix<T> {
     /// Create an n*n identity `Matrix`
     pub fn identity(n: usize) -> Matrix<T> {
@@ -1470,6 +1558,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_factorization() -> TestResult {
+        let entries = vec![
+            (0, 0, 1.0),
+            (0, 1, 1.0),
+            (0, 2, 1.0),
+            (1, 1, 2.0),
+            (1, 2, 5.0),
+            (2, 0, 2.0),
+            (2, 1, 5.0),
+            (2, 2, -1.0),
+        ];
+        let lu = Matrix::from_entries(entries.clone()).factorize()?;
+        assert(lu.size()).eq(3)?;
+        // Each right-hand side matches an independent factor-and-solve
+        for rhs in [vec![6.0, -4.0, 27.0], vec![1.0, 0.0, -2.0]].iter() {
+            let soln = lu.solve(rhs)?;
+            let correct = Matrix::from_entries(entries.clone()).solve(rhs.clone())?;
+            for k in 0..soln.len() {
+                assert!(isclose(soln[k], correct[k]));
+            }
+        }
+        assert(lu.solve(&[1.0, 2.0]).is_err()).eq(true)?;
+        Ok(())
+    }
+
     #[test]
     fn test_solve_id3() -> TestResult {
         let mut m = Matrix::<f64>::identity(3);
@@ -1,17 +1,26 @@
+//!
+//! # Spice21 Numeric Types
+//!
+//! Spice21's matrix and solver internals are generic over the `SpNum` trait.
+//! Built-in implementations cover `f64` (DC and transient) and `Complex<f64>` (AC).
+//!
 use num::traits::NumAssignOps;
 use num::{Complex, Num, One, Zero};
 use std::fmt;
 
-// This long list of traits describes our required behavior for numeric types.
-pub trait SpNum:
-    Clone + Copy + NumAssignOps + Zero + Num + Abs + fmt::Display + fmt::Debug
-{
-}
+/// # Spice21 Numeric Trait
+///
+/// This long list of traits describes our required behavior for numeric types.
+/// Any type meeting them is an `SpNum`, via the blanket implementation below. In short:
+///
+/// * Arithmetic (`Num`, `NumAssignOps`) must form a field, as used by LU factorization.
+/// * `zero()` is the value of absent matrix entries, and `one()` that of identity-matrix entries.
+/// * `Abs::absv` returns a non-negative magnitude. It drives pivot selection and convergence checks,
+///   so should be zero only for `zero()`, and should grow with the value's size.
+///
+pub trait SpNum: Clone + Copy + NumAssignOps + Zero + Num + Abs + fmt::Display + fmt::Debug {}
 
-impl<T> SpNum for T where
-    T: Clone + Copy + NumAssignOps + Zero + One + Num + Abs + fmt::Display + fmt::Debug
-{
-}
+impl<T> SpNum for T where T: Clone + Copy + NumAssignOps + Zero + One + Num + Abs + fmt::Display + fmt::Debug {}
 
 /// Absolute Value Trait for numeric types
 pub trait Abs {
@@ -29,3 +38,207 @@ impl Abs for Complex<f64> {
         self.norm()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert::assert;
+    use crate::sparse21::Matrix;
+    use crate::spresult::{SpResult, TestResult};
+    use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
+
+    /// Dual Number, carrying a value and its derivative with respect to a single parameter
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Dual {
+        val: f64,
+        der: f64,
+    }
+    impl Dual {
+        fn new(val: f64, der: f64) -> Self {
+            Self { val, der }
+        }
+        fn c(val: f64) -> Self {
+            Self::new(val, 0.0)
+        }
+    }
+    impl Add for Dual {
+        type Output = Self;
+        fn add(self, o: Self) -> Self {
+            Self::new(self.val + o.val, self.der + o.der)
+        }
+    }
+    impl Sub for Dual {
+        type Output = Self;
+        fn sub(self, o: Self) -> Self {
+            Self::new(self.val - o.val, self.der - o.der)
+        }
+    }
+    impl Mul for Dual {
+        type Output = Self;
+        fn mul(self, o: Self) -> Self {
+            Self::new(self.val * o.val, self.der * o.val + self.val * o.der)
+        }
+    }
+    impl Div for Dual {
+        type Output = Self;
+        fn div(self, o: Self) -> Self {
+            Self::new(self.val / o.val, (self.der * o.val - self.val * o.der) / (o.val * o.val))
+        }
+    }
+    impl Rem for Dual {
+        type Output = Self;
+        fn rem(self, o: Self) -> Self {
+            let q = (self.val / o.val).trunc();
+            Self::new(self.val % o.val, self.der - q * o.der)
+        }
+    }
+    impl AddAssign for Dual {
+        fn add_assign(&mut self, o: Self) {
+            *self = *self + o;
+        }
+    }
+    impl SubAssign for Dual {
+        fn sub_assign(&mut self, o: Self) {
+            *self = *self - o;
+        }
+    }
+    impl MulAssign for Dual {
+        fn mul_assign(&mut self, o: Self) {
+            *self = *self * o;
+        }
+    }
+    impl DivAssign for Dual {
+        fn div_assign(&mut self, o: Self) {
+            *self = *self / o;
+        }
+    }
+    impl RemAssign for Dual {
+        fn rem_assign(&mut self, o: Self) {
+            *self = *self % o;
+        }
+    }
+    impl Zero for Dual {
+        fn zero() -> Self {
+            Self::c(0.0)
+        }
+        fn is_zero(&self) -> bool {
+            self.val == 0.0 && self.der == 0.0
+        }
+    }
+    impl One for Dual {
+        fn one() -> Self {
+            Self::c(1.0)
+        }
+    }
+    impl Num for Dual {
+        type FromStrRadixErr = <f64 as Num>::FromStrRadixErr;
+        fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+            Ok(Self::c(f64::from_str_radix(s, radix)?))
+        }
+    }
+    impl Abs for Dual {
+        fn absv(&self) -> f64 {
+            self.val.abs()
+        }
+    }
+    impl fmt::Display for Dual {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}+{}e", self.val, self.der)
+        }
+    }
+
+    /// Newtype wrapper over `f64`, delegating all arithmetic
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Wrapped(f64);
+    macro_rules! wrapped_ops {
+        ( $( ($tr:ident, $f:ident, $atr:ident, $af:ident, $op:tt) ),* ) => {
+            $(
+                impl $tr for Wrapped {
+                    type Output = Self;
+                    fn $f(self, o: Self) -> Self {
+                        Wrapped(self.0 $op o.0)
+                    }
+                }
+                impl $atr for Wrapped {
+                    fn $af(&mut self, o: Self) {
+                        *self = *self $op o;
+                    }
+                }
+            )*
+        };
+    }
+    wrapped_ops!(
+        (Add, add, AddAssign, add_assign, +),
+        (Sub, sub, SubAssign, sub_assign, -),
+        (Mul, mul, MulAssign, mul_assign, *),
+        (Div, div, DivAssign, div_assign, /),
+        (Rem, rem, RemAssign, rem_assign, %)
+    );
+    impl Zero for Wrapped {
+        fn zero() -> Self {
+            Wrapped(0.0)
+        }
+        fn is_zero(&self) -> bool {
+            self.0 == 0.0
+        }
+    }
+    impl One for Wrapped {
+        fn one() -> Self {
+            Wrapped(1.0)
+        }
+    }
+    impl Num for Wrapped {
+        type FromStrRadixErr = <f64 as Num>::FromStrRadixErr;
+        fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+            Ok(Wrapped(f64::from_str_radix(s, radix)?))
+        }
+    }
+    impl Abs for Wrapped {
+        fn absv(&self) -> f64 {
+            self.0.abs()
+        }
+    }
+    impl fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    /// Voltage-divider MNA matrix: source `vs` driving conductances `g1` and `g2` in series.
+    /// Variables are the source and divider voltages, and the source current.
+    fn divider<T: SpNum>(vs: T, g1: T, g2: T) -> SpResult<Vec<T>> {
+        let one = T::one();
+        let mut m = Matrix::from_entries(vec![
+            (0, 0, g1),
+            (0, 1, T::zero() - g1),
+            (0, 2, one),
+            (1, 0, T::zero() - g1),
+            (1, 1, g1 + g2),
+            (2, 0, one),
+        ]);
+        m.solve(vec![T::zero(), T::zero(), vs])
+    }
+
+    /// Check a newtype wrapper over `f64` is an `SpNum`, and solves like one
+    #[test]
+    fn test_spnum_newtype() -> TestResult {
+        let x = divider(1.0, 1e-3, 3e-3)?;
+        let w = divider(Wrapped(1.0), Wrapped(1e-3), Wrapped(3e-3))?;
+        for (xv, wv) in x.iter().zip(w.iter()) {
+            assert(wv.0).eq(*xv)?;
+        }
+        assert(x[1]).isclose(0.25, 1e-12)?;
+        Ok(())
+    }
+
+    /// Compute the divider's sensitivity to `g2` with a dual-number backend
+    #[test]
+    fn test_spnum_dual_sens() -> TestResult {
+        let (vs, g1, g2) = (1.0, 1e-3, 3e-3);
+        let x = divider(Dual::c(vs), Dual::c(g1), Dual::new(g2, 1.0))?;
+        // Analytically: v = vs * g1 / (g1 + g2), dv/dg2 = -vs * g1 / (g1 + g2)**2
+        assert(x[1].val).isclose(vs * g1 / (g1 + g2), 1e-12)?;
+        assert(x[1].der).isclose(-vs * g1 / (g1 + g2).powi(2), 1e-9)?;
+        Ok(())
+    }
+}
@@ -5,20 +5,32 @@ use std::error::Error;
 use std::fmt;
 
 /// # Spice21 General Error Type 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SpError {
     pub desc: String,
+    /// Newton diagnostics, for failures to converge
+    pub convergence: Option<Box<ConvergenceError>>,
 }
 // Allow SpError in `dyn Error` contexts
 impl Error for SpError {}
 impl SpError {
     /// Spice Error Constructor, from anything String-convertible
     pub(crate) fn new<S: Into<String>>(s: S) -> SpError {
-        SpError { desc: s.into() }
+        SpError {
+            desc: s.into(),
+            convergence: None,
+        }
     }
     /// Create a Box'ed SpError 
     pub(crate) fn boxed<S: Into<String>>(s: S) -> Box<SpError> {
-        Box::new(SpError { desc: s.into() })
+        Box::new(SpError::new(s))
+    }
+    /// Convergence-failure SpError, described by `c`
+    pub(crate) fn convergence(c: ConvergenceError) -> SpError {
+        SpError {
+            desc: c.to_string(),
+            convergence: Some(Box::new(c)),
+        }
     }
 }
 pub(crate) fn sperror<S: Into<String>>(s: S) -> SpError {
@@ -31,6 +43,35 @@ impl fmt::Display for SpError {
     }
 }
 
+/// # Newton Convergence Failure Diagnostics
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvergenceError {
+    /// Newton iterations attempted
+    pub iterations: usize,
+    /// Largest absolute residual of the last iteration
+    pub max_residual: f64,
+    /// Name of the worst-converging variable: that with the largest residual,
+    /// or for singular matrices, one with no (non-zero) matrix entries, e.g. a floating node.
+    pub worst_var: String,
+    /// Largest absolute update of the last iteration
+    pub last_dx: f64,
+    /// Whether the failure was a singular matrix
+    pub singular: bool,
+}
+impl fmt::Display for ConvergenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.singular {
+            write!(f, "Singular Matrix after {} iterations, at variable {}", self.iterations, self.worst_var)
+        } else {
+            write!(
+                f,
+                "Convergence Failed after {} iterations: residual {:e} at variable {}, last update {:e}",
+                self.iterations, self.max_residual, self.worst_var, self.last_dx
+            )
+        }
+    }
+}
+
 // SpError Conversions 
 impl From<&str> for SpError {
     fn from(s: &str) -> Self {
@@ -43,6 +84,12 @@ impl From<& str> for Box<SpError> {
     }
 }
 
+impl From<std::io::Error> for SpError {
+    fn from(e: std::io::Error) -> Self {
+        SpError::new(e.to_string())
+    }
+}
+
 /// # Spice21 General Result Type
 pub type SpResult<T> = Result<T, SpError>;
 /// # Spice21 Test Result Type 
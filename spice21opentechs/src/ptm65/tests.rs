@@ -64,6 +64,7 @@ fn test1() -> TestResult {
         tstep: 1e-10,
         tstop: 3e-7,
         ic: vec![(NodeRef::Num(1), 0.0)],
+        ..Default::default()
     };
     let soln = tran(ckt, None, Some(opts))?;
     Ok(())